@@ -0,0 +1,92 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use arrow::{
+    array::{AsArray, Float64Array, Int64Array, RecordBatch, StringArray},
+    datatypes::{DataType, Field, Int64Type, Schema},
+};
+
+/// Build a single query which counts the rows and the nulls of every column.
+pub(crate) fn null_audit_sql(name: &str, schema: &Schema) -> String {
+    let mut exprs = vec!["COUNT(*) AS total".to_string()];
+    exprs.extend(
+        schema
+            .fields()
+            .iter()
+            .map(|f| format!("COUNT(*) - COUNT(\"{0}\") AS \"{0}\"", f.name())),
+    );
+    format!("SELECT {} FROM {}", exprs.join(", "), name)
+}
+
+/// Turn the single row result of `null_audit_sql` into one row per column.
+pub(crate) fn null_audit_batch(schema: &Schema, batches: &[RecordBatch]) -> Result<RecordBatch> {
+    let batch = batches
+        .iter()
+        .find(|batch| batch.num_rows() > 0)
+        .ok_or_else(|| anyhow::anyhow!("No result for null audit"))?;
+    let total = batch.column(0).as_primitive::<Int64Type>().value(0);
+
+    let mut names = Vec::with_capacity(schema.fields().len());
+    let mut counts = Vec::with_capacity(schema.fields().len());
+    let mut pcts = Vec::with_capacity(schema.fields().len());
+    for (i, field) in schema.fields().iter().enumerate() {
+        let nulls = batch.column(i + 1).as_primitive::<Int64Type>().value(0);
+        names.push(field.name().to_string());
+        counts.push(nulls);
+        pcts.push(if total == 0 {
+            0.0
+        } else {
+            nulls as f64 * 100.0 / total as f64
+        });
+    }
+
+    let schema = Schema::new(vec![
+        Field::new("column_name", DataType::Utf8, false),
+        Field::new("null_count", DataType::Int64, false),
+        Field::new("null_pct", DataType::Float64, false),
+    ]);
+    let batch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(StringArray::from(names)),
+            Arc::new(Int64Array::from(counts)),
+            Arc::new(Float64Array::from(pcts)),
+        ],
+    )?;
+    Ok(batch)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::DatafusionBackend;
+    use crate::{Backend, ReplDisplay};
+
+    #[tokio::test]
+    async fn test_count_nulls() -> Result<()> {
+        let backend = DatafusionBackend::new();
+        let schema = Schema::new(vec![
+            Field::new("a", DataType::Int64, true),
+            Field::new("b", DataType::Utf8, true),
+        ]);
+        let batch = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![
+                Arc::new(Int64Array::from(vec![Some(1), None, Some(3), Some(4)])),
+                Arc::new(StringArray::from(vec![None, None, Some("x"), None])),
+            ],
+        )?;
+        backend.register_batch("nulls", batch)?;
+
+        let output = backend.count_nulls("nulls").await?;
+        let output = output.display(backend.settings()).await?;
+        let expected = r#"+-------------+------------+----------+
+| column_name | null_count | null_pct |
++-------------+------------+----------+
+| a           | 1          | 25.0     |
+| b           | 3          | 75.0     |
++-------------+------------+----------+"#;
+        assert_eq!(output, expected);
+        Ok(())
+    }
+}
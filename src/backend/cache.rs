@@ -0,0 +1,129 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use arrow::array::RecordBatch;
+
+/// Cache of describe results and value previews, keyed by table name and a
+/// hash of the options. Holds at most `capacity` entries, evicting the oldest first.
+#[derive(Debug)]
+pub(crate) struct StatsCache {
+    entries: HashMap<(String, u64), (Instant, Vec<RecordBatch>)>,
+    capacity: usize,
+}
+
+impl StatsCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: HashMap::new(),
+            capacity,
+        }
+    }
+
+    pub fn get(
+        &self,
+        table: &str,
+        hash: u64,
+        ttl: Duration,
+        now: Instant,
+    ) -> Option<Vec<RecordBatch>> {
+        self.entries
+            .get(&(table.to_string(), hash))
+            .filter(|(created, _)| now.saturating_duration_since(*created) < ttl)
+            .map(|(_, batches)| batches.clone())
+    }
+
+    pub fn insert(&mut self, table: &str, hash: u64, batches: Vec<RecordBatch>, now: Instant) {
+        let key = (table.to_string(), hash);
+        if !self.entries.contains_key(&key) && self.entries.len() >= self.capacity {
+            let oldest = self
+                .entries
+                .iter()
+                .min_by_key(|(_, (created, _))| *created)
+                .map(|(oldest, _)| oldest.clone());
+            match oldest {
+                Some(oldest) => {
+                    self.entries.remove(&oldest);
+                }
+                None => return,
+            }
+        }
+        self.entries.insert(key, (now, batches));
+    }
+
+    pub fn invalidate(&mut self, table: &str) {
+        self.entries.retain(|(name, _), _| name != table);
+    }
+
+    /// Drop every entry, returning the estimated number of bytes released.
+    pub fn clear(&mut self) -> usize {
+        let size = self
+            .entries
+            .values()
+            .flat_map(|(_, batches)| batches)
+            .map(|batch| batch.get_array_memory_size())
+            .sum();
+        self.entries.clear();
+        size
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TTL: Duration = Duration::from_secs(60);
+
+    #[test]
+    fn test_cache_hit_and_expiry() {
+        let mut cache = StatsCache::new(8);
+        let now = Instant::now();
+        cache.insert("t", 1, vec![], now);
+
+        assert!(cache.get("t", 1, TTL, now).is_some());
+        assert!(
+            cache
+                .get("t", 1, TTL, now + Duration::from_secs(59))
+                .is_some()
+        );
+        assert!(
+            cache
+                .get("t", 1, TTL, now + Duration::from_secs(60))
+                .is_none()
+        );
+        assert!(cache.get("t", 2, TTL, now).is_none());
+    }
+
+    #[test]
+    fn test_cache_invalidate() {
+        let mut cache = StatsCache::new(8);
+        let now = Instant::now();
+        cache.insert("t", 1, vec![], now);
+        cache.insert("t", 2, vec![], now);
+        cache.insert("u", 1, vec![], now);
+
+        cache.invalidate("t");
+        assert!(cache.get("t", 1, TTL, now).is_none());
+        assert!(cache.get("t", 2, TTL, now).is_none());
+        assert!(cache.get("u", 1, TTL, now).is_some());
+    }
+
+    #[test]
+    fn test_cache_evicts_oldest() {
+        let mut cache = StatsCache::new(2);
+        let now = Instant::now();
+        cache.insert("t", 1, vec![], now);
+        cache.insert("t", 2, vec![], now + Duration::from_secs(1));
+        cache.insert("t", 3, vec![], now + Duration::from_secs(2));
+
+        let now = now + Duration::from_secs(3);
+        assert!(cache.get("t", 1, TTL, now).is_none());
+        assert!(cache.get("t", 2, TTL, now).is_some());
+        assert!(cache.get("t", 3, TTL, now).is_some());
+
+        let mut cache = StatsCache::new(0);
+        cache.insert("t", 1, vec![], now);
+        assert!(cache.get("t", 1, TTL, now).is_none());
+    }
+}
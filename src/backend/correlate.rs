@@ -0,0 +1,136 @@
+use std::{cmp::Ordering, sync::Arc};
+
+use anyhow::Result;
+use arrow::{
+    array::{Array, ArrayRef, AsArray, Float64Array, RecordBatch, StringArray},
+    datatypes::{DataType, Field, Float64Type, Schema},
+};
+
+use crate::CorrelationMethod;
+
+/// Cast the columns to DOUBLE, or for Spearman replace them by their ranks.
+fn value_exprs(columns: &[String], method: CorrelationMethod) -> Vec<String> {
+    columns
+        .iter()
+        .map(|col| match method {
+            CorrelationMethod::Pearson => format!("CAST(\"{0}\" AS DOUBLE) AS \"{0}\"", col),
+            CorrelationMethod::Spearman => format!(
+                "CASE WHEN \"{0}\" IS NULL THEN NULL ELSE CAST(RANK() OVER (ORDER BY \"{0}\") AS DOUBLE) END AS \"{0}\"",
+                col
+            ),
+        })
+        .collect()
+}
+
+/// Build a single query which correlates every pair of the columns. For
+/// Spearman the values are replaced by their ranks first.
+pub(crate) fn correlation_sql(name: &str, columns: &[String], method: CorrelationMethod) -> String {
+    let values = value_exprs(columns, method);
+    let mut exprs = Vec::with_capacity(columns.len() * columns.len());
+    for a in columns {
+        for b in columns {
+            exprs.push(format!("corr(\"{}\", \"{}\")", a, b));
+        }
+    }
+    format!(
+        "SELECT {} FROM (SELECT {} FROM {})",
+        exprs.join(", "),
+        values.join(", "),
+        name
+    )
+}
+
+/// Build a single query which correlates every feature with the target.
+pub(crate) fn target_correlation_sql(
+    name: &str,
+    target: &str,
+    features: &[String],
+    method: CorrelationMethod,
+) -> String {
+    let mut columns = features.to_vec();
+    columns.push(target.to_string());
+    let exprs = features
+        .iter()
+        .map(|feature| format!("corr(\"{}\", \"{}\")", feature, target))
+        .collect::<Vec<_>>();
+    format!(
+        "SELECT {} FROM (SELECT {} FROM {})",
+        exprs.join(", "),
+        value_exprs(&columns, method).join(", "),
+        name
+    )
+}
+
+/// Turn the single row result of `correlation_sql` into a matrix, whose first
+/// column is labeled with the method.
+pub(crate) fn correlation_batch(
+    columns: &[String],
+    method: CorrelationMethod,
+    batches: &[RecordBatch],
+) -> Result<RecordBatch> {
+    let batch = batches
+        .iter()
+        .find(|batch| batch.num_rows() > 0)
+        .ok_or_else(|| anyhow::anyhow!("No result for correlation"))?;
+
+    let mut fields = vec![Field::new(method.as_str(), DataType::Utf8, false)];
+    let mut arrays: Vec<ArrayRef> = vec![Arc::new(StringArray::from_iter_values(columns))];
+    for (j, col) in columns.iter().enumerate() {
+        let values = (0..columns.len())
+            .map(|i| {
+                let value = batch.column(i * columns.len() + j);
+                let value = arrow::compute::cast(value, &DataType::Float64)?;
+                let value = value.as_primitive::<Float64Type>();
+                Ok((!value.is_null(0)).then_some(value.value(0)))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        fields.push(Field::new(col, DataType::Float64, true));
+        arrays.push(Arc::new(Float64Array::from(values)));
+    }
+    Ok(RecordBatch::try_new(Arc::new(Schema::new(fields)), arrays)?)
+}
+
+/// Turn the single row result of `target_correlation_sql` into one row per
+/// feature, strongest correlation first and undefined ones last.
+pub(crate) fn target_correlation_batch(
+    features: &[String],
+    batches: &[RecordBatch],
+) -> Result<RecordBatch> {
+    let batch = batches
+        .iter()
+        .find(|batch| batch.num_rows() > 0)
+        .ok_or_else(|| anyhow::anyhow!("No result for correlation"))?;
+
+    let mut rows = features
+        .iter()
+        .enumerate()
+        .map(|(i, feature)| {
+            let value = arrow::compute::cast(batch.column(i), &DataType::Float64)?;
+            let value = value.as_primitive::<Float64Type>();
+            let value = (!value.is_null(0)).then_some(value.value(0));
+            Ok((feature, value.filter(|v| !v.is_nan())))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    rows.sort_by(|(_, a), (_, b)| {
+        let (a, b) = (a.map(f64::abs), b.map(f64::abs));
+        b.partial_cmp(&a).unwrap_or(Ordering::Equal)
+    });
+
+    let schema = Schema::new(vec![
+        Field::new("feature", DataType::Utf8, false),
+        Field::new("correlation", DataType::Float64, true),
+        Field::new("abs_correlation", DataType::Float64, true),
+    ]);
+    Ok(RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(StringArray::from_iter_values(
+                rows.iter().map(|(feature, _)| feature),
+            )),
+            Arc::new(Float64Array::from_iter(rows.iter().map(|(_, v)| *v))),
+            Arc::new(Float64Array::from_iter(
+                rows.iter().map(|(_, v)| v.map(f64::abs)),
+            )),
+        ],
+    )?)
+}
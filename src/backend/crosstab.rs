@@ -0,0 +1,29 @@
+/// Query the distinct non-null values of a column as strings, sorted.
+pub(crate) fn crosstab_values_sql(name: &str, column: &str) -> String {
+    format!(
+        "SELECT DISTINCT CAST(\"{0}\" AS VARCHAR) AS value FROM {1} WHERE \"{0}\" IS NOT NULL ORDER BY value",
+        column, name
+    )
+}
+
+/// Build a query counting the rows of every pair of `row_col` and
+/// `col_col` values, with one column per value of `col_col`.
+pub(crate) fn crosstab_sql(name: &str, row_col: &str, col_col: &str, values: &[String]) -> String {
+    let counts = values
+        .iter()
+        .map(|value| {
+            format!(
+                "SUM(CASE WHEN CAST(\"{}\" AS VARCHAR) = '{}' THEN 1 ELSE 0 END) AS \"{}\"",
+                col_col,
+                value.replace('\'', "''"),
+                value.replace('"', "\"\"")
+            )
+        })
+        .collect::<Vec<_>>();
+    format!(
+        "SELECT \"{0}\", {1} FROM {2} GROUP BY \"{0}\" ORDER BY \"{0}\"",
+        row_col,
+        counts.join(", "),
+        name
+    )
+}
@@ -1,7 +1,11 @@
 use std::sync::Arc;
 
-use arrow::datatypes::{DataType, Field};
-use datafusion::prelude::{DataFrame, array_length, case, cast, col, is_null, length, lit};
+use arrow::array::RecordBatch;
+use arrow::datatypes::{DataType, Field, Schema};
+use datafusion::prelude::{
+    DataFrame, SessionContext, array_length, case, cast, col, is_null, length, lit,
+};
+use futures::{Stream, StreamExt, TryStreamExt, stream};
 
 use datafusion::functions_aggregate::expr_fn::{
     approx_percentile_cont, avg, count, max, median, min, stddev, sum,
@@ -18,6 +22,9 @@ pub enum DescribeMethod {
     Max,
     Median,
     Percentile(u8),
+    MinLength,
+    MaxLength,
+    MeanLength,
 }
 
 #[allow(dead_code)]
@@ -36,7 +43,10 @@ impl DataFrameDescriber {
             .map(|field| {
                 let dt = field.data_type();
                 let expr = match dt {
-                    dt if dt.is_temporal() => cast(col(field.name()), DataType::Float64),
+                    dt if dt.is_temporal() => cast(
+                        cast(col(field.name()), temporal_int_type(dt)),
+                        DataType::Float64,
+                    ),
                     dt if dt.is_numeric() => col(field.name()),
                     DataType::List(_) | DataType::LargeList(_) => array_length(col(field.name())),
                     _ => length(cast(col(field.name()), DataType::Utf8)),
@@ -50,56 +60,128 @@ impl DataFrameDescriber {
         Ok(Self {
             original: df,
             transformed,
-            methods: vec![
-                DescribeMethod::Total,
-                DescribeMethod::NullTotal,
-                DescribeMethod::Mean,
-                DescribeMethod::Stddev,
-                DescribeMethod::Min,
-                DescribeMethod::Max,
-                DescribeMethod::Median,
-                DescribeMethod::Percentile(50),
-                DescribeMethod::Percentile(75),
-                DescribeMethod::Percentile(90),
-                DescribeMethod::Percentile(95),
-                DescribeMethod::Percentile(99),
-            ],
+            methods: Self::default_methods(),
         })
     }
 
+    pub fn default_methods() -> Vec<DescribeMethod> {
+        vec![
+            DescribeMethod::Total,
+            DescribeMethod::NullTotal,
+            DescribeMethod::Mean,
+            DescribeMethod::Stddev,
+            DescribeMethod::Min,
+            DescribeMethod::Max,
+            DescribeMethod::Median,
+            DescribeMethod::Percentile(50),
+            DescribeMethod::Percentile(75),
+            DescribeMethod::Percentile(90),
+            DescribeMethod::Percentile(95),
+            DescribeMethod::Percentile(99),
+        ]
+    }
+
+    /// The statistics which make sense for strings, described by their length.
+    pub fn string_methods() -> Vec<DescribeMethod> {
+        vec![
+            DescribeMethod::Total,
+            DescribeMethod::NullTotal,
+            DescribeMethod::MinLength,
+            DescribeMethod::MaxLength,
+            DescribeMethod::MeanLength,
+        ]
+    }
+
+    pub fn with_methods(mut self, methods: Vec<DescribeMethod>) -> Self {
+        self.methods = methods;
+        self
+    }
+
     pub async fn describe(&self) -> anyhow::Result<DataFrame> {
         let df = self.do_describe().await?;
         self.cast_down(df)
     }
 
+    /// Compute the statistics one method at a time, so that only a single
+    /// aggregation is running at any point.
+    pub fn describe_stream(&self) -> impl Stream<Item = anyhow::Result<Vec<RecordBatch>>> + '_ {
+        stream::iter(&self.methods).then(move |method| async move {
+            let df = self.describe_method(method)?;
+            let expressions = df
+                .schema()
+                .fields()
+                .iter()
+                .map(|f| match f.name().as_str() {
+                    "describe" => col(f.name()),
+                    name => cast(col(name), DataType::Float64).alias(name),
+                })
+                .collect::<Vec<_>>();
+            let df = self.cast_down(df.select(expressions)?)?;
+            Ok(df.collect().await?)
+        })
+    }
+
+    pub async fn describe_chunked(&self) -> anyhow::Result<DataFrame> {
+        let chunks = self.describe_stream().try_collect::<Vec<_>>().await?;
+        let batches = chunks.into_iter().flatten().collect::<Vec<_>>();
+        // the chunks only differ in the nullability of their columns
+        let schema = match batches.first() {
+            Some(batch) => Arc::new(Schema::new(
+                batch
+                    .schema()
+                    .fields()
+                    .iter()
+                    .map(|f| f.as_ref().clone().with_nullable(true))
+                    .collect::<Vec<_>>(),
+            )),
+            None => anyhow::bail!("No statistics found"),
+        };
+        let batches = batches
+            .into_iter()
+            .map(|batch| RecordBatch::try_new(schema.clone(), batch.columns().to_vec()))
+            .collect::<Result<Vec<_>, _>>()?;
+        let (state, _) = self.original.clone().into_parts();
+        let df = SessionContext::new_with_state(state)
+            .read_batches(batches)?
+            .sort(vec![col("describe").sort(true, false)])?;
+        Ok(df)
+    }
+
     async fn do_describe(&self) -> anyhow::Result<DataFrame> {
-        let df: Option<DataFrame> = self.methods.iter().fold(None, |acc, method| {
-            let df = self.transformed.clone();
-            let stat_df = match method {
-                DescribeMethod::Total => total(df).unwrap(),
-                DescribeMethod::NullTotal => null_total(df).unwrap(),
-                DescribeMethod::Mean => mean(df).unwrap(),
-                DescribeMethod::Stddev => std_div(df).unwrap(),
-                DescribeMethod::Min => minimum(df).unwrap(),
-                DescribeMethod::Max => maximum(df).unwrap(),
-                DescribeMethod::Median => med(df).unwrap(),
-                DescribeMethod::Percentile(percent) => percentile(df, *percent).unwrap(),
-            };
-
-            // add a new column to the beginning of the dataframe
-            let mut select_expr = vec![lit(method.to_string()).alias("describe")];
-            select_expr.extend(stat_df.schema().fields().iter().map(|f| col(f.name())));
-
-            let stat_df = stat_df.select(select_expr).unwrap();
-
-            match acc {
-                Some(acc) => Some(acc.union(stat_df).unwrap()),
-                None => Some(stat_df),
-            }
-        });
+        let mut df: Option<DataFrame> = None;
+        for method in &self.methods {
+            let stat_df = self.describe_method(method)?;
+            df = Some(match df {
+                Some(acc) => acc.union(stat_df)?,
+                None => stat_df,
+            });
+        }
         df.ok_or_else(|| anyhow::anyhow!("No statistics found"))
     }
 
+    fn describe_method(&self, method: &DescribeMethod) -> anyhow::Result<DataFrame> {
+        let df = self.transformed.clone();
+        let stat_df = match method {
+            DescribeMethod::Total => total(df)?,
+            DescribeMethod::NullTotal => null_total(df)?,
+            DescribeMethod::Mean => mean(df)?,
+            DescribeMethod::Stddev => std_div(df)?,
+            DescribeMethod::Min => minimum(df)?,
+            DescribeMethod::Max => maximum(df)?,
+            DescribeMethod::Median => med(df)?,
+            DescribeMethod::Percentile(percent) => percentile(df, *percent)?,
+            DescribeMethod::MinLength => minimum(df)?,
+            DescribeMethod::MaxLength => maximum(df)?,
+            DescribeMethod::MeanLength => mean(df)?,
+        };
+
+        // add a new column to the beginning of the dataframe
+        let mut select_expr = vec![lit(method.to_string()).alias("describe")];
+        select_expr.extend(stat_df.schema().fields().iter().map(|f| col(f.name())));
+
+        Ok(stat_df.select(select_expr)?)
+    }
+
     fn cast_down(&self, df: DataFrame) -> anyhow::Result<DataFrame> {
         let describe = Arc::new(Field::new("describe", DataType::Utf8, false));
         let mut fields = vec![&describe];
@@ -110,7 +192,9 @@ impl DataFrameDescriber {
             .map(|field| {
                 let dt = field.data_type();
                 let expr = match dt {
-                    dt if dt.is_temporal() => cast(col(field.name()), dt.clone()),
+                    dt if dt.is_temporal() => {
+                        cast(cast(col(field.name()), temporal_int_type(dt)), dt.clone())
+                    }
                     DataType::List(_) | DataType::LargeList(_) => {
                         cast(col(field.name()), DataType::Int32)
                     }
@@ -137,10 +221,22 @@ impl std::fmt::Display for DescribeMethod {
             DescribeMethod::Max => write!(f, "max"),
             DescribeMethod::Median => write!(f, "median"),
             DescribeMethod::Percentile(p) => write!(f, "percentile_{}", p),
+            DescribeMethod::MinLength => write!(f, "min_length"),
+            DescribeMethod::MaxLength => write!(f, "max_length"),
+            DescribeMethod::MeanLength => write!(f, "mean_length"),
         }
     }
 }
 
+/// The integer type a temporal type casts to and from, as arrow can't cast
+/// dates and times to floats directly.
+fn temporal_int_type(dt: &DataType) -> DataType {
+    match dt {
+        DataType::Date32 | DataType::Time32(_) => DataType::Int32,
+        _ => DataType::Int64,
+    }
+}
+
 macro_rules! describe_method {
     ($name:ident,$method:ident) => {
         fn $name(df: DataFrame) -> anyhow::Result<DataFrame> {
@@ -197,11 +293,20 @@ fn percentile(df: DataFrame, p: u8) -> anyhow::Result<DataFrame> {
 
 #[cfg(test)]
 mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
     use super::*;
+    use crate::backend::tests::row_cells;
     use anyhow::Ok;
-    use arrow::array::{Float64Array, Int32Array};
-    use arrow::datatypes::{Field, Schema};
+    use arrow::array::{
+        Array, BooleanArray, Date32Array, Float64Array, Int32Array, ListArray, StringArray,
+    };
+    use arrow::datatypes::{Field, Int32Type, Schema};
     use arrow::util::pretty::pretty_format_batches;
+    use datafusion::execution::memory_pool::{
+        GreedyMemoryPool, MemoryPool, MemoryReservation, UnboundedMemoryPool,
+    };
+    use datafusion::execution::runtime_env::RuntimeEnvBuilder;
     use datafusion::prelude::*;
 
     async fn create_test_df(
@@ -300,4 +405,208 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_describe_non_numeric_columns() -> anyhow::Result<()> {
+        let items = ListArray::from_iter_primitive::<Int32Type, _, _>(vec![
+            Some(vec![Some(1)]),
+            Some(vec![Some(1), Some(2)]),
+            Some(vec![Some(1), Some(2), Some(3)]),
+            None,
+        ]);
+        let schema = Schema::new(vec![
+            Field::new("name", DataType::Utf8, true),
+            Field::new("day", DataType::Date32, true),
+            Field::new("flag", DataType::Boolean, true),
+            Field::new("items", items.data_type().clone(), true),
+        ]);
+        let batch = arrow::record_batch::RecordBatch::try_new(
+            Arc::new(schema),
+            vec![
+                Arc::new(StringArray::from(vec![
+                    Some("a"),
+                    Some("bbb"),
+                    Some("ccccc"),
+                    None,
+                ])),
+                // 2024-01-01 to 2024-01-03
+                Arc::new(Date32Array::from(vec![
+                    Some(19723),
+                    Some(19724),
+                    Some(19725),
+                    None,
+                ])),
+                Arc::new(BooleanArray::from(vec![
+                    Some(true),
+                    Some(false),
+                    Some(true),
+                    None,
+                ])),
+                Arc::new(items),
+            ],
+        )?;
+        let df = SessionContext::new().read_batch(batch)?;
+
+        let describer = DataFrameDescriber::try_new(df)?;
+        let result = describer.describe().await?.collect().await?;
+        let data = pretty_format_batches(&result)?.to_string();
+
+        // strings and booleans are described by the length of their text
+        let total = row_cells(&data, "total");
+        assert_eq!(total[1], "3.0");
+        assert_eq!(total[3], "3.0");
+        assert_eq!(row_cells(&data, "null_total")[3], "1.0");
+        assert_eq!(row_cells(&data, "min")[1], "1.0");
+        assert_eq!(row_cells(&data, "max")[1], "5.0");
+        // dates keep their type
+        assert_eq!(row_cells(&data, "min")[2], "2024-01-01");
+        assert_eq!(row_cells(&data, "max")[2], "2024-01-03");
+        // lists are described by their length
+        assert_eq!(total[4], "3");
+        assert_eq!(row_cells(&data, "min")[4], "1");
+        assert_eq!(row_cells(&data, "max")[4], "3");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_describe_chunked_matches_describe() -> anyhow::Result<()> {
+        let df = create_test_df(
+            vec![Some(1), None, Some(3), None, Some(3), Some(4), Some(5)],
+            vec![
+                Some(1.0),
+                Some(2.0),
+                None,
+                Some(2.0),
+                None,
+                Some(4.0),
+                Some(5.0),
+            ],
+        )
+        .await;
+        let describer = DataFrameDescriber::try_new(df)?;
+
+        let expected = describer.describe().await?.collect().await?;
+        let result = describer.describe_chunked().await?.collect().await?;
+        assert_eq!(
+            pretty_format_batches(&expected)?.to_string(),
+            pretty_format_batches(&result)?.to_string()
+        );
+        Ok(())
+    }
+
+    /// A memory pool recording the most memory reserved at any one time.
+    #[derive(Debug, Default)]
+    struct PeakMemoryPool {
+        inner: UnboundedMemoryPool,
+        peak: AtomicUsize,
+    }
+
+    impl PeakMemoryPool {
+        fn peak(&self) -> usize {
+            self.peak.load(Ordering::Relaxed)
+        }
+    }
+
+    impl MemoryPool for PeakMemoryPool {
+        fn grow(&self, reservation: &MemoryReservation, additional: usize) {
+            self.inner.grow(reservation, additional);
+            self.peak
+                .fetch_max(self.inner.reserved(), Ordering::Relaxed);
+        }
+
+        fn shrink(&self, reservation: &MemoryReservation, shrink: usize) {
+            self.inner.shrink(reservation, shrink);
+        }
+
+        fn try_grow(
+            &self,
+            reservation: &MemoryReservation,
+            additional: usize,
+        ) -> datafusion::error::Result<()> {
+            self.inner.try_grow(reservation, additional)?;
+            self.peak
+                .fetch_max(self.inner.reserved(), Ordering::Relaxed);
+            datafusion::error::Result::Ok(())
+        }
+
+        fn reserved(&self) -> usize {
+            self.inner.reserved()
+        }
+    }
+
+    /// A describer over many batches of rows, whose memory is tracked by `pool`.
+    fn large_describer(pool: Arc<dyn MemoryPool>) -> anyhow::Result<DataFrameDescriber> {
+        let runtime = RuntimeEnvBuilder::new()
+            .with_memory_pool(pool)
+            .build_arc()?;
+        // a single partition keeps the peaks the same on every machine, and
+        // sorting the statistics needs no memory set aside for spilling
+        let config = SessionConfig::new()
+            .with_target_partitions(1)
+            .with_sort_spill_reservation_bytes(0);
+        let ctx = SessionContext::new_with_config_rt(config, runtime);
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("int_col", DataType::Int32, true),
+            Field::new("float_col", DataType::Float64, true),
+        ]));
+        let batches = (0..64)
+            .map(|chunk| {
+                let values = chunk * 4096..(chunk + 1) * 4096;
+                arrow::record_batch::RecordBatch::try_new(
+                    schema.clone(),
+                    vec![
+                        Arc::new(Int32Array::from_iter_values(values.clone())),
+                        Arc::new(Float64Array::from_iter_values(values.map(|v| v as f64))),
+                    ],
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        DataFrameDescriber::try_new(ctx.read_batches(batches)?)
+    }
+
+    #[tokio::test]
+    async fn test_describe_chunked_with_memory_limit() -> anyhow::Result<()> {
+        let pool = Arc::new(PeakMemoryPool::default());
+        let describer = large_describer(pool.clone())?;
+        describer.describe().await?.collect().await?;
+        let describe_peak = pool.peak();
+
+        let pool = Arc::new(PeakMemoryPool::default());
+        let describer = large_describer(pool.clone())?;
+        let mut stream = Box::pin(describer.describe_stream());
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk?;
+            assert_eq!(chunk.iter().map(|b| b.num_rows()).sum::<usize>(), 1);
+        }
+        let chunked_peak = pool.peak();
+        assert_eq!(pool.reserved(), 0);
+        assert!(chunked_peak > 0);
+        assert!(
+            chunked_peak < describe_peak,
+            "chunked peak {} is not below the describe peak {}",
+            chunked_peak,
+            describe_peak
+        );
+
+        // a limit the chunked mode stays under, but describing all at once
+        // does not
+        let limit = chunked_peak + (describe_peak - chunked_peak) / 2;
+        let describer = large_describer(Arc::new(GreedyMemoryPool::new(limit)))?;
+        let err = describer.describe().await?.collect().await.unwrap_err();
+        assert!(err.to_string().contains("Resources exhausted"), "{}", err);
+        let describer = large_describer(Arc::new(GreedyMemoryPool::new(limit)))?;
+        assert!(describer.describe_chunked().await?.collect().await.is_ok());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_describe_returns_plan_errors() -> anyhow::Result<()> {
+        // approx_percentile_cont has no Float16 signature
+        let ctx = SessionContext::new();
+        let df = ctx.sql("SELECT arrow_cast(1.5, 'Float16') AS half").await?;
+        let describer = DataFrameDescriber::try_new(df)?;
+        let err = describer.describe().await.err().unwrap();
+        assert!(err.to_string().contains("approx_percentile_cont"));
+        Ok(())
+    }
 }
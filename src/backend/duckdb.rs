@@ -0,0 +1,1306 @@
+use std::{collections::HashMap, sync::Arc};
+
+use anyhow::Result;
+use arrow::{
+    array::{AsArray, RecordBatch},
+    compute::concat_batches,
+    datatypes::{DataType, Int64Type, Schema, SchemaRef},
+    util::pretty::pretty_format_batches,
+};
+use datafusion::datasource::file_format::file_compression_type::FileCompressionType;
+use duckdb::Connection;
+
+use super::{
+    WithFooter,
+    audit::{null_audit_batch, null_audit_sql},
+    cast_schema_sql, column_values,
+    correlate::{
+        correlation_batch, correlation_sql, target_correlation_batch, target_correlation_sql,
+    },
+    crosstab::{crosstab_sql, crosstab_values_sql},
+    deduplicate_sql, describe_projection, field_names, frequency_sql,
+    fuzzy::{JoinSide, fuzzy_join_sql},
+    hash::hash_batches,
+    histogram::{Bins, range_sql},
+    ks::{ks_batch, ks_sql},
+    numeric_fields,
+    outliers::outliers_sql,
+    pivot_longer_sql, preview_values_sql, quantiles_batch, quote_literal, sample_label,
+    schema_batch, schema_diff, string_fields, table_type_sql, text_search_sql, transpose_batch,
+    type_summary_batch, user_tables, user_tables_sql, value_counts_schema, value_counts_sql,
+};
+use crate::{
+    Backend, ConnectOpts, CorrelationMethod, DataFormat, DatasetConn, DescribeOpts, HashAlgorithm,
+    IcebergOpts, InferSchemaOpts, OutlierMethod, ReplDisplay, Settings, TaotieConfig,
+};
+
+pub struct DuckdbBackend {
+    conn: Connection,
+    settings: Settings,
+    /// The datasets stored as tables rather than views over a source, named
+    /// `schema.table`, with the query they were created from, if any.
+    materialized: HashMap<String, Option<String>>,
+    /// How each connected dataset, named `schema.table`, was registered.
+    registered_datasets: HashMap<String, ConnectOpts>,
+}
+
+impl DuckdbBackend {
+    #[cfg(test)]
+    pub fn try_new() -> Result<Self> {
+        Self::with_config(&TaotieConfig::default())
+    }
+
+    pub fn with_config(config: &TaotieConfig) -> Result<Self> {
+        let conn = Connection::open_in_memory()?;
+        if let Some(limit) = config.memory_limit {
+            conn.execute_batch(&format!("SET memory_limit = '{}B'", limit))?;
+        }
+        let mut settings = Settings::new();
+        settings.global_row_limit = config.global_row_limit;
+        Ok(Self {
+            conn,
+            settings,
+            materialized: HashMap::new(),
+            registered_datasets: HashMap::new(),
+        })
+    }
+
+    fn table_schema(&self, name: &str) -> Result<Schema> {
+        let mut stmt = self
+            .conn
+            .prepare(&format!("SELECT * FROM {} LIMIT 0", name))?;
+        let schema = stmt.query_arrow([])?.get_schema();
+        Ok(schema.as_ref().clone())
+    }
+
+    /// The columns a dataset was registered with, from the catalog, as a view
+    /// can no longer be read once its source changed its columns.
+    fn registered_schema(&self, name: &str) -> Result<Schema> {
+        let (catalog, table) = match name.split_once('.') {
+            Some((catalog, table)) => (Some(catalog), table),
+            None => (None, name),
+        };
+        let batches = self.query(&format!(
+            "SELECT column_name, data_type FROM duckdb_columns() \
+            WHERE schema_name = {} AND table_name = {} ORDER BY column_index",
+            schema_sql(catalog),
+            quote_literal(table)
+        ))?;
+        let columns = batches
+            .iter()
+            .flat_map(|batch| {
+                let names = batch.column(0).as_string::<i32>();
+                let types = batch.column(1).as_string::<i32>();
+                names
+                    .iter()
+                    .zip(types.iter())
+                    .map(|(name, data_type)| {
+                        format!(
+                            "CAST(NULL AS {}) AS \"{}\"",
+                            data_type.unwrap_or_default(),
+                            name.unwrap_or_default()
+                        )
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        if columns.is_empty() {
+            anyhow::bail!("Dataset {} not found", name);
+        }
+        self.table_schema(&format!("(SELECT {})", columns.join(", ")))
+    }
+
+    /// A dataset name qualified with its schema, so it keeps naming the same
+    /// table once another catalog is in use.
+    fn resolve(&self, name: &str) -> Result<String> {
+        if name.contains('.') {
+            return Ok(name.to_string());
+        }
+        let batches = self.query("SELECT current_schema()")?;
+        let schema = batches[0].column(0).as_string::<i32>().value(0);
+        Ok(format!("{}.{}", schema, name))
+    }
+
+    fn query(&self, sql: &str) -> Result<Vec<RecordBatch>> {
+        let mut stmt = self.conn.prepare(sql)?;
+        let batches = stmt.query_arrow([])?.collect();
+        Ok(batches)
+    }
+
+    /// Swap a dataset for the result of a query reading from it, through a
+    /// scratch table as duckdb cannot replace a view by a table reading from it.
+    fn replace_table(&mut self, name: &str, sql: String) -> Result<()> {
+        let resolved = self.resolve(name)?;
+        let kind = if self.materialized.contains_key(&resolved) {
+            "TABLE"
+        } else {
+            "VIEW"
+        };
+        self.conn.execute_batch(&format!(
+            "CREATE TABLE __taotie_replace AS {0}; DROP {1} {2}; ALTER TABLE __taotie_replace RENAME TO {2}",
+            sql, kind, name
+        ))?;
+        self.registered_datasets.remove(&resolved);
+        self.materialized.insert(resolved, None);
+        Ok(())
+    }
+
+    fn row_count(&self, name: &str) -> Result<usize> {
+        let batches = self.query(&format!("SELECT COUNT(*) FROM {}", name))?;
+        Ok(batches[0].column(0).as_primitive::<Int64Type>().value(0) as usize)
+    }
+}
+
+impl Backend for DuckdbBackend {
+    async fn connect(&mut self, opts: &ConnectOpts) -> Result<()> {
+        let source = source_sql(opts)?;
+        if let DatasetConn::Iceberg(_) = &opts.conn {
+            // iceberg_scan comes with the iceberg extension, not built in
+            self.conn.execute_batch("INSTALL iceberg; LOAD iceberg")?;
+        }
+        if let Some(catalog) = &opts.catalog {
+            self.conn
+                .execute_batch(&format!("CREATE SCHEMA IF NOT EXISTS {}", catalog))?;
+        }
+        let name = opts.table_ref();
+        self.conn.execute_batch(&format!(
+            "CREATE OR REPLACE VIEW {} AS SELECT * FROM {}",
+            name, source
+        ))?;
+        let resolved = self.resolve(&name)?;
+        self.materialized.remove(&resolved);
+        self.registered_datasets.insert(resolved, opts.clone());
+        Ok(())
+    }
+
+    async fn connection_info(&self, name: &str) -> Result<ConnectOpts> {
+        self.registered_datasets
+            .get(&self.resolve(name)?)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("Dataset {} was not registered with connect", name))
+    }
+
+    async fn create_table_from_sql(&mut self, name: &str, query: &str) -> Result<()> {
+        self.conn
+            .execute_batch(&format!("CREATE TABLE {} AS {}", name, query))?;
+        self.materialized
+            .insert(self.resolve(name)?, Some(query.to_string()));
+        Ok(())
+    }
+
+    async fn create_table_from_ordered_sql(&mut self, name: &str, query: &str) -> Result<()> {
+        // a single thread scans the source, and numbers its rows, in order
+        self.conn.execute_batch("SET threads = 1")?;
+        let created = self.create_table_from_sql(name, query).await;
+        self.conn.execute_batch("RESET threads")?;
+        created
+    }
+
+    async fn cast_schema(
+        &mut self,
+        name: &str,
+        casts: &[(String, String)],
+        strict: bool,
+    ) -> Result<()> {
+        let all = self.column_names(name).await?;
+        let sql = cast_schema_sql(name, &all, casts, strict)?;
+        self.replace_table(name, sql)
+    }
+
+    async fn column_histogram(&self, name: &str, column: &str, bins: usize) -> Result<RecordBatch> {
+        let range = self.query(&range_sql(name, column))?;
+        let Some(bins) = Bins::from_range(&range, bins) else {
+            anyhow::bail!("Column {} of dataset {} has no values", column, name);
+        };
+        bins.batch(&self.query(&bins.sql(name, column))?)
+    }
+
+    async fn column_types_summary(&self, name: &str) -> Result<RecordBatch> {
+        type_summary_batch(&self.table_schema(name)?)
+    }
+
+    async fn compare_distributions(
+        &self,
+        name1: &str,
+        col1: &str,
+        name2: &str,
+        col2: &str,
+    ) -> Result<RecordBatch> {
+        ks_batch(&self.query(&ks_sql(name1, col1, name2, col2))?)
+    }
+
+    async fn compute_hash(&self, name: &str, algorithm: HashAlgorithm) -> Result<String> {
+        let schema = self.table_schema(name)?;
+        let batches = self.query(&format!("SELECT * FROM {}", name))?;
+        hash_batches(&schema, &batches, algorithm)
+    }
+
+    async fn correlate(&self, name: &str, method: CorrelationMethod) -> Result<RecordBatch> {
+        let columns = self.numeric_columns(name).await?;
+        if columns.is_empty() {
+            anyhow::bail!("Dataset {} has no numeric columns", name);
+        }
+        let batches = self.query(&correlation_sql(name, &columns, method))?;
+        correlation_batch(&columns, method, &batches)
+    }
+
+    async fn cross_tabulate(
+        &self,
+        name: &str,
+        row_col: &str,
+        col_col: &str,
+    ) -> Result<RecordBatch> {
+        let values = column_values(&self.query(&crosstab_values_sql(name, col_col))?)?;
+        if values.is_empty() {
+            anyhow::bail!("Column {} of dataset {} has no values", col_col, name);
+        }
+        let batches = self.query(&crosstab_sql(name, row_col, col_col, &values))?;
+        match batches.first() {
+            Some(batch) => Ok(concat_batches(&batch.schema(), &batches)?),
+            None => Ok(RecordBatch::new_empty(Arc::new(Schema::empty()))),
+        }
+    }
+
+    async fn count_nulls(&self, name: &str) -> Result<RecordBatch> {
+        let schema = self.table_schema(name)?;
+        let batches = self.query(&null_audit_sql(name, &schema))?;
+        null_audit_batch(&schema, &batches)
+    }
+
+    async fn deduplicate(
+        &mut self,
+        name: &str,
+        key: &[String],
+        keep_last: bool,
+        output: Option<&str>,
+    ) -> Result<usize> {
+        let all = self.column_names(name).await?;
+        let sql = deduplicate_sql(name, &all, key, keep_last)?;
+        let target = output.unwrap_or(name);
+        match output {
+            Some(output) => self.create_table_from_ordered_sql(output, &sql).await?,
+            None => {
+                // a single thread numbers the rows in order
+                self.conn.execute_batch("SET threads = 1")?;
+                let replaced = self.replace_table(name, sql);
+                self.conn.execute_batch("RESET threads")?;
+                replaced?
+            }
+        }
+        self.row_count(target)
+    }
+
+    async fn describe(&self, opts: &DescribeOpts) -> Result<impl ReplDisplay> {
+        let projection = describe_projection(self, opts).await?;
+        let mut sql = format!("SELECT {} FROM {}", projection, opts.name);
+        if let Some(predicate) = &opts.predicate {
+            sql = format!("{} WHERE {}", sql, predicate);
+        }
+        if let Some(size) = opts.sample_size {
+            sql = format!("{} USING SAMPLE {} ROWS", sql, size);
+        }
+        let batches = self.query(&format!("SUMMARIZE {}", sql))?;
+        let footer = opts
+            .sample_size
+            .map(|size| format!("(sampled: {} rows)", size));
+        Ok(WithFooter::new(batches, footer))
+    }
+
+    async fn describe_sample(&self, name: &str, sample_size: usize) -> Result<impl ReplDisplay> {
+        let rows = self.row_count(name)?;
+        let opts = DescribeOpts::new(name.to_string())
+            .with_sample_size((rows > sample_size).then_some(sample_size));
+        let batches = self.describe(&opts).await?.into_batches().await?;
+        Ok(WithFooter::new(
+            batches,
+            Some(sample_label(sample_size, rows)),
+        ))
+    }
+
+    async fn detect_schema_changes(&self, name: &str) -> Result<RecordBatch> {
+        let opts = self.connection_info(name).await?;
+        let source = self.table_schema(&source_sql(&opts)?)?;
+        schema_diff(&self.registered_schema(name)?, &source)
+    }
+
+    async fn drop_all(&mut self) -> Result<usize> {
+        let tables =
+            user_tables(&self.query(&user_tables_sql("current_database()", "current_schema()"))?);
+        for (name, table_type) in &tables {
+            let kind = if table_type == "VIEW" {
+                "VIEW"
+            } else {
+                "TABLE"
+            };
+            self.conn
+                .execute_batch(&format!("DROP {} {}", kind, name))?;
+            let resolved = self.resolve(name)?;
+            self.materialized.remove(&resolved);
+            self.registered_datasets.remove(&resolved);
+        }
+        Ok(tables.len())
+    }
+
+    async fn drop_table(&mut self, name: &str) -> Result<()> {
+        let resolved = self.resolve(name)?;
+        let kind = if self.materialized.remove(&resolved).is_some() {
+            "TABLE"
+        } else {
+            "VIEW"
+        };
+        self.conn
+            .execute_batch(&format!("DROP {} {}", kind, name))?;
+        self.registered_datasets.remove(&resolved);
+        Ok(())
+    }
+
+    async fn explain_analyze(&self, sql: &str) -> Result<String> {
+        let batches = self.query(&format!("EXPLAIN ANALYZE {}", sql))?;
+        let plans = batches
+            .iter()
+            .flat_map(|batch| batch.column(1).as_string::<i32>().iter().flatten())
+            .collect::<Vec<_>>();
+        Ok(plans.join("\n"))
+    }
+
+    async fn explain_dot(&self, _sql: &str) -> Result<String> {
+        anyhow::bail!("GraphViz plans are not supported by duckdb backend")
+    }
+
+    async fn frequency_table(
+        &self,
+        name: &str,
+        column: &str,
+        normalize: bool,
+    ) -> Result<impl ReplDisplay> {
+        self.query(&frequency_sql(name, column, normalize))
+    }
+
+    async fn fuzzy_join(
+        &mut self,
+        left: &str,
+        right: &str,
+        left_col: &str,
+        right_col: &str,
+        threshold: f64,
+        output: &str,
+    ) -> Result<usize> {
+        let left = JoinSide {
+            name: left,
+            columns: self.column_names(left).await?,
+            key: left_col,
+        };
+        let right = JoinSide {
+            name: right,
+            columns: self.column_names(right).await?,
+            key: right_col,
+        };
+        let sql = fuzzy_join_sql(&left, &right, threshold, "jaro_winkler_similarity")?;
+        self.create_table_from_sql(output, &sql).await?;
+        self.row_count(output)
+    }
+
+    async fn generate_series(&mut self, name: &str, batch: RecordBatch) -> Result<()> {
+        let data_type = match batch.schema().field(0).data_type() {
+            DataType::Int64 => "BIGINT",
+            DataType::Float64 => "DOUBLE",
+            _ => "DATE",
+        };
+        let rows = column_values(&[batch])?
+            .iter()
+            .map(|value| format!("('{}')", value))
+            .collect::<Vec<_>>();
+        let query = format!(
+            "SELECT CAST(col0 AS {}) AS value FROM (VALUES {})",
+            data_type,
+            rows.join(", ")
+        );
+        self.create_table_from_sql(name, &query).await?;
+        // the values are kept, not the query listing them
+        self.materialized.insert(self.resolve(name)?, None);
+        Ok(())
+    }
+
+    async fn head(&self, name: &str, size: usize, offset: usize) -> Result<impl ReplDisplay> {
+        let size = match self.settings.global_row_limit {
+            Some(limit) => size.min(limit),
+            None => size,
+        };
+        self.query(&format!(
+            "SELECT * FROM {} LIMIT {} OFFSET {}",
+            name, size, offset
+        ))
+    }
+
+    async fn infer_schema(&self, opts: &InferSchemaOpts) -> Result<RecordBatch> {
+        let source = match opts.format {
+            DataFormat::Csv => format!(
+                "read_csv_auto('{}', header = {})",
+                opts.file, !opts.no_header
+            ),
+            DataFormat::Json => format!("read_json_auto('{}')", opts.file),
+            DataFormat::Parquet => format!("read_parquet('{}')", opts.file),
+        };
+        schema_batch(&self.table_schema(&source)?)
+    }
+
+    async fn ingest_streaming(&mut self, _name: &str, _file: &str) -> Result<usize> {
+        anyhow::bail!("Streaming ingestion is not supported by duckdb backend")
+    }
+
+    async fn list(&self, with_schema: bool, catalog: Option<&str>) -> Result<impl ReplDisplay> {
+        let schema = schema_sql(catalog);
+        let materialized = self
+            .materialized
+            .keys()
+            .filter_map(|name| name.split_once('.'));
+        if with_schema {
+            self.query(&format!(
+                "SELECT table_name, {}, columns FROM (SELECT t.table_schema, t.table_name, t.table_type, string_agg(c.column_name || ': ' || c.data_type, ', ' ORDER BY c.ordinal_position) AS columns \
+                FROM information_schema.tables t JOIN information_schema.columns c ON t.table_name = c.table_name AND t.table_schema = c.table_schema \
+                WHERE t.table_schema = {} GROUP BY t.table_schema, t.table_name, t.table_type)",
+                table_type_sql(materialized),
+                schema
+            ))
+        } else {
+            self.query(&format!(
+                "SELECT table_name, {} FROM information_schema.tables WHERE table_schema = {}",
+                table_type_sql(materialized),
+                schema
+            ))
+        }
+    }
+
+    async fn column_correlation_with_target(
+        &self,
+        name: &str,
+        target: &str,
+        method: CorrelationMethod,
+    ) -> Result<RecordBatch> {
+        let features = self
+            .numeric_columns(name)
+            .await?
+            .into_iter()
+            .filter(|column| column != target)
+            .collect::<Vec<_>>();
+        if features.is_empty() {
+            anyhow::bail!("Dataset {} has no numeric columns besides {}", name, target);
+        }
+        let batches = self.query(&target_correlation_sql(name, target, &features, method))?;
+        target_correlation_batch(&features, &batches)
+    }
+
+    async fn column_names(&self, name: &str) -> Result<Vec<String>> {
+        Ok(field_names(&self.table_schema(name)?))
+    }
+
+    async fn column_type(&self, name: &str, column: &str) -> Result<DataType> {
+        let schema = self.table_schema(name)?;
+        Ok(schema.field_with_name(column)?.data_type().clone())
+    }
+
+    async fn numeric_columns(&self, name: &str) -> Result<Vec<String>> {
+        Ok(numeric_fields(&self.table_schema(name)?))
+    }
+
+    async fn quantiles(&self, name: &str, column: &str, quantiles: &[f64]) -> Result<RecordBatch> {
+        let exprs = quantiles
+            .iter()
+            .map(|q| format!("quantile_cont(CAST(\"{}\" AS DOUBLE), {})", column, q))
+            .collect::<Vec<_>>();
+        let batches = self.query(&format!("SELECT {} FROM {}", exprs.join(", "), name))?;
+        quantiles_batch(quantiles, &batches)
+    }
+
+    async fn register_schema(&mut self, _name: &str, _schema: SchemaRef) -> Result<()> {
+        anyhow::bail!("Schema-only datasets are not supported by duckdb backend")
+    }
+
+    async fn row_at(&self, name: &str, index: usize) -> Result<Option<RecordBatch>> {
+        let batches = self.query(&format!("SELECT * FROM {} LIMIT 1 OFFSET {}", name, index))?;
+        Ok(batches.into_iter().find(|batch| batch.num_rows() > 0))
+    }
+
+    async fn schema(&self, name: &str) -> Result<impl ReplDisplay> {
+        self.query(&format!("DESCRIBE {}", name))
+    }
+
+    async fn source_query(&self, name: &str) -> Result<Option<String>> {
+        Ok(self
+            .materialized
+            .get(&self.resolve(name)?)
+            .cloned()
+            .flatten())
+    }
+
+    async fn sql(&self, sql: &str) -> Result<impl ReplDisplay> {
+        self.query(&limit_sql(sql, self.settings.global_row_limit))
+    }
+
+    async fn string_columns(&self, name: &str) -> Result<Vec<String>> {
+        Ok(string_fields(&self.table_schema(name)?))
+    }
+
+    async fn table_exists(&self, name: &str) -> Result<bool> {
+        let (catalog, name) = match name.split_once('.') {
+            Some((catalog, name)) => (Some(catalog), name),
+            None => (None, name),
+        };
+        let batches = self.query(&format!(
+            "SELECT table_name FROM information_schema.tables WHERE table_schema = {} AND table_name = '{}'",
+            schema_sql(catalog),
+            name
+        ))?;
+        Ok(batches.iter().any(|batch| batch.num_rows() > 0))
+    }
+
+    async fn table_names(&self) -> Result<Vec<String>> {
+        let batches = self.query(
+            "SELECT table_name FROM information_schema.tables WHERE table_schema = current_schema() ORDER BY table_name",
+        )?;
+        let names = batches
+            .iter()
+            .flat_map(|batch| batch.column(0).as_string::<i32>().iter().flatten())
+            .map(|name| name.to_string())
+            .collect();
+        Ok(names)
+    }
+
+    async fn text_search(
+        &self,
+        name: &str,
+        columns: &[String],
+        query: &str,
+        case_sensitive: bool,
+        limit: usize,
+    ) -> Result<impl ReplDisplay> {
+        self.query(&text_search_sql(
+            name,
+            columns,
+            query,
+            case_sensitive,
+            limit,
+        ))
+    }
+
+    async fn transpose(&self, name: &str, row: usize) -> Result<Option<RecordBatch>> {
+        let batch = self.row_at(name, row).await?;
+        batch.map(|batch| transpose_batch(&batch)).transpose()
+    }
+
+    async fn use_catalog(&mut self, catalog: &str) -> Result<()> {
+        self.conn.execute_batch(&format!(
+            "CREATE SCHEMA IF NOT EXISTS {}; SET schema = {}",
+            catalog,
+            quote_literal(catalog)
+        ))?;
+        Ok(())
+    }
+
+    async fn vacuum(&mut self) -> Result<usize> {
+        anyhow::bail!("Vacuum is not supported by duckdb backend")
+    }
+
+    async fn validate_sql(&self, sql: &str) -> Result<String> {
+        let batches = self.query(&format!("EXPLAIN {}", sql))?;
+        Ok(pretty_format_batches(&batches)?.to_string())
+    }
+
+    async fn outliers(
+        &self,
+        name: &str,
+        column: &str,
+        method: OutlierMethod,
+    ) -> Result<impl ReplDisplay> {
+        self.query(&outliers_sql(name, column, method, "quantile_cont"))
+    }
+
+    async fn partition_info(&self, _name: &str) -> Result<RecordBatch> {
+        anyhow::bail!("Partition info is not supported by duckdb backend")
+    }
+
+    async fn pivot_longer(
+        &self,
+        name: &str,
+        id_cols: &[String],
+        value_cols: &[String],
+        variable_col: &str,
+        value_col: &str,
+    ) -> Result<impl ReplDisplay> {
+        let all = self.column_names(name).await?;
+        self.query(&pivot_longer_sql(
+            name,
+            &all,
+            id_cols,
+            value_cols,
+            variable_col,
+            value_col,
+        )?)
+    }
+
+    async fn preview_values(&self, name: &str, column: &str, limit: usize) -> Result<Vec<String>> {
+        let batches = self.query(&preview_values_sql(name, column, limit))?;
+        column_values(&batches)
+    }
+
+    async fn value_counts(&self, name: &str, column: &str, top: usize) -> Result<RecordBatch> {
+        let batches = self.query(&value_counts_sql(name, column, Some(top)))?;
+        Ok(concat_batches(&value_counts_schema(), &batches)?)
+    }
+
+    fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    fn settings_mut(&mut self) -> &mut Settings {
+        &mut self.settings
+    }
+}
+
+/// The `table_schema` a listing is filtered by, the current schema by default.
+fn schema_sql(catalog: Option<&str>) -> String {
+    match catalog {
+        Some(catalog) => quote_literal(catalog),
+        None => "current_schema()".to_string(),
+    }
+}
+
+/// The table function which reads a dataset from its source.
+fn source_sql(opts: &ConnectOpts) -> Result<String> {
+    let source = match &opts.conn {
+        DatasetConn::Parquet(path) if opts.schema_evolution => {
+            format!("read_parquet('{}', union_by_name = true)", path)
+        }
+        DatasetConn::Parquet(path) => format!("read_parquet('{}')", path),
+        DatasetConn::CompressedParquet(_) => {
+            anyhow::bail!("Compressed parquet files are not supported by duckdb backend")
+        }
+        DatasetConn::Postgres(_) => {
+            anyhow::bail!("Postgres datasets are not supported by duckdb backend")
+        }
+        DatasetConn::Iceberg(iceberg) => iceberg_scan_sql(iceberg),
+        DatasetConn::Csv(file_opts) => format!(
+            "read_csv_auto('{}', compression = '{}')",
+            file_opts.filename,
+            compression(file_opts.compression)
+        ),
+        DatasetConn::Json(file_opts) => format!(
+            "read_json_auto('{}', compression = '{}')",
+            file_opts.filename,
+            compression(file_opts.compression)
+        ),
+        DatasetConn::Stdin(_) => {
+            anyhow::bail!("Piped input is not supported by duckdb backend")
+        }
+    };
+    Ok(source)
+}
+
+fn compression(compression: FileCompressionType) -> &'static str {
+    match compression {
+        FileCompressionType::GZIP => "gzip",
+        FileCompressionType::ZSTD => "zstd",
+        _ => "auto",
+    }
+}
+
+/// Cap a query which returns rows at `limit` rows, by selecting from it as a
+/// subquery so any limit of its own is kept. The query is closed on a line
+/// of its own, so a trailing `--` comment cannot swallow the cap.
+pub(crate) fn limit_sql(sql: &str, limit: Option<usize>) -> String {
+    let Some(limit) = limit else {
+        return sql.to_string();
+    };
+    let sql = sql.trim().trim_end_matches(';');
+    let lower = skip_comments(sql).to_ascii_lowercase();
+    if lower.starts_with("select") || lower.starts_with("with") {
+        format!("SELECT * FROM ({}\n) AS capped LIMIT {}", sql, limit)
+    } else {
+        sql.to_string()
+    }
+}
+
+/// The query after any leading comments.
+fn skip_comments(mut sql: &str) -> &str {
+    loop {
+        sql = sql.trim_start();
+        if let Some(rest) = sql.strip_prefix("--") {
+            sql = rest.split_once('\n').map_or("", |(_, rest)| rest);
+        } else if let Some(rest) = sql.strip_prefix("/*") {
+            sql = rest.split_once("*/").map_or("", |(_, rest)| rest);
+        } else {
+            return sql;
+        }
+    }
+}
+
+/// Scan a table of a file catalog, where `ns.table` lives under
+/// `<catalog_uri>/ns/table`.
+fn iceberg_scan_sql(iceberg: &IcebergOpts) -> String {
+    let path = format!(
+        "{}/{}",
+        iceberg.catalog_uri,
+        iceberg.table_identifier.replace('.', "/")
+    );
+    match iceberg.snapshot_id {
+        Some(id) => format!("iceberg_scan('{}', snapshot_from_id = {})", path, id),
+        None => format!("iceberg_scan('{}')", path),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::FileOpts;
+    use crate::backend::tests::{row_cells, row_count, temp_dir};
+    use arrow::array::{ArrayRef, Int64Array};
+    use arrow::datatypes::Field;
+
+    async fn test_backend() -> Result<DuckdbBackend> {
+        let mut backend = DuckdbBackend::try_new()?;
+        let opts = ConnectOpts::new(
+            DatasetConn::Parquet("assets/sample.parquet".to_string()),
+            None,
+            "sample".to_string(),
+        );
+        backend.connect(&opts).await?;
+        Ok(backend)
+    }
+
+    /// Create a backend with a table `t` of ids, names and scores.
+    async fn values_backend() -> Result<DuckdbBackend> {
+        let mut backend = DuckdbBackend::try_new()?;
+        backend
+            .create_table_from_sql(
+                "t",
+                "SELECT CAST(id AS BIGINT) AS id, name, CAST(score AS DOUBLE) AS score \
+                FROM (VALUES (1, 'a', 1.5), (2, 'b', 2.5), (3, 'a', NULL), (4, 'c', 4.5)) \
+                AS v(id, name, score)",
+            )
+            .await?;
+        Ok(backend)
+    }
+
+    async fn display(data: impl ReplDisplay, backend: &DuckdbBackend) -> Result<String> {
+        data.display(backend.settings()).await
+    }
+
+    #[test]
+    fn test_limit_sql() {
+        assert_eq!(limit_sql("SELECT * FROM t", None), "SELECT * FROM t");
+        assert_eq!(
+            limit_sql("select * from t;", Some(5)),
+            "SELECT * FROM (select * from t\n) AS capped LIMIT 5"
+        );
+        assert_eq!(
+            limit_sql("SELECT * FROM t -- all rows", Some(5)),
+            "SELECT * FROM (SELECT * FROM t -- all rows\n) AS capped LIMIT 5"
+        );
+        assert_eq!(
+            limit_sql("-- all rows\nSELECT * FROM t", Some(5)),
+            "SELECT * FROM (-- all rows\nSELECT * FROM t\n) AS capped LIMIT 5"
+        );
+        assert_eq!(
+            limit_sql("CREATE TABLE u AS SELECT 1", Some(5)),
+            "CREATE TABLE u AS SELECT 1"
+        );
+    }
+
+    #[test]
+    fn test_iceberg_scan_sql() {
+        let mut iceberg = IcebergOpts {
+            catalog_uri: "/tmp/warehouse".to_string(),
+            table_identifier: "db.events".to_string(),
+            snapshot_id: None,
+        };
+        assert_eq!(
+            iceberg_scan_sql(&iceberg),
+            "iceberg_scan('/tmp/warehouse/db/events')"
+        );
+        iceberg.snapshot_id = Some(42);
+        assert_eq!(
+            iceberg_scan_sql(&iceberg),
+            "iceberg_scan('/tmp/warehouse/db/events', snapshot_from_id = 42)"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_duckdb_connect_and_list() -> Result<()> {
+        let backend = test_backend().await?;
+        let output = display(backend.list(false, None).await?, &backend).await?;
+        assert!(output.contains("sample"));
+
+        let output = display(backend.list(true, None).await?, &backend).await?;
+        assert!(output.contains("email"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_duckdb_head() -> Result<()> {
+        let backend = test_backend().await?;
+        let output = display(backend.head("sample", 3, 0).await?, &backend).await?;
+        assert_eq!(row_count(&output), 3);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_duckdb_row_at() -> Result<()> {
+        let backend = test_backend().await?;
+        assert!(backend.row_at("sample", 0).await?.is_some());
+        assert!(backend.row_at("sample", 1_000_000).await?.is_none());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_duckdb_describe_and_schema() -> Result<()> {
+        let backend = test_backend().await?;
+        let output = display(
+            backend
+                .describe(&DescribeOpts::new("sample".to_string()))
+                .await?,
+            &backend,
+        )
+        .await?;
+        assert!(output.contains("column_name"));
+
+        let output = display(backend.schema("sample").await?, &backend).await?;
+        assert!(output.contains("email"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_duckdb_sql_and_validate() -> Result<()> {
+        let backend = test_backend().await?;
+        let output = display(
+            backend.sql("SELECT count(*) AS total FROM sample").await?,
+            &backend,
+        )
+        .await?;
+        assert!(output.contains("total"));
+
+        assert!(backend.validate_sql("SELECT * FROM sample").await.is_ok());
+        assert!(backend.validate_sql("SELECT * FROM missing").await.is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_duckdb_global_row_limit() -> Result<()> {
+        let mut backend = test_backend().await?;
+        backend.settings_mut().set("global_row_limit", "3")?;
+        for sql in [
+            "SELECT * FROM sample",
+            "SELECT * FROM sample LIMIT 8",
+            "SELECT * FROM sample -- every row",
+            "WITH x AS (SELECT * FROM sample LIMIT 5) SELECT * FROM sample",
+        ] {
+            let output = display(backend.sql(sql).await?, &backend).await?;
+            assert_eq!(row_count(&output), 3, "{}", sql);
+        }
+        let output = display(backend.sql("SELECT * FROM sample LIMIT 2").await?, &backend).await?;
+        assert_eq!(row_count(&output), 2);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_duckdb_connection_info() -> Result<()> {
+        let backend = test_backend().await?;
+        let opts = backend.connection_info("sample").await?;
+        assert_eq!(opts.name, "sample");
+        assert!(backend.connection_info("missing").await.is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_duckdb_create_and_drop_table() -> Result<()> {
+        let mut backend = values_backend().await?;
+        backend
+            .create_table_from_sql("u", "SELECT * FROM t WHERE id > 2")
+            .await?;
+        let output = display(backend.sql("SELECT * FROM u").await?, &backend).await?;
+        assert_eq!(row_count(&output), 2);
+        assert!(backend.table_exists("u").await?);
+        assert_eq!(backend.table_names().await?, ["t", "u"]);
+
+        backend.drop_table("u").await?;
+        assert!(!backend.table_exists("u").await?);
+        assert_eq!(backend.drop_all().await?, 1);
+        assert!(backend.table_names().await?.is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_duckdb_column_helpers() -> Result<()> {
+        let backend = values_backend().await?;
+        assert_eq!(backend.column_names("t").await?, ["id", "name", "score"]);
+        assert_eq!(backend.numeric_columns("t").await?, ["id", "score"]);
+        assert_eq!(backend.string_columns("t").await?, ["name"]);
+        assert_eq!(backend.column_type("t", "score").await?, DataType::Float64);
+        assert!(backend.column_type("t", "missing").await.is_err());
+
+        let output = display(backend.column_types_summary("t").await?, &backend).await?;
+        assert_eq!(row_cells(&output, "Int64")[1], "1");
+        assert_eq!(row_cells(&output, "Float64")[1], "1");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_duckdb_cast_schema() -> Result<()> {
+        let mut backend = values_backend().await?;
+        let casts = [("id".to_string(), "VARCHAR".to_string())];
+        backend.cast_schema("t", &casts, true).await?;
+        assert_eq!(backend.column_type("t", "id").await?, DataType::Utf8);
+        assert_eq!(backend.row_count("t")?, 4);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_duckdb_column_histogram() -> Result<()> {
+        let backend = values_backend().await?;
+        let batch = backend.column_histogram("t", "score", 3).await?;
+        assert_eq!(batch.num_rows(), 3);
+        let counts = batch.column(2).as_primitive::<Int64Type>();
+        assert_eq!(counts.values().iter().sum::<i64>(), 3);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_duckdb_compare_distributions() -> Result<()> {
+        let backend = values_backend().await?;
+        let output = display(
+            backend
+                .compare_distributions("t", "score", "t", "score")
+                .await?,
+            &backend,
+        )
+        .await?;
+        assert_eq!(row_cells(&output, "0.0")[2..], ["3", "3"]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_duckdb_compute_hash() -> Result<()> {
+        let mut backend = values_backend().await?;
+        let hash = backend.compute_hash("t", HashAlgorithm::Sha256).await?;
+        assert_eq!(
+            hash,
+            backend.compute_hash("t", HashAlgorithm::Sha256).await?
+        );
+        backend
+            .create_table_from_sql("u", "SELECT * FROM t ORDER BY id")
+            .await?;
+        assert_eq!(
+            hash,
+            backend.compute_hash("u", HashAlgorithm::Sha256).await?
+        );
+        // the row order is part of the fingerprint
+        backend
+            .create_table_from_sql("v", "SELECT * FROM t ORDER BY id DESC")
+            .await?;
+        assert_ne!(
+            hash,
+            backend.compute_hash("v", HashAlgorithm::Sha256).await?
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_duckdb_correlate() -> Result<()> {
+        let mut backend = values_backend().await?;
+        let output = display(
+            backend.correlate("t", CorrelationMethod::Pearson).await?,
+            &backend,
+        )
+        .await?;
+        let coefficient: f64 = row_cells(&output, "id")[1].parse()?;
+        assert!((coefficient - 1.0).abs() < 1e-9);
+
+        backend
+            .create_table_from_sql("scored", "SELECT id, score FROM t WHERE score IS NOT NULL")
+            .await?;
+        let output = display(
+            backend
+                .column_correlation_with_target("scored", "id", CorrelationMethod::Spearman)
+                .await?,
+            &backend,
+        )
+        .await?;
+        let coefficient: f64 = row_cells(&output, "score")[1].parse()?;
+        assert!((coefficient - 1.0).abs() < 1e-9);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_duckdb_cross_tabulate() -> Result<()> {
+        let backend = values_backend().await?;
+        let batch = backend.cross_tabulate("t", "name", "id").await?;
+        assert_eq!(batch.num_rows(), 3);
+        // the row column and one per id
+        assert_eq!(batch.num_columns(), 5);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_duckdb_count_nulls() -> Result<()> {
+        let backend = values_backend().await?;
+        let output = display(backend.count_nulls("t").await?, &backend).await?;
+        assert_eq!(row_cells(&output, "score")[1], "1");
+        assert_eq!(row_cells(&output, "id")[1], "0");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_duckdb_deduplicate() -> Result<()> {
+        let mut backend = values_backend().await?;
+        let key = ["name".to_string()];
+        assert_eq!(backend.deduplicate("t", &key, false, Some("u")).await?, 3);
+        assert_eq!(backend.row_count("t")?, 4);
+        assert_eq!(backend.deduplicate("t", &key, true, None).await?, 3);
+        assert_eq!(backend.row_count("t")?, 3);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_duckdb_describe_sample() -> Result<()> {
+        let backend = values_backend().await?;
+        let output = display(backend.describe_sample("t", 2).await?, &backend).await?;
+        assert!(output.contains("column_name"));
+        assert!(output.contains("(sample: 2 of 4 rows, ratio 0.5000)"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_duckdb_detect_schema_changes() -> Result<()> {
+        let dir = temp_dir()?;
+        let path = dir.path().join("drift.csv");
+        fs::write(&path, "id,name\n1,a\n")?;
+        let filename = path.to_string_lossy().to_string();
+        let mut backend = DuckdbBackend::try_new()?;
+        let conn = DatasetConn::Csv(FileOpts {
+            filename,
+            extension: "csv".to_string(),
+            compression: FileCompressionType::UNCOMPRESSED,
+        });
+        backend
+            .connect(&ConnectOpts::new(conn, None, "drift".to_string()))
+            .await?;
+        assert_eq!(backend.detect_schema_changes("drift").await?.num_rows(), 0);
+
+        fs::write(&path, "id,name,score\n1,a,1.5\n")?;
+        let output = display(backend.detect_schema_changes("drift").await?, &backend).await?;
+        assert_eq!(row_cells(&output, "score")[1], "added");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_duckdb_explain_analyze() -> Result<()> {
+        let backend = values_backend().await?;
+        let plan = backend.explain_analyze("SELECT * FROM t").await?;
+        assert!(
+            plan.contains("SEQ_SCAN") || plan.contains("TABLE_SCAN"),
+            "{}",
+            plan
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_duckdb_fuzzy_join() -> Result<()> {
+        let mut backend = values_backend().await?;
+        backend
+            .create_table_from_sql(
+                "u",
+                "SELECT * FROM (VALUES ('a', 10), ('z', 20)) AS v(label, amount)",
+            )
+            .await?;
+        let rows = backend
+            .fuzzy_join("t", "u", "name", "label", 0.9, "joined")
+            .await?;
+        assert_eq!(rows, 2);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_duckdb_generate_series() -> Result<()> {
+        let mut backend = DuckdbBackend::try_new()?;
+        let batch = RecordBatch::try_from_iter([(
+            "value",
+            Arc::new(Int64Array::from_iter_values(0..5)) as ArrayRef,
+        )])?;
+        backend.generate_series("series", batch).await?;
+        assert_eq!(backend.row_count("series")?, 5);
+        assert_eq!(
+            backend.column_type("series", "value").await?,
+            DataType::Int64
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_duckdb_infer_schema() -> Result<()> {
+        let dir = temp_dir()?;
+        let path = dir.path().join("infer.csv");
+        fs::write(&path, "id,name\n1,a\n")?;
+        let backend = DuckdbBackend::try_new()?;
+        let opts = InferSchemaOpts::new(path.to_string_lossy().to_string(), DataFormat::Csv, false);
+        let output = display(backend.infer_schema(&opts).await?, &backend).await?;
+        assert_eq!(row_cells(&output, "id")[1], "Int64");
+        assert_eq!(row_cells(&output, "name")[1], "Utf8");
+        assert!(!backend.table_exists("infer").await?);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_duckdb_outliers() -> Result<()> {
+        let mut backend = DuckdbBackend::try_new()?;
+        backend
+            .create_table_from_sql(
+                "t",
+                "SELECT * FROM (VALUES (1.0), (2.0), (2.0), (3.0), (100.0)) AS v(x)",
+            )
+            .await?;
+        let output = display(
+            backend.outliers("t", "x", OutlierMethod::Iqr).await?,
+            &backend,
+        )
+        .await?;
+        assert_eq!(row_count(&output), 1);
+        assert!(output.contains("100"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_duckdb_pivot_longer() -> Result<()> {
+        let backend = values_backend().await?;
+        let output = display(
+            backend
+                .pivot_longer(
+                    "t",
+                    &["id".to_string()],
+                    &["name".to_string(), "score".to_string()],
+                    "variable",
+                    "value",
+                )
+                .await?,
+            &backend,
+        )
+        .await?;
+        assert_eq!(row_count(&output), 8);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_duckdb_preview_values() -> Result<()> {
+        let backend = values_backend().await?;
+        let values = backend.preview_values("t", "name", 10).await?;
+        assert_eq!(values, ["a", "b", "c"]);
+        assert_eq!(backend.preview_values("t", "name", 1).await?.len(), 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_duckdb_quantiles() -> Result<()> {
+        let backend = values_backend().await?;
+        let output = display(
+            backend.quantiles("t", "id", &[0.0, 0.5, 1.0]).await?,
+            &backend,
+        )
+        .await?;
+        assert_eq!(row_cells(&output, "0.0")[1], "1.0");
+        assert_eq!(row_cells(&output, "0.5")[1], "2.5");
+        assert_eq!(row_cells(&output, "1.0")[1], "4.0");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_duckdb_text_search() -> Result<()> {
+        let backend = values_backend().await?;
+        let columns = ["name".to_string()];
+        let output = display(
+            backend.text_search("t", &columns, "A", false, 10).await?,
+            &backend,
+        )
+        .await?;
+        assert_eq!(row_count(&output), 2);
+        let output = display(
+            backend.text_search("t", &columns, "A", true, 10).await?,
+            &backend,
+        )
+        .await?;
+        assert_eq!(row_count(&output), 0);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_duckdb_transpose() -> Result<()> {
+        let backend = values_backend().await?;
+        let output = display(backend.transpose("t", 1).await?.unwrap(), &backend).await?;
+        assert_eq!(row_cells(&output, "name"), ["name", "b"]);
+        assert!(backend.transpose("t", 10).await?.is_none());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_duckdb_use_catalog() -> Result<()> {
+        let mut backend = DuckdbBackend::try_new()?;
+        backend.use_catalog("staging").await?;
+        backend
+            .create_table_from_sql("s", "SELECT 1 AS one")
+            .await?;
+        assert!(backend.table_exists("staging.s").await?);
+        assert!(!backend.table_exists("main.s").await?);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_duckdb_value_counts() -> Result<()> {
+        let backend = values_backend().await?;
+        let output = display(backend.value_counts("t", "name", 2).await?, &backend).await?;
+        assert_eq!(row_count(&output), 2);
+        assert_eq!(row_cells(&output, "a"), ["a", "2"]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_duckdb_unsupported_methods() -> Result<()> {
+        let mut backend = values_backend().await?;
+        assert!(backend.explain_dot("SELECT * FROM t").await.is_err());
+        assert!(backend.ingest_streaming("t", "t.csv").await.is_err());
+        assert!(backend.partition_info("t").await.is_err());
+        assert!(backend.vacuum().await.is_err());
+        let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Int64, false)]));
+        assert!(backend.register_schema("empty", schema).await.is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_duckdb_describe_selected_columns() -> Result<()> {
+        let backend = values_backend().await?;
+        let opts = DescribeOpts::new("t".to_string())
+            .with_columns(vec!["score".to_string(), "id".to_string()]);
+        let output = display(backend.describe(&opts).await?, &backend).await?;
+        assert_eq!(row_count(&output), 2);
+        assert_eq!(row_cells(&output, "score")[0], "score");
+        assert_eq!(row_cells(&output, "id")[0], "id");
+        assert!(row_cells(&output, "name").is_empty());
+
+        let opts = DescribeOpts::new("t".to_string())
+            .with_columns(vec!["id".to_string(), "missing".to_string()]);
+        let err = backend.describe(&opts).await.err().unwrap();
+        assert_eq!(err.to_string(), "Dataset t has no column(s): missing");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_duckdb_frequency_table() -> Result<()> {
+        let backend = values_backend().await?;
+        let output = display(backend.frequency_table("t", "name", false).await?, &backend).await?;
+        assert_eq!(row_cells(&output, "a"), ["a", "2"]);
+        assert_eq!(row_count(&output), 3);
+
+        let output = display(backend.frequency_table("t", "name", true).await?, &backend).await?;
+        assert_eq!(row_cells(&output, "a"), ["a", "0.5"]);
+        Ok(())
+    }
+}
@@ -0,0 +1,79 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use arrow::{
+    array::{AsArray, Float64Array},
+    datatypes::DataType,
+};
+use datafusion::logical_expr::{ColumnarValue, ScalarUDF, Volatility, create_udf};
+
+/// The name DataFusion knows the Jaro-Winkler similarity by.
+pub(crate) const JARO_WINKLER: &str = "jaro_winkler";
+
+/// A scalar function giving the Jaro-Winkler similarity of two strings,
+/// between 0 and 1, or null if any of them is null.
+pub(crate) fn jaro_winkler_udf() -> ScalarUDF {
+    create_udf(
+        JARO_WINKLER,
+        vec![DataType::Utf8, DataType::Utf8],
+        DataType::Float64,
+        Volatility::Immutable,
+        Arc::new(|args: &[ColumnarValue]| {
+            let arrays = ColumnarValue::values_to_arrays(args)?;
+            let (left, right) = (arrays[0].as_string::<i32>(), arrays[1].as_string::<i32>());
+            let scores = left
+                .iter()
+                .zip(right.iter())
+                .map(|(l, r)| Some(strsim::jaro_winkler(l?, r?)))
+                .collect::<Float64Array>();
+            Ok(ColumnarValue::Array(Arc::new(scores)))
+        }),
+    )
+}
+
+/// A dataset of a join, with all its columns and the join key.
+pub(crate) struct JoinSide<'a> {
+    pub name: &'a str,
+    pub columns: Vec<String>,
+    pub key: &'a str,
+}
+
+/// Every pair of rows whose keys are at least `threshold` (0 to 100) similar,
+/// with the similarity alongside. Right columns clashing with left ones get
+/// a `_right` suffix.
+pub(crate) fn fuzzy_join_sql(
+    left: &JoinSide,
+    right: &JoinSide,
+    threshold: f64,
+    function: &str,
+) -> Result<String> {
+    for side in [left, right] {
+        if !side.columns.iter().any(|column| column == side.key) {
+            anyhow::bail!("Dataset {} has no column(s): {}", side.name, side.key);
+        }
+    }
+    let mut projection = left
+        .columns
+        .iter()
+        .map(|column| format!("l.\"{}\"", column))
+        .collect::<Vec<_>>();
+    projection.extend(right.columns.iter().map(|column| {
+        if left.columns.contains(column) {
+            format!("r.\"{0}\" AS \"{0}_right\"", column)
+        } else {
+            format!("r.\"{}\"", column)
+        }
+    }));
+    let similarity = format!(
+        "{}(CAST(l.\"{}\" AS VARCHAR), CAST(r.\"{}\" AS VARCHAR)) * 100",
+        function, left.key, right.key
+    );
+    Ok(format!(
+        "SELECT {0}, {1} AS similarity FROM {2} l CROSS JOIN {3} r WHERE {1} >= {4}",
+        projection.join(", "),
+        similarity,
+        left.name,
+        right.name,
+        threshold
+    ))
+}
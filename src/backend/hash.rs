@@ -0,0 +1,52 @@
+use anyhow::Result;
+use std::sync::Arc;
+
+use arrow::{
+    array::{Array, ArrayRef, BooleanArray, RecordBatch, new_null_array},
+    compute::{concat_batches, kernels::zip::zip},
+    datatypes::Schema,
+};
+use md5::Md5;
+use sha2::{Digest, Sha256};
+use xxhash_rust::xxh3::xxh3_64;
+
+use super::ipc_stream_bytes;
+use crate::HashAlgorithm;
+
+/// Hex digest of the batches serialized as an Arrow IPC stream, so the
+/// schema and the row order are part of the fingerprint. The rows are
+/// concatenated first, so how they were split into batches is not.
+pub(crate) fn hash_batches(
+    schema: &Schema,
+    batches: &[RecordBatch],
+    algorithm: HashAlgorithm,
+) -> Result<String> {
+    let schema = Arc::new(schema.clone());
+    let batch = concat_batches(&schema, batches)?;
+    let columns = batch
+        .columns()
+        .iter()
+        .map(clear_nulls)
+        .collect::<Result<Vec<_>>>()?;
+    let batch = RecordBatch::try_new(schema.clone(), columns)?;
+    let buf = ipc_stream_bytes(&schema, &[batch])?;
+    let digest = match algorithm {
+        HashAlgorithm::Md5 => format!("{:x}", Md5::digest(&buf)),
+        HashAlgorithm::Sha256 => format!("{:x}", Sha256::digest(&buf)),
+        HashAlgorithm::Xxhash => format!("{:016x}", xxh3_64(&buf)),
+    };
+    Ok(digest)
+}
+
+/// Zero the values under the nulls of an array, which some sources (e.g.
+/// duckdb) leave as whatever was in memory.
+fn clear_nulls(array: &ArrayRef) -> Result<ArrayRef> {
+    match array.nulls() {
+        Some(nulls) if nulls.null_count() > 0 => {
+            let valid = BooleanArray::new(nulls.inner().clone(), None);
+            let nulls = new_null_array(array.data_type(), array.len());
+            Ok(zip(&valid, array, &nulls)?)
+        }
+        _ => Ok(array.clone()),
+    }
+}
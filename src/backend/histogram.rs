@@ -0,0 +1,104 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use arrow::{
+    array::{Array, AsArray, Float64Array, Int64Array, RecordBatch},
+    datatypes::{DataType, Field, Float64Type, Int64Type, Schema},
+};
+
+/// Keep the finite values of a column as doubles. NaN sorts above every
+/// number, so the bounds leave it out along with the infinities.
+fn finite_values_sql(name: &str, column: &str) -> String {
+    format!(
+        "SELECT v FROM (SELECT CAST(\"{0}\" AS DOUBLE) AS v FROM {1}) AS t \
+        WHERE v BETWEEN {2:e} AND {3:e}",
+        column,
+        name,
+        f64::MIN,
+        f64::MAX
+    )
+}
+
+/// Query the minimum and maximum of the finite values of a numeric column.
+pub(crate) fn range_sql(name: &str, column: &str) -> String {
+    format!(
+        "SELECT MIN(v) AS lo, MAX(v) AS hi FROM ({}) AS t",
+        finite_values_sql(name, column)
+    )
+}
+
+/// Equal width bins spanning the range of a column.
+pub(crate) struct Bins {
+    min: f64,
+    max: f64,
+    width: f64,
+    count: usize,
+}
+
+impl Bins {
+    /// Split the range read by `range_sql`, `None` if the column has no
+    /// values. A column holding a single value gets a single bin.
+    pub(crate) fn from_range(batches: &[RecordBatch], count: usize) -> Option<Self> {
+        let batch = batches.iter().find(|batch| batch.num_rows() > 0)?;
+        let lo = batch.column(0).as_primitive::<Float64Type>();
+        let hi = batch.column(1).as_primitive::<Float64Type>();
+        if lo.is_null(0) || hi.is_null(0) {
+            return None;
+        }
+        let (min, max) = (lo.value(0), hi.value(0));
+        let count = if max > min { count.max(1) } else { 1 };
+        let width = if max > min {
+            (max - min) / count as f64
+        } else {
+            1.0
+        };
+        Some(Self {
+            min,
+            max,
+            width,
+            count,
+        })
+    }
+
+    /// Count the values of every non-empty bin. The maximum belongs to the
+    /// last bin rather than one of its own.
+    pub(crate) fn sql(&self, name: &str, column: &str) -> String {
+        format!(
+            "SELECT CAST(CASE WHEN v >= {max} THEN {last} ELSE FLOOR((v - {min}) / {width}) END AS BIGINT) AS bin, \
+            COUNT(*) AS \"count\" FROM ({values}) AS t GROUP BY bin ORDER BY bin",
+            max = self.max,
+            last = self.count - 1,
+            min = self.min,
+            width = self.width,
+            values = finite_values_sql(name, column),
+        )
+    }
+
+    /// One row per bin with its edges, the empty bins included.
+    pub(crate) fn batch(&self, batches: &[RecordBatch]) -> Result<RecordBatch> {
+        let mut counts = vec![0i64; self.count];
+        for batch in batches {
+            let bins = batch.column(0).as_primitive::<Int64Type>();
+            let values = batch.column(1).as_primitive::<Int64Type>();
+            for (bin, count) in bins.iter().zip(values.iter()) {
+                if let (Some(bin), Some(count)) = (bin, count) {
+                    counts[(bin.max(0) as usize).min(self.count - 1)] += count;
+                }
+            }
+        }
+        let edge = |i: usize| self.min + i as f64 * self.width;
+        let schema = Schema::new(vec![
+            Field::new("bin_start", DataType::Float64, false),
+            Field::new("bin_end", DataType::Float64, false),
+            Field::new("count", DataType::Int64, false),
+        ]);
+        Ok(RecordBatch::try_new(
+            Arc::new(schema),
+            vec![
+                Arc::new(Float64Array::from_iter_values((0..self.count).map(edge))),
+                Arc::new(Float64Array::from_iter_values((1..=self.count).map(edge))),
+                Arc::new(Int64Array::from(counts)),
+            ],
+        )?)
+    }
+}
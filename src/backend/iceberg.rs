@@ -0,0 +1,98 @@
+use std::{path::Path, sync::Arc};
+
+use anyhow::Result;
+use arrow::datatypes::Schema;
+use datafusion::{
+    catalog::TableProvider,
+    datasource::{
+        empty::EmptyTable,
+        file_format::parquet::ParquetFormat,
+        listing::{ListingOptions, ListingTable, ListingTableConfig, ListingTableUrl},
+    },
+};
+use futures::TryStreamExt;
+use iceberg_file_catalog::FileCatalog;
+use iceberg_rust::{
+    catalog::{Catalog, identifier::Identifier, tabular::Tabular},
+    object_store::ObjectStoreBuilder,
+    spec::{
+        manifest::{Content, Status},
+        util::strip_prefix,
+    },
+};
+
+use crate::IcebergOpts;
+
+/// Open a table of a file catalog, where `ns.table` lives under
+/// `<catalog_uri>/ns/table`, as of the given snapshot or the current one.
+/// The data files of the snapshot are read as a listing table, so tables
+/// with delete files are not supported.
+pub(crate) async fn iceberg_table(opts: &IcebergOpts) -> Result<Arc<dyn TableProvider>> {
+    let root = Path::new(&opts.catalog_uri);
+    if !root.is_dir() {
+        anyhow::bail!("Iceberg catalog {} not found", opts.catalog_uri);
+    }
+    let root = root.canonicalize()?;
+    let catalog = FileCatalog::new("/", ObjectStoreBuilder::filesystem(&root)).await?;
+    let identifier = Identifier::parse(&opts.table_identifier, None)?;
+    let table = match Arc::new(catalog).load_tabular(&identifier).await {
+        Ok(Tabular::Table(table)) => table,
+        Ok(_) => anyhow::bail!("{} is not an iceberg table", opts.table_identifier),
+        Err(e) => anyhow::bail!(
+            "Iceberg table {} not found in {}: {}",
+            opts.table_identifier,
+            opts.catalog_uri,
+            e
+        ),
+    };
+    let metadata = table.metadata();
+    let snapshot = match opts.snapshot_id {
+        Some(id) => Some(metadata.snapshots.get(&id).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Snapshot {} not found in iceberg table {}",
+                id,
+                opts.table_identifier
+            )
+        })?),
+        None => metadata.current_snapshot(None)?,
+    };
+    let schema = match snapshot {
+        Some(snapshot) => metadata.schema(*snapshot.snapshot_id())?,
+        None => metadata.current_schema(None)?,
+    };
+    let schema: Schema = (&**schema).try_into()?;
+    let schema = Arc::new(schema);
+
+    let Some(snapshot) = snapshot else {
+        return Ok(Arc::new(EmptyTable::new(schema)));
+    };
+    let manifests = table.manifests(None, Some(*snapshot.snapshot_id())).await?;
+    let entries: Vec<_> = table
+        .datafiles(&manifests, None, (None, None))
+        .await?
+        .try_collect()
+        .await?;
+    let mut urls = Vec::new();
+    for entry in entries {
+        if matches!(entry.status(), Status::Deleted) {
+            continue;
+        }
+        let data_file = entry.data_file();
+        if !matches!(data_file.content(), Content::Data) {
+            anyhow::bail!(
+                "Iceberg table {} has delete files, which are not supported",
+                opts.table_identifier
+            );
+        }
+        let path = root.join(strip_prefix(data_file.file_path()).trim_start_matches('/'));
+        urls.push(ListingTableUrl::parse(path.to_string_lossy())?);
+    }
+    if urls.is_empty() {
+        return Ok(Arc::new(EmptyTable::new(schema)));
+    }
+    let options = ListingOptions::new(Arc::new(ParquetFormat::default()));
+    let config = ListingTableConfig::new_with_multi_paths(urls)
+        .with_listing_options(options)
+        .with_schema(schema);
+    Ok(Arc::new(ListingTable::try_new(config)?))
+}
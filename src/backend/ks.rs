@@ -0,0 +1,100 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use arrow::{
+    array::{AsArray, Float64Array, Int64Array, RecordBatch},
+    datatypes::{DataType, Field, Float64Type, Int64Type, Schema},
+};
+
+/// The largest distance between the empirical CDFs of two columns, possibly
+/// of different datasets, along with the size of each sample.
+pub(crate) fn ks_sql(name1: &str, col1: &str, name2: &str, col2: &str) -> String {
+    let sample = |s: u8, name: &str, col: &str| {
+        format!(
+            "SELECT CAST(\"{0}\" AS DOUBLE) AS v, {1} AS s FROM {2} WHERE \"{0}\" IS NOT NULL",
+            col, s, name
+        )
+    };
+    let count = |s: u8, over: &str| {
+        format!(
+            "CAST(SUM(CASE WHEN s = {} THEN 1 ELSE 0 END) OVER ({}) AS DOUBLE)",
+            s, over
+        )
+    };
+    format!(
+        "SELECT MAX(ABS(c1 / n1 - c2 / n2)) AS statistic, CAST(MAX(n1) AS BIGINT) AS n1, \
+        CAST(MAX(n2) AS BIGINT) AS n2 FROM (SELECT {}, {}, {}, {} FROM ({} UNION ALL {}) AS pts) AS cdf",
+        count(1, "ORDER BY v") + " AS c1",
+        count(2, "ORDER BY v") + " AS c2",
+        count(1, "") + " AS n1",
+        count(2, "") + " AS n2",
+        sample(1, name1, col1),
+        sample(2, name2, col2),
+    )
+}
+
+/// Pair the statistic queried by `ks_sql` with its approximate p-value.
+pub(crate) fn ks_batch(batches: &[RecordBatch]) -> Result<RecordBatch> {
+    let batch = batches.iter().find(|batch| batch.num_rows() > 0);
+    let Some(batch) = batch.filter(|batch| !batch.column(0).is_null(0)) else {
+        anyhow::bail!("Both columns need values to compare their distributions");
+    };
+    let statistic = batch.column(0).as_primitive::<Float64Type>().value(0);
+    let n1 = batch.column(1).as_primitive::<Int64Type>().value(0);
+    let n2 = batch.column(2).as_primitive::<Int64Type>().value(0);
+    if n1 == 0 || n2 == 0 {
+        anyhow::bail!("Both columns need values to compare their distributions");
+    }
+    let p_value = ks_p_value(statistic, n1 as f64, n2 as f64);
+
+    let schema = Schema::new(vec![
+        Field::new("ks_statistic", DataType::Float64, false),
+        Field::new("p_value_approx", DataType::Float64, false),
+        Field::new("n1", DataType::Int64, false),
+        Field::new("n2", DataType::Int64, false),
+    ]);
+    Ok(RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(Float64Array::from(vec![statistic])),
+            Arc::new(Float64Array::from(vec![p_value])),
+            Arc::new(Int64Array::from(vec![n1])),
+            Arc::new(Int64Array::from(vec![n2])),
+        ],
+    )?)
+}
+
+/// The asymptotic Kolmogorov distribution with the small sample correction
+/// of Stephens, 1 when the series doesn't converge (tiny statistics).
+fn ks_p_value(statistic: f64, n1: f64, n2: f64) -> f64 {
+    let en = (n1 * n2 / (n1 + n2)).sqrt();
+    let lambda = (en + 0.12 + 0.11 / en) * statistic;
+    let mut sum = 0.0;
+    let mut sign = 1.0;
+    let mut previous = 0.0_f64;
+    for k in 1..=100 {
+        let k = k as f64;
+        let term = sign * 2.0 * (-2.0 * k * k * lambda * lambda).exp();
+        sum += term;
+        if term.abs() <= 1e-3 * previous || term.abs() <= 1e-8 * sum {
+            return sum.clamp(0.0, 1.0);
+        }
+        sign = -sign;
+        previous = term.abs();
+    }
+    1.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ks_p_value() {
+        assert_eq!(ks_p_value(0.0, 100.0, 100.0), 1.0);
+        assert!(ks_p_value(1.0, 100.0, 100.0) < 1e-10);
+        // the 5% critical value of two samples of 100 is about 0.19
+        let p = ks_p_value(0.19, 100.0, 100.0);
+        assert!((0.03..0.07).contains(&p), "p = {}", p);
+    }
+}
@@ -1,41 +1,306 @@
+mod audit;
+mod cache;
+mod correlate;
+mod crosstab;
 pub mod describe;
+#[cfg(feature = "duckdb")]
+pub mod duckdb;
+mod fuzzy;
+mod hash;
+mod histogram;
+mod iceberg;
+mod ks;
+mod outliers;
+mod stdin;
+mod stream;
 
-use std::ops::Deref;
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    hash::{DefaultHasher, Hash, Hasher},
+    io::Read,
+    ops::Deref,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
 
-use crate::{Backend, ConnectOpts, DatasetConn, ReplDisplay};
+use crate::{
+    Backend, CompressedParquetOpts, ConnectOpts, CorrelationMethod, DataFormat, DatasetConn,
+    DescribeOpts, ExplainFormat, HashAlgorithm, InferSchemaOpts, OutlierMethod, ParquetCompression,
+    ReplDisplay, Settings, TaotieConfig,
+};
 use anyhow::Result;
-use arrow::{array::RecordBatch, util::pretty::pretty_format_batches};
-use datafusion::prelude::{
-    CsvReadOptions, DataFrame, NdJsonReadOptions, SessionConfig, SessionContext,
+use arrow::{
+    array::{Array, AsArray, Float64Array, Int64Array, RecordBatch, StringArray},
+    compute::concat_batches,
+    datatypes::{DataType, Field, Float64Type, Schema, SchemaRef},
+    ipc::writer::StreamWriter,
+    json::ArrayWriter,
+    util::{
+        display::{ArrayFormatter, FormatOptions},
+        pretty::pretty_format_batches_with_options,
+    },
+};
+use audit::{null_audit_batch, null_audit_sql};
+use bytes::Bytes;
+use cache::StatsCache;
+use clap::ValueEnum;
+use correlate::{
+    correlation_batch, correlation_sql, target_correlation_batch, target_correlation_sql,
+};
+use crosstab::{crosstab_sql, crosstab_values_sql};
+use datafusion::{
+    catalog::{MemoryCatalogProvider, MemorySchemaProvider},
+    common::{ResolvedTableReference, TableReference},
+    datasource::{
+        MemTable,
+        file_format::{file_compression_type::FileCompressionType, parquet::ParquetFormat},
+        listing::{ListingOptions, ListingTable, ListingTableConfig, ListingTableUrl},
+    },
+    execution::runtime_env::{RuntimeEnv, RuntimeEnvBuilder},
+    functions_aggregate::expr_fn::approx_percentile_cont,
+    logical_expr::LogicalPlan,
+    physical_plan::{ExecutionPlan, collect, display::DisplayableExecutionPlan, displayable},
+    prelude::{
+        CsvReadOptions, DataFrame, NdJsonReadOptions, ParquetReadOptions, SessionConfig,
+        SessionContext, cast, ident, lit,
+    },
 };
 use describe::DataFrameDescriber;
+use fuzzy::{JARO_WINKLER, JoinSide, fuzzy_join_sql, jaro_winkler_udf};
+use hash::hash_batches;
+use histogram::{Bins, range_sql};
+use iceberg::iceberg_table;
+use ks::{ks_batch, ks_sql};
+use outliers::outliers_sql;
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use stdin::{read_piped, take_stdin};
+use stream::read_appended_rows;
+
+#[cfg(feature = "duckdb")]
+pub use self::duckdb::DuckdbBackend;
+
+/// How long, in seconds, previewed column values stay cached.
+const PREVIEW_CACHE_TTL: u64 = 10;
+
+/// The query engine which backs the REPL.
+#[derive(Debug, Clone, Copy, Default, ValueEnum)]
+pub enum BackendKind {
+    #[default]
+    Datafusion,
+    #[cfg(feature = "duckdb")]
+    Duckdb,
+}
 
-pub struct DatafusionBackend(SessionContext);
+pub struct DatafusionBackend {
+    ctx: SessionContext,
+    settings: Settings,
+    stats_cache: Mutex<StatsCache>,
+    /// The datasets held in memory rather than read from a source, with the
+    /// query they were created from, if any.
+    materialized: HashMap<ResolvedTableReference, Option<String>>,
+    /// How each connected dataset was registered.
+    registered_datasets: HashMap<ResolvedTableReference, ConnectOpts>,
+    /// How far each file streamed into a dataset was read.
+    stream_offsets: HashMap<(ResolvedTableReference, String), u64>,
+}
 
 impl DatafusionBackend {
     pub fn new() -> Self {
-        let mut config = SessionConfig::new();
-        config.options_mut().catalog.information_schema = true;
+        Self::with_config(&TaotieConfig::default()).expect("Failed to create datafusion backend")
+    }
+
+    pub fn with_config(config: &TaotieConfig) -> Result<Self> {
+        let mut session_config = SessionConfig::new();
+        session_config.options_mut().catalog.information_schema = true;
+        let mut runtime = RuntimeEnvBuilder::new();
+        if let Some(limit) = config.memory_limit {
+            runtime = runtime.with_memory_limit(limit, 1.0);
+        }
 
-        Self(SessionContext::new_with_config(config))
+        let mut settings = Settings::new();
+        settings.global_row_limit = config.global_row_limit;
+
+        let ctx = Self::session_context(session_config, runtime.build_arc()?);
+
+        Ok(Self {
+            ctx,
+            settings,
+            stats_cache: Mutex::new(StatsCache::new(config.cache_size)),
+            materialized: HashMap::new(),
+            registered_datasets: HashMap::new(),
+            stream_offsets: HashMap::new(),
+        })
     }
-}
 
-impl Backend for DatafusionBackend {
-    async fn connect(&mut self, opts: &ConnectOpts) -> Result<()> {
+    /// A session with the functions taotie adds to SQL registered.
+    fn session_context(config: SessionConfig, runtime: Arc<RuntimeEnv>) -> SessionContext {
+        let ctx = SessionContext::new_with_config_rt(config, runtime);
+        ctx.register_udf(jaro_winkler_udf());
+        ctx
+    }
+
+    /// Register a directory of parquet files, hive-style partitions (e.g.
+    /// `date=2024-01-01/part-0.parquet`) are added as columns. With schema
+    /// evolution the files may have different columns, missing ones read as
+    /// null.
+    async fn register_parquet_dir(
+        &self,
+        table: &ResolvedTableReference,
+        path: &str,
+        schema_evolution: bool,
+    ) -> Result<()> {
+        let state = self.ctx.state();
+        let table_path = ListingTableUrl::parse(path)?;
+        let options =
+            ListingOptions::new(Arc::new(ParquetFormat::default())).with_file_extension(".parquet");
+        let config = ListingTableConfig::new(table_path)
+            .with_listing_options(options)
+            .infer_partitions_from_path(&state)
+            .await?;
+        let options = config
+            .options
+            .ok_or_else(|| anyhow::anyhow!("No listing options for {}", path))?;
+        let partitions = options
+            .table_partition_cols
+            .iter()
+            .map(|(col, _)| (col.clone(), DataType::Utf8))
+            .collect();
+        let options = options.with_table_partition_cols(partitions);
+        let schema = if schema_evolution {
+            let schemas = parquet_files(Path::new(path))?
+                .iter()
+                .map(|file| {
+                    let builder = ParquetRecordBatchReaderBuilder::try_new(File::open(file)?)?;
+                    Ok(builder.schema().as_ref().clone())
+                })
+                .collect::<Result<Vec<_>>>()?;
+            Some(Arc::new(merge_schema(&schemas)?))
+        } else {
+            None
+        };
+        self.ctx
+            .register_listing_table(table.clone(), path, options, schema, None)
+            .await?;
+        Ok(())
+    }
+
+    /// Parquet files compressed as a whole (e.g. `data.parquet.gz`) are
+    /// decompressed into memory, as the parquet reader needs to seek the file.
+    fn register_compressed_parquet(
+        &self,
+        table: &ResolvedTableReference,
+        opts: &CompressedParquetOpts,
+    ) -> Result<()> {
+        let file = File::open(&opts.filename)?;
+        let mut reader: Box<dyn Read> = match opts.compression {
+            ParquetCompression::Gzip => FileCompressionType::GZIP.convert_read(file)?,
+            ParquetCompression::Bzip2 => FileCompressionType::BZIP2.convert_read(file)?,
+            ParquetCompression::Xz => FileCompressionType::XZ.convert_read(file)?,
+            ParquetCompression::Zstd => FileCompressionType::ZSTD.convert_read(file)?,
+            ParquetCompression::Lz4 => Box::new(lz4_flex::frame::FrameDecoder::new(file)),
+            ParquetCompression::Snappy => Box::new(snap::read::FrameDecoder::new(file)),
+        };
+        let mut buf = Vec::new();
+        reader.read_to_end(&mut buf)?;
+
+        let builder = ParquetRecordBatchReaderBuilder::try_new(Bytes::from(buf))?;
+        let schema = builder.schema().clone();
+        let batches = builder.build()?.collect::<Result<Vec<_>, _>>()?;
+        let provider = MemTable::try_new(schema, vec![batches])?;
+        self.ctx.register_table(table.clone(), Arc::new(provider))?;
+        Ok(())
+    }
+
+    /// Register a table without any row, so queries against its schema
+    /// could be planned and validated before there is data.
+    pub fn register_empty_table(&self, name: &str, schema: SchemaRef) -> Result<()> {
+        let table = MemTable::try_new(schema, vec![vec![]])?;
+        self.ctx.register_table(name, Arc::new(table))?;
+        self.stats_cache
+            .lock()
+            .unwrap()
+            .invalidate(&self.resolve(name).to_string());
+        Ok(())
+    }
+
+    /// Swap a dataset for in-memory batches. It no longer matches its source,
+    /// so it is kept like a materialized dataset rather than reconnected.
+    fn replace_table(
+        &mut self,
+        name: &str,
+        schema: SchemaRef,
+        batches: Vec<RecordBatch>,
+    ) -> Result<()> {
+        let table = MemTable::try_new(schema, vec![batches])?;
+        self.ctx.deregister_table(name)?;
+        self.ctx.register_table(name, Arc::new(table))?;
+        let resolved = self.resolve(name);
+        self.stats_cache
+            .lock()
+            .unwrap()
+            .invalidate(&resolved.to_string());
+        self.registered_datasets.remove(&resolved);
+        self.materialized.insert(resolved, None);
+        Ok(())
+    }
+
+    /// Piped input is parsed into memory, as it can't be read again.
+    fn register_piped<R: Read>(
+        &self,
+        table: impl Into<TableReference>,
+        reader: R,
+        extension: &str,
+    ) -> Result<()> {
+        let (schema, batches) = read_piped(reader, extension)?;
+        let provider = MemTable::try_new(schema, vec![batches])?;
+        self.ctx.register_table(table, Arc::new(provider))?;
+        Ok(())
+    }
+
+    /// Register a dataset from its source under a resolved name, so it lands
+    /// in the same catalog whichever one is in use.
+    async fn register_dataset(
+        &mut self,
+        table: ResolvedTableReference,
+        opts: &ConnectOpts,
+    ) -> Result<()> {
+        self.stats_cache
+            .lock()
+            .unwrap()
+            .invalidate(&table.to_string());
+        self.materialized.remove(&table);
         match &opts.conn {
+            DatasetConn::Parquet(path) if Path::new(path).is_dir() => {
+                self.register_parquet_dir(&table, path, opts.schema_evolution)
+                    .await?;
+            }
+            DatasetConn::Parquet(path) if opts.schema_evolution => {
+                anyhow::bail!(
+                    "Schema evolution merges the files of a parquet directory, {} is a single file",
+                    path
+                );
+            }
             DatasetConn::Parquet(path) => {
-                self.register_parquet(&opts.name, path, Default::default())
+                self.register_parquet(table.clone(), path, Default::default())
                     .await?;
             }
+            DatasetConn::CompressedParquet(file_opts) => {
+                self.register_compressed_parquet(&table, file_opts)?;
+            }
             DatasetConn::Postgres(_) => todo!(),
+            DatasetConn::Iceberg(iceberg) => {
+                self.ctx
+                    .register_table(table.clone(), iceberg_table(iceberg).await?)?;
+            }
             DatasetConn::Csv(file_opts) => {
                 let options = CsvReadOptions {
                     file_extension: &file_opts.extension,
                     file_compression_type: file_opts.compression,
                     ..Default::default()
                 };
-                self.register_csv(&opts.name, &file_opts.filename, options)
+                self.register_csv(table.clone(), &file_opts.filename, options)
                     .await?;
             }
             DatasetConn::Json(file_opts) => {
@@ -44,42 +309,932 @@ impl Backend for DatafusionBackend {
                     file_compression_type: file_opts.compression,
                     ..Default::default()
                 };
-                self.register_json(&opts.name, &file_opts.filename, options)
+                self.register_json(table.clone(), &file_opts.filename, options)
                     .await?;
             }
+            DatasetConn::Stdin(file_opts) => {
+                self.register_piped(table.clone(), take_stdin()?, &file_opts.extension)?;
+                // kept like a materialized dataset, so vacuum doesn't read stdin again
+                self.materialized.insert(table, None);
+                return Ok(());
+            }
+        }
+        self.registered_datasets.insert(table, opts.clone());
+        Ok(())
+    }
+
+    /// Catalogs are schemas of the default DataFusion catalog, created on
+    /// first use.
+    fn ensure_schema(&self, schema: &str) -> Result<()> {
+        let catalog_name = self.current_catalog();
+        let catalog = self
+            .ctx
+            .catalog(&catalog_name)
+            .ok_or_else(|| anyhow::anyhow!("Catalog {} not found", catalog_name))?;
+        if catalog.schema(schema).is_none() {
+            catalog.register_schema(schema, Arc::new(MemorySchemaProvider::new()))?;
+        }
+        Ok(())
+    }
+
+    fn current_catalog(&self) -> String {
+        self.ctx
+            .state()
+            .config()
+            .options()
+            .catalog
+            .default_catalog
+            .clone()
+    }
+
+    /// The schema which unqualified names are resolved in.
+    fn current_schema(&self) -> String {
+        self.ctx
+            .state()
+            .config()
+            .options()
+            .catalog
+            .default_schema
+            .clone()
+    }
+
+    /// Keep the result of a query in memory as a dataset, along with the
+    /// query it was created from.
+    async fn materialize(&mut self, name: &str, query: &str, df: DataFrame) -> Result<()> {
+        let schema = df.schema().as_arrow().clone();
+        let batches = df.collect().await?;
+        let table = MemTable::try_new(Arc::new(schema), vec![batches])?;
+        self.ctx.register_table(name, Arc::new(table))?;
+        let resolved = self.resolve(name);
+        self.stats_cache
+            .lock()
+            .unwrap()
+            .invalidate(&resolved.to_string());
+        self.materialized.insert(resolved, Some(query.to_string()));
+        Ok(())
+    }
+
+    /// A session over the same tables which reads every source as a single
+    /// partition, so rows come, and are numbered, in the order the source
+    /// holds them.
+    fn ordered_context(&self) -> SessionContext {
+        let mut state = self.ctx.state();
+        state.config_mut().options_mut().execution.target_partitions = 1;
+        SessionContext::new_with_state(state)
+    }
+
+    /// A dataset name resolved against the current catalog and schema, so it
+    /// keeps naming the same table once another catalog is in use.
+    fn resolve(&self, name: &str) -> ResolvedTableReference {
+        TableReference::from(name).resolve(&self.current_catalog(), &self.current_schema())
+    }
+}
+
+impl Backend for DatafusionBackend {
+    async fn connect(&mut self, opts: &ConnectOpts) -> Result<()> {
+        if let Some(catalog) = &opts.catalog {
+            self.ensure_schema(catalog)?;
+        }
+        let table = self.resolve(&opts.table_ref());
+        self.register_dataset(table, opts).await
+    }
+
+    async fn connection_info(&self, name: &str) -> anyhow::Result<ConnectOpts> {
+        self.registered_datasets
+            .get(&self.resolve(name))
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("Dataset {} was not registered with connect", name))
+    }
+
+    async fn create_table_from_sql(&mut self, name: &str, query: &str) -> anyhow::Result<()> {
+        let df = self.ctx.sql(query).await?;
+        self.materialize(name, query, df).await
+    }
+
+    async fn create_table_from_ordered_sql(
+        &mut self,
+        name: &str,
+        query: &str,
+    ) -> anyhow::Result<()> {
+        let df = self.ordered_context().sql(query).await?;
+        self.materialize(name, query, df).await
+    }
+
+    async fn cast_schema(
+        &mut self,
+        name: &str,
+        casts: &[(String, String)],
+        strict: bool,
+    ) -> anyhow::Result<()> {
+        let all = self.column_names(name).await?;
+        let sql = cast_schema_sql(name, &all, casts, strict)?;
+        let df = self.ctx.sql(&sql).await?;
+        let schema = Arc::new(df.schema().as_arrow().clone());
+        let batches = df.collect().await?;
+        self.replace_table(name, schema, batches)
+    }
+
+    async fn column_histogram(
+        &self,
+        name: &str,
+        column: &str,
+        bins: usize,
+    ) -> anyhow::Result<RecordBatch> {
+        let range = self
+            .ctx
+            .sql(&range_sql(name, column))
+            .await?
+            .collect()
+            .await?;
+        let Some(bins) = Bins::from_range(&range, bins) else {
+            anyhow::bail!("Column {} of dataset {} has no values", column, name);
+        };
+        let batches = self
+            .ctx
+            .sql(&bins.sql(name, column))
+            .await?
+            .collect()
+            .await?;
+        bins.batch(&batches)
+    }
+
+    async fn column_types_summary(&self, name: &str) -> anyhow::Result<RecordBatch> {
+        let schema = self.ctx.table_provider(name).await?.schema();
+        type_summary_batch(&schema)
+    }
+
+    async fn compare_distributions(
+        &self,
+        name1: &str,
+        col1: &str,
+        name2: &str,
+        col2: &str,
+    ) -> anyhow::Result<RecordBatch> {
+        let sql = ks_sql(name1, col1, name2, col2);
+        let batches = self.ctx.sql(&sql).await?.collect().await?;
+        ks_batch(&batches)
+    }
+
+    async fn compute_hash(&self, name: &str, algorithm: HashAlgorithm) -> anyhow::Result<String> {
+        // a single partition read in order, so the rows always come in the
+        // same order
+        let df = self.ordered_context().table(name).await?;
+        let schema = df.schema().as_arrow().clone();
+        let batches = df.collect_partitioned().await?.concat();
+        hash_batches(&schema, &batches, algorithm)
+    }
+
+    async fn correlate(
+        &self,
+        name: &str,
+        method: CorrelationMethod,
+    ) -> anyhow::Result<RecordBatch> {
+        let columns = self.numeric_columns(name).await?;
+        if columns.is_empty() {
+            anyhow::bail!("Dataset {} has no numeric columns", name);
+        }
+        let batches = self
+            .ctx
+            .sql(&correlation_sql(name, &columns, method))
+            .await?
+            .collect()
+            .await?;
+        correlation_batch(&columns, method, &batches)
+    }
+
+    async fn cross_tabulate(
+        &self,
+        name: &str,
+        row_col: &str,
+        col_col: &str,
+    ) -> anyhow::Result<RecordBatch> {
+        let batches = self
+            .ctx
+            .sql(&crosstab_values_sql(name, col_col))
+            .await?
+            .collect()
+            .await?;
+        let values = column_values(&batches)?;
+        if values.is_empty() {
+            anyhow::bail!("Column {} of dataset {} has no values", col_col, name);
+        }
+        let df = self
+            .ctx
+            .sql(&crosstab_sql(name, row_col, col_col, &values))
+            .await?;
+        let schema = Arc::new(df.schema().as_arrow().clone());
+        Ok(concat_batches(&schema, &df.collect().await?)?)
+    }
+
+    async fn count_nulls(&self, name: &str) -> anyhow::Result<RecordBatch> {
+        let schema = self.ctx.table(name).await?.schema().as_arrow().clone();
+        let batches = self
+            .ctx
+            .sql(&null_audit_sql(name, &schema))
+            .await?
+            .collect()
+            .await?;
+        null_audit_batch(&schema, &batches)
+    }
+
+    async fn deduplicate(
+        &mut self,
+        name: &str,
+        key: &[String],
+        keep_last: bool,
+        output: Option<&str>,
+    ) -> anyhow::Result<usize> {
+        let all = self.column_names(name).await?;
+        let sql = deduplicate_sql(name, &all, key, keep_last)?;
+        // which row comes first depends on the order rows are numbered in
+        let df = self.ordered_context().sql(&sql).await?;
+        let schema = Arc::new(df.schema().as_arrow().clone());
+        let batches = df.collect().await?;
+        let rows = batches.iter().map(|batch| batch.num_rows()).sum();
+        self.replace_table(output.unwrap_or(name), schema, batches)?;
+        Ok(rows)
+    }
+
+    async fn describe(&self, opts: &DescribeOpts) -> anyhow::Result<impl ReplDisplay> {
+        let footer = opts
+            .sample_size
+            .map(|size| format!("(sampled: {} rows)", size));
+        // sampled statistics are random, so they are never cached
+        let use_cache = !opts.no_cache && opts.sample_size.is_none();
+        let hash = describe_hash(opts);
+        let ttl = Duration::from_secs(self.settings.describe_cache_ttl);
+        let key = self.resolve(&opts.name).to_string();
+        if use_cache {
+            let cache = self.stats_cache.lock().unwrap();
+            if let Some(batches) = cache.get(&key, hash, ttl, Instant::now()) {
+                return Ok(WithFooter::new(batches, footer));
+            }
+        }
+
+        let projection = describe_projection(self, opts).await?;
+        let mut sql = format!("SELECT {} FROM {}", projection, opts.name);
+        if let Some(predicate) = &opts.predicate {
+            sql = format!("{} WHERE {}", sql, predicate);
+        }
+        if let Some(size) = opts.sample_size {
+            sql = format!("SELECT * FROM ({} ORDER BY random() LIMIT {})", sql, size);
+        }
+        let mut df = self.ctx.sql(&sql).await?;
+        if let Some(predicate) = &opts.predicate {
+            df.clone()
+                .limit(0, Some(0))?
+                .collect()
+                .await
+                .map_err(|e| anyhow::anyhow!("Invalid predicate '{}': {}", predicate, e))?;
+        }
+        if opts.sample_size.is_some() {
+            // keep the sample fixed while the statistics are computed
+            df = df.cache().await?;
+        }
+        let mut describe_df = DataFrameDescriber::try_new(df)?;
+        if opts.text_only {
+            describe_df = describe_df.with_methods(DataFrameDescriber::string_methods());
+        }
+        let describe_df = if opts.chunked {
+            describe_df.describe_chunked().await?
+        } else {
+            describe_df.describe().await?
+        };
+        let batches = describe_df.collect().await?;
+        if use_cache {
+            self.stats_cache
+                .lock()
+                .unwrap()
+                .insert(&key, hash, batches.clone(), Instant::now());
+        }
+        Ok(WithFooter::new(batches, footer))
+    }
+
+    async fn describe_sample(
+        &self,
+        name: &str,
+        sample_size: usize,
+    ) -> anyhow::Result<impl ReplDisplay> {
+        let rows = self.ctx.table(name).await?.count().await?;
+        let opts = DescribeOpts::new(name.to_string())
+            .with_sample_size((rows > sample_size).then_some(sample_size));
+        let batches = self.describe(&opts).await?.into_batches().await?;
+        Ok(WithFooter::new(
+            batches,
+            Some(sample_label(sample_size, rows)),
+        ))
+    }
+
+    async fn detect_schema_changes(&self, name: &str) -> anyhow::Result<RecordBatch> {
+        let opts = self.connection_info(name).await?;
+        // read the source again in a scratch session, leaving ours untouched
+        let mut scratch = DatafusionBackend::new();
+        scratch.connect(&opts).await?;
+        let source = scratch.ctx.table_provider(opts.table_ref()).await?.schema();
+        let current = self.ctx.table_provider(name).await?.schema();
+        schema_diff(&current, &source)
+    }
+
+    async fn drop_all(&mut self) -> anyhow::Result<usize> {
+        let sql = user_tables_sql(
+            &quote_literal(&self.current_catalog()),
+            &quote_literal(&self.current_schema()),
+        );
+        let tables = user_tables(&self.ctx.sql(&sql).await?.collect().await?);
+        for (name, _) in &tables {
+            self.drop_table(name).await?;
         }
+        self.stats_cache.lock().unwrap().clear();
+        Ok(tables.len())
+    }
+
+    async fn drop_table(&mut self, name: &str) -> anyhow::Result<()> {
+        let resolved = self.resolve(name);
+        self.ctx.deregister_table(name)?;
+        self.stats_cache
+            .lock()
+            .unwrap()
+            .invalidate(&resolved.to_string());
+        self.materialized.remove(&resolved);
+        self.registered_datasets.remove(&resolved);
+        self.stream_offsets
+            .retain(|(table, _), _| *table != resolved);
         Ok(())
     }
 
-    async fn describe(&self, name: &str) -> anyhow::Result<impl ReplDisplay> {
-        let df = self.0.sql(&format!("SELECT * FROM {}", name)).await?;
-        let describe_df = DataFrameDescriber::try_new(df)?;
-        let describe_df = describe_df.describe().await?;
-        Ok(describe_df)
+    async fn explain_analyze(&self, sql: &str) -> anyhow::Result<String> {
+        let df = self.ctx.sql(sql).await?;
+        let task_ctx = df.task_ctx();
+        let plan = df.create_physical_plan().await?;
+        collect(plan.clone(), Arc::new(task_ctx)).await?;
+        PlanDisplay::analyzed(plan.as_ref())
+            .display(&self.settings)
+            .await
+    }
+
+    async fn explain_dot(&self, sql: &str) -> anyhow::Result<String> {
+        let plan = self.ctx.sql(sql).await?.create_physical_plan().await?;
+        PlanDisplay::new(plan.as_ref(), ExplainFormat::Dot)
+            .display(&self.settings)
+            .await
     }
 
-    async fn head(&self, name: &str, size: usize) -> anyhow::Result<impl ReplDisplay> {
+    async fn frequency_table(
+        &self,
+        name: &str,
+        column: &str,
+        normalize: bool,
+    ) -> anyhow::Result<impl ReplDisplay> {
         let df = self
-            .0
-            .sql(&format!("SELECT * FROM {} LIMIT {}", name, size))
+            .ctx
+            .sql(&frequency_sql(name, column, normalize))
             .await?;
         Ok(df)
     }
 
-    async fn list(&self) -> anyhow::Result<impl ReplDisplay> {
-        let df = self.0.sql("SELECT table_name, table_type FROM information_schema.tables WHERE table_schema = 'public'").await?;
+    async fn fuzzy_join(
+        &mut self,
+        left: &str,
+        right: &str,
+        left_col: &str,
+        right_col: &str,
+        threshold: f64,
+        output: &str,
+    ) -> anyhow::Result<usize> {
+        let left = JoinSide {
+            name: left,
+            columns: self.column_names(left).await?,
+            key: left_col,
+        };
+        let right = JoinSide {
+            name: right,
+            columns: self.column_names(right).await?,
+            key: right_col,
+        };
+        let sql = fuzzy_join_sql(&left, &right, threshold, JARO_WINKLER)?;
+        self.create_table_from_sql(output, &sql).await?;
+        Ok(self.ctx.table(output).await?.count().await?)
+    }
+
+    async fn generate_series(&mut self, name: &str, batch: RecordBatch) -> anyhow::Result<()> {
+        let table = MemTable::try_new(batch.schema(), vec![vec![batch]])?;
+        self.ctx.register_table(name, Arc::new(table))?;
+        let resolved = self.resolve(name);
+        self.stats_cache
+            .lock()
+            .unwrap()
+            .invalidate(&resolved.to_string());
+        self.materialized.insert(resolved, None);
+        Ok(())
+    }
+
+    async fn head(
+        &self,
+        name: &str,
+        size: usize,
+        offset: usize,
+    ) -> anyhow::Result<impl ReplDisplay> {
+        let size = match self.settings.global_row_limit {
+            Some(limit) => size.min(limit),
+            None => size,
+        };
+        let df = self
+            .ctx
+            .sql(&format!(
+                "SELECT * FROM {} LIMIT {} OFFSET {}",
+                name, size, offset
+            ))
+            .await?;
         Ok(df)
     }
 
+    async fn infer_schema(&self, opts: &InferSchemaOpts) -> anyhow::Result<RecordBatch> {
+        // a scratch context, so the file is never registered in the session
+        let ctx = SessionContext::new();
+        let extension = Path::new(&opts.file)
+            .extension()
+            .map(|ext| format!(".{}", ext.to_string_lossy()))
+            .unwrap_or_default();
+        match opts.format {
+            DataFormat::Csv => {
+                let options = CsvReadOptions {
+                    has_header: !opts.no_header,
+                    file_extension: &extension,
+                    ..Default::default()
+                };
+                ctx.register_csv("infer", &opts.file, options).await?;
+            }
+            DataFormat::Json => {
+                let options = NdJsonReadOptions {
+                    file_extension: &extension,
+                    ..Default::default()
+                };
+                ctx.register_json("infer", &opts.file, options).await?;
+            }
+            DataFormat::Parquet => {
+                let options = ParquetReadOptions {
+                    file_extension: &extension,
+                    ..Default::default()
+                };
+                ctx.register_parquet("infer", &opts.file, options).await?;
+            }
+        }
+        let schema = ctx.table_provider("infer").await?.schema();
+        schema_batch(&schema)
+    }
+
+    async fn ingest_streaming(&mut self, name: &str, file: &str) -> anyhow::Result<usize> {
+        let key = (self.resolve(name), file.to_string());
+        let connected_from_file = matches!(
+            self.registered_datasets.get(&key.0).map(|opts| &opts.conn),
+            Some(DatasetConn::Csv(file_opts)) if file_opts.filename == file
+        );
+        if connected_from_file && !self.stream_offsets.contains_key(&key) {
+            // the dataset reads the whole file when queried, so it is swapped
+            // for the rows read so far and only later rows are streamed
+            let schema = self.ctx.table_provider(name).await?.schema();
+            let (batches, offset) = read_appended_rows(file, 0, schema.clone())?;
+            self.replace_table(name, schema, batches)?;
+            self.stream_offsets.insert(key.clone(), offset);
+        }
+
+        let offset = self.stream_offsets.get(&key).copied().unwrap_or_default();
+        let provider = self.ctx.table_provider(name).await?;
+        let schema = provider.schema();
+        let (appended, offset) = read_appended_rows(file, offset, schema.clone())?;
+        self.stream_offsets.insert(key, offset);
+        let rows = appended.iter().map(|batch| batch.num_rows()).sum();
+        if rows == 0 {
+            return Ok(0);
+        }
+
+        let mut batches = self.ctx.read_table(provider)?.collect().await?;
+        batches.extend(appended);
+        self.replace_table(name, schema, batches)?;
+        Ok(rows)
+    }
+
+    async fn list(
+        &self,
+        with_schema: bool,
+        catalog: Option<&str>,
+    ) -> anyhow::Result<impl ReplDisplay> {
+        let schema_name = catalog
+            .map(|catalog| catalog.to_string())
+            .unwrap_or_else(|| self.current_schema());
+        let current_catalog = self.current_catalog();
+        let materialized = self
+            .materialized
+            .keys()
+            .filter(|table| *table.catalog == current_catalog)
+            .map(|table| (&*table.schema, &*table.table));
+        let df = self
+            .ctx
+            .sql(&format!(
+                "SELECT table_name, {} FROM information_schema.tables \
+                WHERE table_catalog = {} AND table_schema = {}",
+                table_type_sql(materialized),
+                quote_literal(&current_catalog),
+                quote_literal(&schema_name)
+            ))
+            .await?;
+        if !with_schema {
+            return Ok(df);
+        }
+
+        let mut names = Vec::new();
+        let mut types = Vec::new();
+        let mut columns = Vec::new();
+        for batch in df.collect().await? {
+            let name_col = batch.column(0).as_string::<i32>();
+            let type_col = batch.column(1).as_string::<i32>();
+            for i in 0..batch.num_rows() {
+                let name = name_col.value(i);
+                let table = self
+                    .ctx
+                    .table(TableReference::partial(schema_name.as_str(), name))
+                    .await?;
+                let fields = table
+                    .schema()
+                    .fields()
+                    .iter()
+                    .map(|f| format!("{}: {}", f.name(), f.data_type()))
+                    .collect::<Vec<_>>();
+                names.push(name.to_string());
+                types.push(type_col.value(i).to_string());
+                columns.push(fields.join(", "));
+            }
+        }
+
+        let schema = Schema::new(vec![
+            Field::new("table_name", DataType::Utf8, false),
+            Field::new("table_type", DataType::Utf8, false),
+            Field::new("columns", DataType::Utf8, false),
+        ]);
+        let batch = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![
+                Arc::new(StringArray::from(names)),
+                Arc::new(StringArray::from(types)),
+                Arc::new(StringArray::from(columns)),
+            ],
+        )?;
+        Ok(self.ctx.read_batch(batch)?)
+    }
+
+    async fn column_correlation_with_target(
+        &self,
+        name: &str,
+        target: &str,
+        method: CorrelationMethod,
+    ) -> anyhow::Result<RecordBatch> {
+        let features = self
+            .numeric_columns(name)
+            .await?
+            .into_iter()
+            .filter(|column| column != target)
+            .collect::<Vec<_>>();
+        if features.is_empty() {
+            anyhow::bail!("Dataset {} has no numeric columns besides {}", name, target);
+        }
+        let batches = self
+            .ctx
+            .sql(&target_correlation_sql(name, target, &features, method))
+            .await?
+            .collect()
+            .await?;
+        target_correlation_batch(&features, &batches)
+    }
+
+    async fn column_names(&self, name: &str) -> anyhow::Result<Vec<String>> {
+        let schema = self.ctx.table_provider(name).await?.schema();
+        Ok(field_names(&schema))
+    }
+
+    async fn column_type(&self, name: &str, column: &str) -> anyhow::Result<DataType> {
+        let schema = self.ctx.table_provider(name).await?.schema();
+        Ok(schema.field_with_name(column)?.data_type().clone())
+    }
+
+    async fn numeric_columns(&self, name: &str) -> anyhow::Result<Vec<String>> {
+        let schema = self.ctx.table_provider(name).await?.schema();
+        Ok(numeric_fields(&schema))
+    }
+
+    async fn quantiles(
+        &self,
+        name: &str,
+        column: &str,
+        quantiles: &[f64],
+    ) -> anyhow::Result<RecordBatch> {
+        let exprs = quantiles
+            .iter()
+            .enumerate()
+            .map(|(i, q)| {
+                approx_percentile_cont(
+                    cast(ident(column), DataType::Float64),
+                    lit(*q),
+                    Some(lit(100)),
+                )
+                .alias(format!("q{}", i))
+            })
+            .collect::<Vec<_>>();
+        let batches = self
+            .ctx
+            .table(name)
+            .await?
+            .aggregate(vec![], exprs)?
+            .collect()
+            .await?;
+        quantiles_batch(quantiles, &batches)
+    }
+
+    async fn register_schema(&mut self, name: &str, schema: SchemaRef) -> anyhow::Result<()> {
+        self.register_empty_table(name, schema)?;
+        self.materialized.insert(self.resolve(name), None);
+        Ok(())
+    }
+
+    async fn row_at(&self, name: &str, index: usize) -> anyhow::Result<Option<RecordBatch>> {
+        let df = self
+            .ctx
+            .sql(&format!("SELECT * FROM {} LIMIT 1 OFFSET {}", name, index))
+            .await?;
+        let batches = df.collect().await?;
+        Ok(batches.into_iter().find(|batch| batch.num_rows() > 0))
+    }
+
     async fn schema(&self, name: &str) -> anyhow::Result<impl ReplDisplay> {
-        let df = self.0.sql(&format!("DESCRIBE {}", name)).await?;
+        let df = self.ctx.sql(&format!("DESCRIBE {}", name)).await?;
         Ok(df)
     }
 
+    async fn source_query(&self, name: &str) -> anyhow::Result<Option<String>> {
+        Ok(self
+            .materialized
+            .get(&self.resolve(name))
+            .cloned()
+            .flatten())
+    }
+
     async fn sql(&self, sql: &str) -> anyhow::Result<impl ReplDisplay> {
-        let df = self.0.sql(sql).await?;
+        // classified before running it, as DDL runs as soon as it is planned
+        let plan = self.ctx.state().create_logical_plan(sql).await?;
+        let returns_rows = !matches!(
+            plan,
+            LogicalPlan::Ddl(_)
+                | LogicalPlan::Dml(_)
+                | LogicalPlan::Copy(_)
+                | LogicalPlan::Statement(_)
+        );
+        if !returns_rows {
+            // the statement may change any table, so no statistic can be trusted
+            self.stats_cache.lock().unwrap().clear();
+        }
+        let df = self.ctx.execute_logical_plan(plan).await?;
+        match self.settings.global_row_limit {
+            // capping the planned query leaves any limit of its own, even in
+            // a subquery, as it is
+            Some(limit) if returns_rows => Ok(df.limit(0, Some(limit))?),
+            _ => Ok(df),
+        }
+    }
+
+    async fn string_columns(&self, name: &str) -> anyhow::Result<Vec<String>> {
+        let schema = self.ctx.table_provider(name).await?.schema();
+        Ok(string_fields(&schema))
+    }
+
+    async fn table_exists(&self, name: &str) -> anyhow::Result<bool> {
+        Ok(self.ctx.table_provider(name).await.is_ok())
+    }
+
+    async fn table_names(&self) -> anyhow::Result<Vec<String>> {
+        let batches = self
+            .ctx
+            .sql(&format!(
+                "SELECT table_name FROM information_schema.tables WHERE table_schema = {} ORDER BY table_name",
+                quote_literal(&self.current_schema())
+            ))
+            .await?
+            .collect()
+            .await?;
+        let names = batches
+            .iter()
+            .flat_map(|batch| batch.column(0).as_string::<i32>().iter().flatten())
+            .map(|name| name.to_string())
+            .collect();
+        Ok(names)
+    }
+
+    async fn text_search(
+        &self,
+        name: &str,
+        columns: &[String],
+        query: &str,
+        case_sensitive: bool,
+        limit: usize,
+    ) -> anyhow::Result<impl ReplDisplay> {
+        let sql = text_search_sql(name, columns, query, case_sensitive, limit);
+        let df = self.ctx.sql(&sql).await?;
+        Ok(df)
+    }
+
+    async fn transpose(&self, name: &str, row: usize) -> anyhow::Result<Option<RecordBatch>> {
+        let batch = self.row_at(name, row).await?;
+        batch.map(|batch| transpose_batch(&batch)).transpose()
+    }
+
+    async fn use_catalog(&mut self, catalog: &str) -> anyhow::Result<()> {
+        self.ensure_schema(catalog)?;
+        self.ctx
+            .sql(&format!(
+                "SET datafusion.catalog.default_schema = {}",
+                quote_literal(catalog)
+            ))
+            .await?;
+        Ok(())
+    }
+
+    async fn vacuum(&mut self) -> anyhow::Result<usize> {
+        let mut freed = self.stats_cache.lock().unwrap().clear();
+        let state = self.ctx.state();
+
+        // every table is kept, the materialized ones are compacted into a
+        // single batch and the connected datasets are registered again from
+        // their source
+        let ctx = Self::session_context(state.config().clone(), state.runtime_env().clone());
+        for catalog_name in self.ctx.catalog_names() {
+            let Some(catalog) = self.ctx.catalog(&catalog_name) else {
+                continue;
+            };
+            let new_catalog = match ctx.catalog(&catalog_name) {
+                Some(new_catalog) => new_catalog,
+                None => {
+                    let new_catalog = Arc::new(MemoryCatalogProvider::new());
+                    ctx.register_catalog(&catalog_name, new_catalog.clone());
+                    new_catalog
+                }
+            };
+            for schema_name in catalog.schema_names() {
+                let Some(schema) = catalog.schema(&schema_name) else {
+                    continue;
+                };
+                let new_schema = match new_catalog.schema(&schema_name) {
+                    Some(new_schema) => new_schema,
+                    None => {
+                        let new_schema = Arc::new(MemorySchemaProvider::new());
+                        new_catalog.register_schema(&schema_name, new_schema.clone())?;
+                        new_schema
+                    }
+                };
+                for table_name in schema.table_names() {
+                    let table = TableReference::full(
+                        catalog_name.as_str(),
+                        schema_name.as_str(),
+                        table_name.as_str(),
+                    )
+                    .resolve("", "");
+                    if self.registered_datasets.contains_key(&table) {
+                        continue;
+                    }
+                    let Some(mut provider) = schema.table(&table_name).await? else {
+                        continue;
+                    };
+                    if self.materialized.contains_key(&table) && provider.as_any().is::<MemTable>()
+                    {
+                        let batches = self.ctx.read_table(provider.clone())?.collect().await?;
+                        let size = batches
+                            .iter()
+                            .map(|batch| batch.get_array_memory_size())
+                            .sum::<usize>();
+                        let batch = concat_batches(&provider.schema(), &batches)?;
+                        freed += size.saturating_sub(batch.get_array_memory_size());
+                        provider =
+                            Arc::new(MemTable::try_new(provider.schema(), vec![vec![batch]])?);
+                    }
+                    new_schema.register_table(table_name, provider)?;
+                }
+            }
+        }
+
+        self.ctx = ctx;
+        let datasets = self
+            .registered_datasets
+            .iter()
+            .map(|(table, opts)| (table.clone(), opts.clone()))
+            .collect::<Vec<_>>();
+        for (table, opts) in datasets {
+            self.register_dataset(table, &opts).await?;
+        }
+        Ok(freed)
+    }
+
+    async fn validate_sql(&self, sql: &str) -> anyhow::Result<String> {
+        let state = self.ctx.state();
+        let plan = state.create_logical_plan(sql).await?;
+        let plan = state.optimize(&plan)?;
+        Ok(plan.display_indent().to_string())
+    }
+
+    async fn outliers(
+        &self,
+        name: &str,
+        column: &str,
+        method: OutlierMethod,
+    ) -> anyhow::Result<impl ReplDisplay> {
+        let sql = outliers_sql(name, column, method, "approx_percentile_cont");
+        let df = self.ctx.sql(&sql).await?;
         Ok(df)
     }
+
+    async fn partition_info(&self, name: &str) -> anyhow::Result<RecordBatch> {
+        let provider = self.ctx.table_provider(name).await?;
+        let keys = provider
+            .as_any()
+            .downcast_ref::<ListingTable>()
+            .map(|table| {
+                table
+                    .options()
+                    .table_partition_cols
+                    .iter()
+                    .map(|(col, _)| col.clone())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        if keys.is_empty() {
+            anyhow::bail!("Dataset {} is not partitioned", name);
+        }
+        let df = self.ctx.sql(&partition_info_sql(name, &keys)).await?;
+        let schema = Arc::new(df.schema().as_arrow().clone());
+        Ok(concat_batches(&schema, &df.collect().await?)?)
+    }
+
+    async fn pivot_longer(
+        &self,
+        name: &str,
+        id_cols: &[String],
+        value_cols: &[String],
+        variable_col: &str,
+        value_col: &str,
+    ) -> anyhow::Result<impl ReplDisplay> {
+        let all = self.column_names(name).await?;
+        let sql = pivot_longer_sql(name, &all, id_cols, value_cols, variable_col, value_col)?;
+        Ok(self.ctx.sql(&sql).await?)
+    }
+
+    async fn preview_values(
+        &self,
+        name: &str,
+        column: &str,
+        limit: usize,
+    ) -> anyhow::Result<Vec<String>> {
+        let hash = preview_hash(column, limit);
+        let ttl = Duration::from_secs(PREVIEW_CACHE_TTL);
+        let key = self.resolve(name).to_string();
+        if let Some(batches) = self
+            .stats_cache
+            .lock()
+            .unwrap()
+            .get(&key, hash, ttl, Instant::now())
+        {
+            return column_values(&batches);
+        }
+        let batches = self
+            .ctx
+            .sql(&preview_values_sql(name, column, limit))
+            .await?
+            .collect()
+            .await?;
+        self.stats_cache
+            .lock()
+            .unwrap()
+            .insert(&key, hash, batches.clone(), Instant::now());
+        column_values(&batches)
+    }
+
+    async fn value_counts(
+        &self,
+        name: &str,
+        column: &str,
+        top: usize,
+    ) -> anyhow::Result<RecordBatch> {
+        let batches = self
+            .ctx
+            .sql(&value_counts_sql(name, column, Some(top)))
+            .await?
+            .collect()
+            .await?;
+        Ok(concat_batches(&value_counts_schema(), &batches)?)
+    }
+
+    fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    fn settings_mut(&mut self) -> &mut Settings {
+        &mut self.settings
+    }
 }
 
 impl Default for DatafusionBackend {
@@ -92,21 +1247,1539 @@ impl Deref for DatafusionBackend {
     type Target = SessionContext;
 
     fn deref(&self) -> &Self::Target {
-        &self.0
+        &self.ctx
+    }
+}
+
+/// Note how much of a dataset a sampled describe has seen.
+pub(crate) fn sample_label(sample_size: usize, rows: usize) -> String {
+    let sampled = sample_size.min(rows);
+    let ratio = if rows == 0 {
+        1.0
+    } else {
+        sampled as f64 / rows as f64
+    };
+    format!("(sample: {} of {} rows, ratio {:.4})", sampled, rows, ratio)
+}
+
+/// Data displayed with an optional note below it.
+pub struct WithFooter<T> {
+    data: T,
+    footer: Option<String>,
+}
+
+impl<T> WithFooter<T> {
+    pub fn new(data: T, footer: Option<String>) -> Self {
+        Self { data, footer }
+    }
+}
+
+impl<T: ReplDisplay> ReplDisplay for WithFooter<T> {
+    async fn display(self, settings: &Settings) -> anyhow::Result<String> {
+        let data = self.data.display(settings).await?;
+        match self.footer {
+            Some(footer) => Ok(format!("{}\n{}", data, footer)),
+            None => Ok(data),
+        }
+    }
+
+    async fn into_batches(self) -> anyhow::Result<Vec<RecordBatch>> {
+        self.data.into_batches().await
+    }
+
+    async fn into_schema_batches(self) -> anyhow::Result<(SchemaRef, Vec<RecordBatch>)> {
+        self.data.into_schema_batches().await
     }
 }
 
 impl ReplDisplay for DataFrame {
-    async fn display(self) -> anyhow::Result<String> {
+    async fn display(self, settings: &Settings) -> anyhow::Result<String> {
         let results = self.collect().await?;
-        let data = pretty_format_batches(&results)?;
-        Ok(data.to_string())
+        format_batches(&results, settings)
+    }
+
+    async fn into_batches(self) -> anyhow::Result<Vec<RecordBatch>> {
+        Ok(self.collect().await?)
+    }
+
+    async fn into_schema_batches(self) -> anyhow::Result<(SchemaRef, Vec<RecordBatch>)> {
+        let schema = Arc::new(self.schema().as_arrow().clone());
+        Ok((schema, self.collect().await?))
+    }
+}
+
+impl ReplDisplay for Vec<RecordBatch> {
+    async fn display(self, settings: &Settings) -> anyhow::Result<String> {
+        format_batches(&self, settings)
+    }
+
+    async fn into_batches(self) -> anyhow::Result<Vec<RecordBatch>> {
+        Ok(self)
     }
 }
 
 impl ReplDisplay for RecordBatch {
-    async fn display(self) -> anyhow::Result<String> {
-        let data = pretty_format_batches(&[self])?;
-        Ok(data.to_string())
+    async fn display(self, settings: &Settings) -> anyhow::Result<String> {
+        format_batches(&[self], settings)
+    }
+
+    async fn into_batches(self) -> anyhow::Result<Vec<RecordBatch>> {
+        Ok(vec![self])
+    }
+
+    async fn into_schema_batches(self) -> anyhow::Result<(SchemaRef, Vec<RecordBatch>)> {
+        Ok((self.schema(), vec![self]))
+    }
+}
+
+/// A physical plan rendered as an indented tree or as a GraphViz graph.
+pub struct PlanDisplay(String);
+
+impl PlanDisplay {
+    pub fn new(plan: &dyn ExecutionPlan, format: ExplainFormat) -> Self {
+        let plan = displayable(plan);
+        match format {
+            ExplainFormat::Text => Self(plan.indent(true).to_string()),
+            ExplainFormat::Dot => Self(plan.graphviz().to_string()),
+        }
+    }
+
+    /// An executed plan as a tree, with the metrics of every node next to
+    /// its estimated statistics.
+    pub fn analyzed(plan: &dyn ExecutionPlan) -> Self {
+        let plan = DisplayableExecutionPlan::with_metrics(plan).set_show_statistics(true);
+        Self(plan.indent(true).to_string())
+    }
+}
+
+impl ReplDisplay for PlanDisplay {
+    async fn display(self, _settings: &Settings) -> anyhow::Result<String> {
+        Ok(self.0)
+    }
+
+    async fn into_batches(self) -> anyhow::Result<Vec<RecordBatch>> {
+        let schema = Schema::new(vec![Field::new("plan", DataType::Utf8, false)]);
+        let lines = StringArray::from_iter_values(self.0.lines());
+        Ok(vec![RecordBatch::try_new(
+            Arc::new(schema),
+            vec![Arc::new(lines)],
+        )?])
+    }
+}
+
+/// Hash of the describe options which affect the statistics.
+fn describe_hash(opts: &DescribeOpts) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    opts.predicate.hash(&mut hasher);
+    opts.numeric_only.hash(&mut hasher);
+    opts.text_only.hash(&mut hasher);
+    opts.columns.hash(&mut hasher);
+    format!("{:?}", DataFrameDescriber::default_methods()).hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Count the rows of every partition. With several partition keys, the keys
+/// and values are joined by `/` as in the directory path.
+fn partition_info_sql(name: &str, keys: &[String]) -> String {
+    let values = keys
+        .iter()
+        .map(|key| format!("\"{}\"", key))
+        .collect::<Vec<_>>();
+    format!(
+        "SELECT partition_key, partition_value, COUNT(*) AS row_count FROM \
+        (SELECT '{}' AS partition_key, concat_ws('/', {}) AS partition_value FROM {}) \
+        GROUP BY partition_key, partition_value ORDER BY partition_value",
+        keys.join("/"),
+        values.join(", "),
+        name
+    )
+}
+
+/// Hash identifying a value preview in the stats cache.
+fn preview_hash(column: &str, limit: usize) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    "preview".hash(&mut hasher);
+    column.hash(&mut hasher);
+    limit.hash(&mut hasher);
+    hasher.finish()
+}
+
+pub(crate) fn preview_values_sql(name: &str, column: &str, limit: usize) -> String {
+    format!(
+        "SELECT DISTINCT \"{0}\" FROM {1} WHERE \"{0}\" IS NOT NULL LIMIT {2}",
+        column, name, limit
+    )
+}
+
+/// Format the values of the first column as strings.
+pub(crate) fn column_values(batches: &[RecordBatch]) -> Result<Vec<String>> {
+    let options = FormatOptions::default();
+    let mut values = Vec::new();
+    for batch in batches {
+        let formatter = ArrayFormatter::try_new(batch.column(0).as_ref(), &options)?;
+        for row in 0..batch.num_rows() {
+            values.push(formatter.value(row).to_string());
+        }
+    }
+    Ok(values)
+}
+
+/// Count every value of a column, or its fraction of the rows when
+/// normalized, most frequent first.
+pub(crate) fn frequency_sql(name: &str, column: &str, normalize: bool) -> String {
+    let counts = value_counts_sql(name, column, None);
+    if !normalize {
+        return counts;
+    }
+    format!(
+        "SELECT value, CAST(\"count\" AS DOUBLE) / SUM(\"count\") OVER () AS pct FROM ({}) \
+        ORDER BY \"count\" DESC, value",
+        counts
+    )
+}
+
+/// Count the values of a column, most frequent first, keeping the `top`
+/// ones if given.
+pub(crate) fn value_counts_sql(name: &str, column: &str, top: Option<usize>) -> String {
+    let sql = format!(
+        "SELECT CAST(\"{0}\" AS VARCHAR) AS value, CAST(COUNT(*) AS BIGINT) AS \"count\" FROM {1} \
+        GROUP BY \"{0}\" ORDER BY \"count\" DESC, value",
+        column, name
+    );
+    match top {
+        Some(top) => format!("{} LIMIT {}", sql, top),
+        None => sql,
+    }
+}
+
+pub(crate) fn value_counts_schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![
+        Field::new("value", DataType::Utf8, true),
+        Field::new("count", DataType::Int64, false),
+    ]))
+}
+
+/// Rows where any of the columns contains the query. Quotes and LIKE
+/// wildcards in the query are escaped, so it always matches literally.
+pub(crate) fn text_search_sql(
+    name: &str,
+    columns: &[String],
+    query: &str,
+    case_sensitive: bool,
+    limit: usize,
+) -> String {
+    let pattern = query
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+        .replace('\'', "''");
+    let predicates = columns
+        .iter()
+        .map(|column| {
+            let value = format!("CAST(\"{}\" AS VARCHAR)", column);
+            if case_sensitive {
+                format!("{} LIKE '%{}%' ESCAPE '\\'", value, pattern)
+            } else {
+                format!("lower({}) LIKE lower('%{}%') ESCAPE '\\'", value, pattern)
+            }
+        })
+        .collect::<Vec<_>>();
+    format!(
+        "SELECT * FROM {} WHERE {} LIMIT {}",
+        name,
+        predicates.join(" OR "),
+        limit
+    )
+}
+
+/// Every table and view of the catalog outside the system schemas, named
+/// relative to the current schema, with its table type. The catalog and
+/// schema are SQL expressions.
+pub(crate) fn user_tables_sql(catalog: &str, schema: &str) -> String {
+    format!(
+        "SELECT CASE WHEN table_schema = {1} THEN table_name \
+        ELSE table_schema || '.' || table_name END AS name, table_type \
+        FROM information_schema.tables WHERE table_catalog = {0} \
+        AND table_schema NOT IN ('information_schema', 'pg_catalog') ORDER BY name",
+        catalog, schema
+    )
+}
+
+/// The names and table types of a `user_tables_sql` result.
+pub(crate) fn user_tables(batches: &[RecordBatch]) -> Vec<(String, String)> {
+    batches
+        .iter()
+        .flat_map(|batch| {
+            let names = batch.column(0).as_string::<i32>();
+            let types = batch.column(1).as_string::<i32>();
+            names.iter().zip(types.iter())
+        })
+        .filter_map(|(name, table_type)| Some((name?.to_string(), table_type?.to_string())))
+        .collect()
+}
+
+/// A SQL string literal holding the value, its quotes escaped.
+pub(crate) fn quote_literal(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+/// The `table_type` column of a dataset listing, with the materialized
+/// datasets, given by schema and table name, reported as `MATERIALIZED`.
+pub(crate) fn table_type_sql<'a>(
+    materialized: impl IntoIterator<Item = (&'a str, &'a str)>,
+) -> String {
+    let predicates = materialized
+        .into_iter()
+        .map(|(schema, table)| {
+            format!(
+                "(table_schema = {} AND table_name = {})",
+                quote_literal(schema),
+                quote_literal(table)
+            )
+        })
+        .collect::<Vec<_>>();
+    if predicates.is_empty() {
+        return "table_type".to_string();
+    }
+    format!(
+        "CASE WHEN {} THEN 'MATERIALIZED' ELSE table_type END AS table_type",
+        predicates.join(" OR ")
+    )
+}
+
+/// Count the columns of every data type, most common first.
+pub(crate) fn type_summary_batch(schema: &Schema) -> Result<RecordBatch> {
+    let mut counts: Vec<(String, i64)> = Vec::new();
+    for field in schema.fields() {
+        let data_type = field.data_type().to_string();
+        match counts.iter_mut().find(|(t, _)| *t == data_type) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((data_type, 1)),
+        }
+    }
+    counts.sort_by(|(a, x), (b, y)| y.cmp(x).then_with(|| a.cmp(b)));
+
+    let schema = Schema::new(vec![
+        Field::new("data_type", DataType::Utf8, false),
+        Field::new("column_count", DataType::Int64, false),
+    ]);
+    let (types, counts): (Vec<_>, Vec<_>) = counts.into_iter().unzip();
+    Ok(RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(StringArray::from(types)),
+            Arc::new(Int64Array::from(counts)),
+        ],
+    )?)
+}
+
+/// The union of the columns of all the schemas, in the order they first
+/// appear. Columns missing from any schema become nullable.
+pub(crate) fn merge_schema(schemas: &[Schema]) -> Result<Schema> {
+    let mut fields: Vec<Field> = Vec::new();
+    for schema in schemas {
+        for field in schema.fields() {
+            match fields.iter_mut().find(|f| f.name() == field.name()) {
+                Some(merged) if merged.data_type() != field.data_type() => anyhow::bail!(
+                    "Column {} has conflicting types {} and {}",
+                    field.name(),
+                    merged.data_type(),
+                    field.data_type()
+                ),
+                Some(merged) => {
+                    let nullable = merged.is_nullable() || field.is_nullable();
+                    *merged = merged.clone().with_nullable(nullable);
+                }
+                None => fields.push(field.as_ref().clone()),
+            }
+        }
+    }
+    let fields = fields
+        .into_iter()
+        .map(|field| {
+            let everywhere = schemas
+                .iter()
+                .all(|schema| schema.field_with_name(field.name()).is_ok());
+            let nullable = field.is_nullable() || !everywhere;
+            field.with_nullable(nullable)
+        })
+        .collect::<Vec<_>>();
+    Ok(Schema::new(fields))
+}
+
+/// The parquet files under a directory, including its partitions.
+fn parquet_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            files.extend(parquet_files(&path)?);
+        } else if path.extension().is_some_and(|ext| ext == "parquet") {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Describe a schema as `column_name`, `data_type` and `is_nullable` rows.
+pub(crate) fn schema_batch(schema: &Schema) -> Result<RecordBatch> {
+    let fields = schema.fields();
+    let names = fields.iter().map(|field| field.name().to_string());
+    let types = fields.iter().map(|field| field.data_type().to_string());
+    let nullables = fields
+        .iter()
+        .map(|field| if field.is_nullable() { "YES" } else { "NO" });
+
+    let schema = Schema::new(vec![
+        Field::new("column_name", DataType::Utf8, false),
+        Field::new("data_type", DataType::Utf8, false),
+        Field::new("is_nullable", DataType::Utf8, false),
+    ]);
+    let batch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(StringArray::from_iter_values(names)),
+            Arc::new(StringArray::from_iter_values(types)),
+            Arc::new(StringArray::from_iter_values(nullables)),
+        ],
+    )?;
+    Ok(batch)
+}
+
+/// Compare a registered schema with the one read from the source, one row
+/// per added, removed or retyped column.
+pub(crate) fn schema_diff(old: &Schema, new: &Schema) -> Result<RecordBatch> {
+    let mut columns = Vec::new();
+    let mut changes = Vec::new();
+    let mut old_types = Vec::new();
+    let mut new_types = Vec::new();
+    for field in old.fields() {
+        match new.field_with_name(field.name()) {
+            Ok(new_field) if new_field.data_type() == field.data_type() => continue,
+            Ok(new_field) => {
+                changes.push("type_changed");
+                new_types.push(Some(new_field.data_type().to_string()));
+            }
+            Err(_) => {
+                changes.push("removed");
+                new_types.push(None);
+            }
+        }
+        columns.push(field.name().to_string());
+        old_types.push(Some(field.data_type().to_string()));
+    }
+    for field in new.fields() {
+        if old.field_with_name(field.name()).is_err() {
+            columns.push(field.name().to_string());
+            changes.push("added");
+            old_types.push(None);
+            new_types.push(Some(field.data_type().to_string()));
+        }
+    }
+
+    let schema = Schema::new(vec![
+        Field::new("column", DataType::Utf8, false),
+        Field::new("change", DataType::Utf8, false),
+        Field::new("old_type", DataType::Utf8, true),
+        Field::new("new_type", DataType::Utf8, true),
+    ]);
+    let batch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(StringArray::from(columns)),
+            Arc::new(StringArray::from(changes)),
+            Arc::new(StringArray::from(old_types)),
+            Arc::new(StringArray::from(new_types)),
+        ],
+    )?;
+    Ok(batch)
+}
+
+/// The names of all the columns of a schema.
+pub(crate) fn field_names(schema: &Schema) -> Vec<String> {
+    schema
+        .fields()
+        .iter()
+        .map(|field| field.name().to_string())
+        .collect()
+}
+
+/// The SQL name of a type both backends can cast to, `None` for the types
+/// without a common name.
+pub(crate) fn sql_type(data_type: &DataType) -> Option<String> {
+    let name = match data_type {
+        DataType::Boolean => "BOOLEAN",
+        DataType::Int8 => "TINYINT",
+        DataType::Int16 => "SMALLINT",
+        DataType::Int32 => "INTEGER",
+        DataType::Int64 => "BIGINT",
+        DataType::Float32 => "REAL",
+        DataType::Float64 => "DOUBLE",
+        DataType::Decimal128(precision, scale) => {
+            return Some(format!("DECIMAL({}, {})", precision, scale));
+        }
+        DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View => "VARCHAR",
+        DataType::Date32 => "DATE",
+        DataType::Timestamp(_, None) => "TIMESTAMP",
+        _ => return None,
+    };
+    Some(name.to_string())
+}
+
+/// The names of the numeric columns of a schema.
+pub(crate) fn numeric_fields(schema: &Schema) -> Vec<String> {
+    schema
+        .fields()
+        .iter()
+        .filter(|field| field.data_type().is_numeric())
+        .map(|field| field.name().to_string())
+        .collect()
+}
+
+/// The names of the string columns of a schema.
+pub(crate) fn string_fields(schema: &Schema) -> Vec<String> {
+    schema
+        .fields()
+        .iter()
+        .filter(|field| {
+            matches!(
+                field.data_type(),
+                DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View
+            )
+        })
+        .map(|field| field.name().to_string())
+        .collect()
+}
+
+/// Keep the first (or last) row of every group of rows sharing the key
+/// columns, all of them by default, in their original order.
+pub(crate) fn deduplicate_sql(
+    name: &str,
+    all: &[String],
+    key: &[String],
+    keep_last: bool,
+) -> Result<String> {
+    let unknown = key
+        .iter()
+        .filter(|column| !all.contains(column))
+        .cloned()
+        .collect::<Vec<_>>();
+    if !unknown.is_empty() {
+        anyhow::bail!("Dataset {} has no column(s): {}", name, unknown.join(", "));
+    }
+    let quote = |columns: &[String]| {
+        columns
+            .iter()
+            .map(|column| format!("\"{}\"", column))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let key = if key.is_empty() { all } else { key };
+    let direction = if keep_last { "DESC" } else { "ASC" };
+    Ok(format!(
+        "SELECT {} FROM (SELECT *, ROW_NUMBER() OVER (PARTITION BY {} ORDER BY __row {}) AS __rn \
+        FROM (SELECT *, ROW_NUMBER() OVER () AS __row FROM {}) AS numbered) AS ranked \
+        WHERE __rn = 1 ORDER BY __row",
+        quote(all),
+        quote(key),
+        direction,
+        name
+    ))
+}
+
+/// Select every column of a dataset, casting the given ones to their new
+/// types. Values failing a non-strict cast become NULL.
+pub(crate) fn cast_schema_sql(
+    name: &str,
+    all: &[String],
+    casts: &[(String, String)],
+    strict: bool,
+) -> Result<String> {
+    let unknown = casts
+        .iter()
+        .filter(|(column, _)| !all.contains(column))
+        .map(|(column, _)| column.as_str())
+        .collect::<Vec<_>>();
+    if !unknown.is_empty() {
+        anyhow::bail!("Dataset {} has no column(s): {}", name, unknown.join(", "));
+    }
+    let cast = if strict { "CAST" } else { "TRY_CAST" };
+    let projection = all
+        .iter()
+        .map(|column| match casts.iter().find(|(c, _)| c == column) {
+            Some((_, data_type)) => {
+                format!("{0}(\"{1}\" AS {2}) AS \"{1}\"", cast, column, data_type)
+            }
+            None => format!("\"{}\"", column),
+        })
+        .collect::<Vec<_>>();
+    Ok(format!("SELECT {} FROM {}", projection.join(", "), name))
+}
+
+/// Melt the value columns of a wide table into `variable` and `value` rows,
+/// one `SELECT` per value column glued with `UNION ALL`.
+pub(crate) fn pivot_longer_sql(
+    name: &str,
+    all: &[String],
+    id_cols: &[String],
+    value_cols: &[String],
+    variable_col: &str,
+    value_col: &str,
+) -> Result<String> {
+    let unknown = id_cols
+        .iter()
+        .chain(value_cols)
+        .filter(|column| !all.contains(column))
+        .cloned()
+        .collect::<Vec<_>>();
+    if !unknown.is_empty() {
+        anyhow::bail!("Dataset {} has no column(s): {}", name, unknown.join(", "));
+    }
+    let value_cols = if value_cols.is_empty() {
+        all.iter()
+            .filter(|column| !id_cols.contains(column))
+            .cloned()
+            .collect()
+    } else {
+        value_cols.to_vec()
+    };
+    if value_cols.is_empty() {
+        anyhow::bail!("Dataset {} has no columns to melt", name);
+    }
+    let ids = id_cols
+        .iter()
+        .map(|column| format!("\"{}\", ", column))
+        .collect::<String>();
+    let selects = value_cols
+        .iter()
+        .map(|column| {
+            format!(
+                "SELECT {}'{}' AS \"{}\", \"{}\" AS \"{}\" FROM {}",
+                ids,
+                column.replace('\'', "''"),
+                variable_col,
+                column,
+                value_col,
+                name
+            )
+        })
+        .collect::<Vec<_>>();
+    Ok(selects.join(" UNION ALL "))
+}
+
+/// The select list of a describe query, honouring `--numeric-only` and
+/// `--text-only`.
+pub(crate) async fn describe_projection<T: Backend>(
+    backend: &T,
+    opts: &DescribeOpts,
+) -> Result<String> {
+    let (columns, kind) = if !opts.columns.is_empty() {
+        let names = backend.column_names(&opts.name).await?;
+        let unknown = opts
+            .columns
+            .iter()
+            .filter(|column| !names.contains(column))
+            .cloned()
+            .collect::<Vec<_>>();
+        if !unknown.is_empty() {
+            anyhow::bail!(
+                "Dataset {} has no column(s): {}",
+                opts.name,
+                unknown.join(", ")
+            );
+        }
+        (opts.columns.clone(), "selected")
+    } else if opts.numeric_only {
+        (backend.numeric_columns(&opts.name).await?, "numeric")
+    } else if opts.text_only {
+        (backend.string_columns(&opts.name).await?, "string")
+    } else {
+        return Ok("*".to_string());
+    };
+    if columns.is_empty() {
+        anyhow::bail!("Dataset {} has no {} columns", opts.name, kind);
+    }
+    let columns = columns
+        .iter()
+        .map(|column| format!("\"{}\"", column))
+        .collect::<Vec<_>>();
+    Ok(columns.join(", "))
+}
+
+fn format_options(settings: &Settings) -> FormatOptions<'_> {
+    FormatOptions::default()
+        .with_display_error(true)
+        .with_null(&settings.null_display)
+}
+
+fn format_batches(batches: &[RecordBatch], settings: &Settings) -> anyhow::Result<String> {
+    let data = pretty_format_batches_with_options(batches, &format_options(settings))?;
+    Ok(data.to_string())
+}
+
+/// Serialize the batches as an Arrow IPC stream.
+pub(crate) fn ipc_stream_bytes(schema: &Schema, batches: &[RecordBatch]) -> Result<Vec<u8>> {
+    let mut writer = StreamWriter::try_new(Vec::new(), schema)?;
+    for batch in batches {
+        writer.write(batch)?;
+    }
+    writer.finish()?;
+    Ok(writer.into_inner()?)
+}
+
+/// Serialize the batches as a JSON array of rows.
+pub(crate) fn json_bytes(batches: &[RecordBatch]) -> Result<Vec<u8>> {
+    let mut writer = ArrayWriter::new(Vec::new());
+    for batch in batches {
+        writer.write(batch)?;
+    }
+    writer.finish()?;
+    Ok(writer.into_inner())
+}
+
+/// Pivot the single row of quantile values into `quantile` and `value`
+/// columns, one row per quantile.
+pub(crate) fn quantiles_batch(quantiles: &[f64], batches: &[RecordBatch]) -> Result<RecordBatch> {
+    let batch = batches
+        .iter()
+        .find(|batch| batch.num_rows() > 0)
+        .ok_or_else(|| anyhow::anyhow!("No result for quantiles"))?;
+    let values = batch
+        .columns()
+        .iter()
+        .map(|column| {
+            let column = arrow::compute::cast(column, &DataType::Float64)?;
+            let column = column.as_primitive::<Float64Type>();
+            Ok((!column.is_null(0)).then(|| column.value(0)))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let schema = Schema::new(vec![
+        Field::new("quantile", DataType::Float64, false),
+        Field::new("value", DataType::Float64, true),
+    ]);
+    let batch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(Float64Array::from(quantiles.to_vec())),
+            Arc::new(Float64Array::from(values)),
+        ],
+    )?;
+    Ok(batch)
+}
+
+/// Pivot the first row of a batch into `field` and `value` columns, one row
+/// per original column.
+pub(crate) fn transpose_batch(batch: &RecordBatch) -> Result<RecordBatch> {
+    let options = FormatOptions::default();
+    let schema = batch.schema();
+    let mut fields = Vec::with_capacity(batch.num_columns());
+    let mut values = Vec::with_capacity(batch.num_columns());
+    for (field, column) in schema.fields().iter().zip(batch.columns()) {
+        let value = if column.is_null(0) {
+            None
+        } else {
+            let formatter = ArrayFormatter::try_new(column.as_ref(), &options)?;
+            Some(formatter.value(0).to_string())
+        };
+        fields.push(field.name().to_string());
+        values.push(value);
+    }
+
+    let schema = Schema::new(vec![
+        Field::new("field", DataType::Utf8, false),
+        Field::new("value", DataType::Utf8, true),
+    ]);
+    let batch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(StringArray::from(fields)),
+            Arc::new(StringArray::from(values)),
+        ],
+    )?;
+    Ok(batch)
+}
+
+/// Format the rows of a batch vertically, one field per line. `start` is the
+/// index of the first row in the whole dataset.
+pub(crate) fn format_vertical(
+    batch: &RecordBatch,
+    start: usize,
+    settings: &Settings,
+) -> anyhow::Result<String> {
+    let options = format_options(settings);
+    let schema = batch.schema();
+    let width = schema
+        .fields()
+        .iter()
+        .map(|f| f.name().len())
+        .max()
+        .unwrap_or_default();
+    let formatters = batch
+        .columns()
+        .iter()
+        .map(|column| ArrayFormatter::try_new(column.as_ref(), &options))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut lines = Vec::new();
+    for row in 0..batch.num_rows() {
+        lines.push(format!("-[ RECORD {} ]-", start + row));
+        for (field, formatter) in schema.fields().iter().zip(&formatters) {
+            lines.push(format!(
+                "{:width$} | {}",
+                field.name(),
+                formatter.value(row),
+                width = width
+            ));
+        }
+    }
+    Ok(lines.join("\n"))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::{
+        fs, io,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    use super::*;
+    use crate::{DisplayFormat, FileOpts};
+    use arrow::array::{ArrayRef, BooleanArray, Float64Array, Int64Array, LargeStringArray};
+    use arrow::ipc::reader::StreamReader;
+    use datafusion::catalog::streaming::StreamingTable;
+    use datafusion::execution::{SendableRecordBatchStream, TaskContext};
+    use datafusion::physical_plan::{stream::RecordBatchStreamAdapter, streaming::PartitionStream};
+    use flate2::{Compression, write::GzEncoder};
+    use parquet::arrow::ArrowWriter;
+
+    /// Create a backend with a single table `t` holding ids `0..10`.
+    pub(crate) fn test_backend() -> DatafusionBackend {
+        let backend = DatafusionBackend::new();
+        let schema = Schema::new(vec![Field::new("id", DataType::Int64, false)]);
+        let batch = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![Arc::new(Int64Array::from_iter_values(0..10))],
+        )
+        .unwrap();
+        backend.register_batch("t", batch).unwrap();
+        backend
+    }
+
+    /// Create a backend with a single table `name` holding the given columns.
+    pub(crate) fn backend_with(name: &str, columns: Vec<(&str, ArrayRef)>) -> DatafusionBackend {
+        let backend = DatafusionBackend::new();
+        let batch = RecordBatch::try_from_iter(columns).unwrap();
+        backend.register_batch(name, batch).unwrap();
+        backend
+    }
+
+    /// Create a unique temporary directory for test files. The dataset parser
+    /// reads the format from the dotted parts of the whole path, so the
+    /// directory name must not contain a dot.
+    pub(crate) fn temp_dir() -> io::Result<tempfile::TempDir> {
+        tempfile::Builder::new().prefix("taotie").tempdir()
+    }
+
+    /// Connect a csv file whose scans are split into several partitions, so
+    /// a query without an order reads its rows in no particular order.
+    pub(crate) async fn connect_partitioned_csv(
+        backend: &mut DatafusionBackend,
+        name: &str,
+        content: &str,
+    ) -> Result<tempfile::TempDir> {
+        for setting in [
+            "datafusion.execution.target_partitions = 4",
+            "datafusion.optimizer.repartition_file_min_size = 1",
+        ] {
+            let sql = format!("SET {}", setting);
+            backend.sql(&sql).await?.into_batches().await?;
+        }
+        let dir = temp_dir()?;
+        let path = dir.path().join(format!("{}.csv", name));
+        fs::write(&path, content)?;
+        let conn = DatasetConn::Csv(FileOpts {
+            filename: path.to_string_lossy().to_string(),
+            extension: ".csv".to_string(),
+            compression: FileCompressionType::UNCOMPRESSED,
+        });
+        backend
+            .connect(&ConnectOpts::new(conn, None, name.to_string()))
+            .await?;
+        Ok(dir)
+    }
+
+    pub(crate) fn write_parquet(path: &Path, batch: &RecordBatch) -> Result<()> {
+        let file = File::create(path)?;
+        let mut writer = ArrowWriter::try_new(file, batch.schema(), None)?;
+        writer.write(batch)?;
+        writer.close()?;
+        Ok(())
+    }
+
+    /// Count the data rows of a pretty formatted table.
+    pub(crate) fn row_count(output: &str) -> usize {
+        output
+            .lines()
+            .filter(|line| line.starts_with('|'))
+            .count()
+            .saturating_sub(1)
+    }
+
+    /// Get the trimmed cells of the row whose first cell is `key`.
+    pub(crate) fn row_cells(output: &str, key: &str) -> Vec<String> {
+        output
+            .lines()
+            .filter(|line| line.starts_with('|'))
+            .map(|line| {
+                line.trim_matches('|')
+                    .split('|')
+                    .map(|cell| cell.trim().to_string())
+                    .collect::<Vec<_>>()
+            })
+            .find(|cells| cells[0] == key)
+            .unwrap_or_default()
+    }
+
+    #[tokio::test]
+    async fn test_plan_display() -> anyhow::Result<()> {
+        let backend = test_backend();
+        let df = backend.ctx.sql("SELECT id FROM t WHERE id > 3").await?;
+        let plan = df.create_physical_plan().await?;
+
+        let text = PlanDisplay::new(plan.as_ref(), ExplainFormat::Text)
+            .display(backend.settings())
+            .await?;
+        assert!(text.contains("FilterExec"));
+
+        let dot = PlanDisplay::new(plan.as_ref(), ExplainFormat::Dot)
+            .display(backend.settings())
+            .await?;
+        assert!(dot.contains("digraph"));
+        assert!(dot.contains("FilterExec"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_display_bytes() -> anyhow::Result<()> {
+        let backend = test_backend();
+        let output = backend.sql("SELECT * FROM t").await?;
+        let bytes = output
+            .display_bytes(DisplayFormat::ArrowIpc, backend.settings())
+            .await?;
+        let reader = StreamReader::try_new(io::Cursor::new(bytes), None)?;
+        let batches = reader.collect::<Result<Vec<_>, _>>()?;
+        let expected = backend.table("t").await?.collect().await?;
+        assert_eq!(
+            concat_batches(&batches[0].schema(), &batches)?,
+            concat_batches(&expected[0].schema(), &expected)?
+        );
+
+        // an empty result still carries its schema
+        let output = backend.sql("SELECT * FROM t WHERE id < 0").await?;
+        let bytes = output
+            .display_bytes(DisplayFormat::ArrowIpc, backend.settings())
+            .await?;
+        let reader = StreamReader::try_new(io::Cursor::new(bytes), None)?;
+        assert_eq!(reader.schema().field(0).name(), "id");
+        assert_eq!(reader.count(), 0);
+
+        let output = backend.sql("SELECT * FROM t LIMIT 2").await?;
+        let bytes = output
+            .display_bytes(DisplayFormat::Json, backend.settings())
+            .await?;
+        assert_eq!(String::from_utf8(bytes)?, r#"[{"id":0},{"id":1}]"#);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_head_without_offset() -> anyhow::Result<()> {
+        let backend = test_backend();
+        let output = backend
+            .head("t", 3, 0)
+            .await?
+            .display(backend.settings())
+            .await?;
+        assert_eq!(row_count(&output), 3);
+        assert!(output.contains("| 0  |"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_head_with_offset() -> anyhow::Result<()> {
+        let backend = test_backend();
+        let output = backend
+            .head("t", 10, 5)
+            .await?
+            .display(backend.settings())
+            .await?;
+        assert_eq!(row_count(&output), 5);
+        assert!(output.contains("| 5  |"));
+        assert!(!output.contains("| 4  |"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_head_with_offset_beyond_table() -> anyhow::Result<()> {
+        let backend = test_backend();
+        let output = backend
+            .head("t", 10, 20)
+            .await?
+            .display(backend.settings())
+            .await?;
+        assert_eq!(row_count(&output), 0);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_display_null_values() -> anyhow::Result<()> {
+        let mut backend = test_backend();
+        let schema = Schema::new(vec![Field::new("name", DataType::Utf8, true)]);
+        let batch = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![Arc::new(StringArray::from(vec![Some("a"), None]))],
+        )?;
+        backend.register_batch("nullable", batch)?;
+
+        let output = backend.sql("SELECT * FROM nullable").await?;
+        let output = output.display(backend.settings()).await?;
+        assert!(output.contains("| NULL |"));
+
+        backend.settings_mut().set("null_display", "<null>")?;
+        let output = backend.sql("SELECT * FROM nullable").await?;
+        let output = output.display(backend.settings()).await?;
+        assert!(output.contains("| <null> |"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_list_with_schema() -> anyhow::Result<()> {
+        let backend = test_backend();
+        let output = backend.list(true, None).await?;
+        let output = output.display(backend.settings()).await?;
+        assert!(output.contains("| table_name | table_type | columns   |"));
+        assert!(output.contains("| t          | BASE TABLE | id: Int64 |"));
+        Ok(())
+    }
+
+    fn mixed_backend() -> Result<DatafusionBackend> {
+        let backend = DatafusionBackend::new();
+        let schema = Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("name", DataType::Utf8, false),
+            Field::new("price", DataType::Float64, false),
+            Field::new("active", DataType::Boolean, false),
+            Field::new("code", DataType::LargeUtf8, false),
+        ]);
+        let batch = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![
+                Arc::new(Int64Array::from(vec![1, 2])),
+                Arc::new(StringArray::from(vec!["a", "b"])),
+                Arc::new(Float64Array::from(vec![1.5, 2.5])),
+                Arc::new(BooleanArray::from(vec![true, false])),
+                Arc::new(LargeStringArray::from(vec!["x", "y"])),
+            ],
+        )?;
+        backend.register_batch("mixed", batch)?;
+        Ok(backend)
+    }
+
+    #[tokio::test]
+    async fn test_numeric_and_string_columns() -> anyhow::Result<()> {
+        let backend = mixed_backend()?;
+        assert_eq!(backend.numeric_columns("mixed").await?, ["id", "price"]);
+        assert_eq!(backend.string_columns("mixed").await?, ["name", "code"]);
+
+        let backend = test_backend();
+        assert_eq!(backend.numeric_columns("t").await?, ["id"]);
+        assert!(backend.string_columns("t").await?.is_empty());
+        assert!(backend.numeric_columns("missing").await.is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_describe_numeric_and_text_only() -> anyhow::Result<()> {
+        let backend = mixed_backend()?;
+        let opts = DescribeOpts::new("mixed".to_string()).with_numeric_only(true);
+        let output = backend.describe(&opts).await?;
+        let output = output.display(backend.settings()).await?;
+        assert_eq!(row_cells(&output, "describe"), ["describe", "id", "price"]);
+
+        let opts = DescribeOpts::new("mixed".to_string()).with_text_only(true);
+        let output = backend.describe(&opts).await?;
+        let output = output.display(backend.settings()).await?;
+        assert_eq!(row_cells(&output, "describe"), ["describe", "name", "code"]);
+        assert_eq!(
+            row_cells(&output, "max_length"),
+            ["max_length", "1.0", "1.0"]
+        );
+        assert!(!output.contains("stddev"));
+
+        let opts = <DescribeOpts as clap::Parser>::try_parse_from([
+            "describe",
+            "-n",
+            "mixed",
+            "--string-only",
+        ])?;
+        assert!(opts.text_only);
+
+        let backend = test_backend();
+        let opts = DescribeOpts::new("t".to_string()).with_text_only(true);
+        let err = backend.describe(&opts).await.err().unwrap();
+        assert_eq!(err.to_string(), "Dataset t has no string columns");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_describe_selected_columns() -> anyhow::Result<()> {
+        let backend = mixed_backend()?;
+        let opts = <DescribeOpts as clap::Parser>::try_parse_from([
+            "describe",
+            "-n",
+            "mixed",
+            "--columns",
+            "price,id",
+            "--columns",
+            "active",
+        ])?;
+        assert_eq!(opts.columns, ["price", "id", "active"]);
+        let output = backend.describe(&opts).await?;
+        let output = output.display(backend.settings()).await?;
+        assert_eq!(
+            row_cells(&output, "describe"),
+            ["describe", "price", "id", "active"]
+        );
+
+        let opts = DescribeOpts::new("mixed".to_string())
+            .with_columns(vec!["id".to_string(), "missing".to_string()]);
+        let err = backend.describe(&opts).await.err().unwrap();
+        assert_eq!(err.to_string(), "Dataset mixed has no column(s): missing");
+        Ok(())
+    }
+
+    async fn sql_rows(backend: &DatafusionBackend, sql: &str) -> anyhow::Result<usize> {
+        let output = backend.sql(sql).await?;
+        Ok(row_count(&output.display(backend.settings()).await?))
+    }
+
+    #[tokio::test]
+    async fn test_global_row_limit() -> anyhow::Result<()> {
+        let mut backend = test_backend();
+        backend.settings_mut().set("global_row_limit", "3")?;
+        assert_eq!(sql_rows(&backend, "SELECT * FROM t").await?, 3);
+        assert_eq!(sql_rows(&backend, "SELECT * FROM t LIMIT 2").await?, 2);
+        assert_eq!(sql_rows(&backend, "SELECT * FROM t LIMIT 8").await?, 3);
+        assert_eq!(sql_rows(&backend, "SELECT * FROM t -- every row").await?, 3);
+        let sql = "WITH x AS (SELECT * FROM t LIMIT 5) SELECT * FROM t";
+        assert_eq!(sql_rows(&backend, sql).await?, 3);
+        let sql = "SELECT * FROM t WHERE id IN (SELECT id FROM t LIMIT 5)";
+        assert_eq!(sql_rows(&backend, sql).await?, 3);
+        let output = backend.head("t", 5, 0).await?;
+        assert_eq!(row_count(&output.display(backend.settings()).await?), 3);
+
+        backend.settings_mut().set("global_row_limit", "none")?;
+        assert_eq!(sql_rows(&backend, "SELECT * FROM t").await?, 10);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_connect_partitioned_parquet_dir() -> anyhow::Result<()> {
+        let tmp = temp_dir()?;
+        let dir = tmp.path();
+        let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Int64, false)]));
+        for (date, ids) in [("2024-01-01", 0..3), ("2024-01-02", 3..5)] {
+            let partition = dir.join(format!("date={}", date));
+            fs::create_dir_all(&partition)?;
+            let batch = RecordBatch::try_new(
+                schema.clone(),
+                vec![Arc::new(Int64Array::from_iter_values(ids))],
+            )?;
+            write_parquet(&partition.join("part-0.parquet"), &batch)?;
+        }
+
+        let mut backend = DatafusionBackend::new();
+        let path = dir.to_string_lossy().to_string();
+        let opts = ConnectOpts::new(DatasetConn::Parquet(path), None, "events".to_string());
+        backend.connect(&opts).await?;
+
+        let table = backend.table("events").await?;
+        let names = table
+            .schema()
+            .fields()
+            .iter()
+            .map(|f| f.name().as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["id", "date"]);
+
+        let count = backend
+            .ctx
+            .sql("SELECT * FROM events WHERE date = '2024-01-02'")
+            .await?
+            .count()
+            .await?;
+        assert_eq!(count, 2);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_connect_parquet_dir_with_schema_evolution() -> anyhow::Result<()> {
+        let tmp = temp_dir()?;
+        let dir = tmp.path().join("schema_evolution");
+        fs::create_dir_all(&dir)?;
+        let old = RecordBatch::try_new(
+            Arc::new(Schema::new(vec![Field::new("id", DataType::Int64, false)])),
+            vec![Arc::new(Int64Array::from(vec![1, 2]))],
+        )?;
+        write_parquet(&dir.join("part-0.parquet"), &old)?;
+        let new = RecordBatch::try_new(
+            Arc::new(Schema::new(vec![
+                Field::new("id", DataType::Int64, false),
+                Field::new("name", DataType::Utf8, false),
+            ])),
+            vec![
+                Arc::new(Int64Array::from(vec![3])),
+                Arc::new(StringArray::from(vec!["c"])),
+            ],
+        )?;
+        write_parquet(&dir.join("part-1.parquet"), &new)?;
+
+        // without schema evolution the column only some files have is
+        // declared non-nullable and reading the older file fails
+        let mut backend = DatafusionBackend::new();
+        let path = dir.to_string_lossy().to_string();
+        let opts = ConnectOpts::new(
+            DatasetConn::Parquet(path.clone()),
+            None,
+            "events".to_string(),
+        );
+        backend.connect(&opts).await?;
+        assert!(
+            backend
+                .sql("SELECT id, name FROM events")
+                .await?
+                .display(backend.settings())
+                .await
+                .is_err()
+        );
+
+        let opts = ConnectOpts::new(DatasetConn::Parquet(path), None, "merged".to_string())
+            .with_schema_evolution(true);
+        backend.connect(&opts).await?;
+
+        let output = backend
+            .sql("SELECT id, name FROM merged ORDER BY id")
+            .await?
+            .display(backend.settings())
+            .await?;
+        assert_eq!(row_count(&output), 3);
+        assert_eq!(row_cells(&output, "1"), ["1", "NULL"]);
+        assert_eq!(row_cells(&output, "3"), ["3", "c"]);
+
+        let file = dir.join("part-0.parquet").to_string_lossy().to_string();
+        let opts = ConnectOpts::new(DatasetConn::Parquet(file), None, "old".to_string())
+            .with_schema_evolution(true);
+        let err = backend.connect(&opts).await.unwrap_err();
+        assert!(err.to_string().contains("is a single file"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_merge_schema() -> anyhow::Result<()> {
+        let a = Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("name", DataType::Utf8, false),
+        ]);
+        let b = Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("score", DataType::Float64, false),
+        ]);
+        let merged = merge_schema(&[a.clone(), b])?;
+        assert_eq!(
+            merged,
+            Schema::new(vec![
+                Field::new("id", DataType::Int64, false),
+                Field::new("name", DataType::Utf8, true),
+                Field::new("score", DataType::Float64, true),
+            ])
+        );
+
+        let c = Schema::new(vec![Field::new("id", DataType::Utf8, false)]);
+        let err = merge_schema(&[a, c]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Column id has conflicting types Int64 and Utf8"
+        );
+        Ok(())
+    }
+
+    /// A table of ids `0..rows` which counts the rows scanned from it.
+    #[derive(Debug)]
+    struct CountingPartition {
+        schema: SchemaRef,
+        rows: i64,
+        scanned: Arc<AtomicUsize>,
+    }
+
+    impl PartitionStream for CountingPartition {
+        fn schema(&self) -> &SchemaRef {
+            &self.schema
+        }
+
+        fn execute(&self, _ctx: Arc<TaskContext>) -> SendableRecordBatchStream {
+            let batch = RecordBatch::try_new(
+                self.schema.clone(),
+                vec![Arc::new(Int64Array::from_iter_values(0..self.rows))],
+            )
+            .map_err(Into::into);
+            let scanned = self.scanned.clone();
+            let stream =
+                futures::StreamExt::inspect(futures::stream::iter([batch]), move |batch| {
+                    if let Ok(batch) = batch {
+                        scanned.fetch_add(batch.num_rows(), Ordering::SeqCst);
+                    }
+                });
+            Box::pin(RecordBatchStreamAdapter::new(self.schema.clone(), stream))
+        }
+    }
+
+    fn counting_backend(rows: i64) -> anyhow::Result<(DatafusionBackend, Arc<AtomicUsize>)> {
+        let backend = DatafusionBackend::new();
+        let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Int64, false)]));
+        let scanned = Arc::new(AtomicUsize::new(0));
+        let partition = CountingPartition {
+            schema: schema.clone(),
+            rows,
+            scanned: scanned.clone(),
+        };
+        let table = StreamingTable::try_new(schema, vec![Arc::new(partition)])?;
+        backend.ctx.register_table("t", Arc::new(table))?;
+        Ok((backend, scanned))
+    }
+
+    #[tokio::test]
+    async fn test_describe_with_sample_size() -> anyhow::Result<()> {
+        let (backend, scanned) = counting_backend(1000)?;
+        let opts = DescribeOpts::new("t".to_string()).with_sample_size(Some(4));
+        let output = backend.describe(&opts).await?;
+        let output = output.display(backend.settings()).await?;
+        assert_eq!(row_cells(&output, "total"), ["total", "4.0"]);
+        assert!(output.ends_with("(sampled: 4 rows)"));
+        // drawing the sample reads the table once, the statistics only
+        // read the sample
+        let sampled = scanned.swap(0, Ordering::SeqCst);
+        assert_eq!(sampled, 1000);
+
+        let opts = DescribeOpts::new("t".to_string()).with_no_cache(true);
+        backend
+            .describe(&opts)
+            .await?
+            .display(backend.settings())
+            .await?;
+        assert!(scanned.load(Ordering::SeqCst) > 2 * sampled);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_describe_with_predicate() -> anyhow::Result<()> {
+        let backend = test_backend();
+        let opts = DescribeOpts::new("t".to_string()).with_predicate(Some("id >= 5".to_string()));
+        let output = backend.describe(&opts).await?;
+        let output = output.display(backend.settings()).await?;
+        assert_eq!(row_cells(&output, "total"), ["total", "5.0"]);
+        assert_eq!(row_cells(&output, "min"), ["min", "5.0"]);
+        assert_eq!(row_cells(&output, "max"), ["max", "9.0"]);
+
+        let opts =
+            DescribeOpts::new("t".to_string()).with_predicate(Some("missing > 1".to_string()));
+        assert!(backend.describe(&opts).await.is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_connect_compressed_parquet() -> anyhow::Result<()> {
+        let dir = temp_dir()?;
+        let gz = dir.path().join("sample.parquet.gz");
+        let mut encoder = GzEncoder::new(File::create(&gz)?, Compression::default());
+        io::copy(&mut File::open("assets/sample.parquet")?, &mut encoder)?;
+        encoder.finish()?;
+        let lz4 = dir.path().join("sample.parquet.lz4");
+        let mut encoder = lz4_flex::frame::FrameEncoder::new(File::create(&lz4)?);
+        io::copy(&mut File::open("assets/sample.parquet")?, &mut encoder)?;
+        encoder.finish()?;
+        let snappy = dir.path().join("sample.parquet.snappy");
+        let mut encoder = snap::write::FrameEncoder::new(File::create(&snappy)?);
+        io::copy(&mut File::open("assets/sample.parquet")?, &mut encoder)?;
+        encoder.into_inner()?;
+
+        let mut backend = DatafusionBackend::new();
+        let plain = ConnectOpts::new(
+            DatasetConn::Parquet("assets/sample.parquet".to_string()),
+            None,
+            "plain".to_string(),
+        );
+        backend.connect(&plain).await?;
+        let expected = backend.ctx.table("plain").await?.count().await?;
+
+        for (path, compression) in [
+            (gz, ParquetCompression::Gzip),
+            (lz4, ParquetCompression::Lz4),
+            (snappy, ParquetCompression::Snappy),
+        ] {
+            let name = compression.as_str().to_lowercase();
+            let compressed = ConnectOpts::new(
+                DatasetConn::CompressedParquet(CompressedParquetOpts {
+                    filename: path.to_string_lossy().to_string(),
+                    compression,
+                }),
+                None,
+                name.clone(),
+            );
+            backend.connect(&compressed).await?;
+            let count = backend.ctx.table(&name).await?.count().await?;
+            assert_eq!(count, expected, "{}", compression.as_str());
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_connect_iceberg_table() -> anyhow::Result<()> {
+        let mut backend = DatafusionBackend::new();
+        for (name, snapshot_id, rows) in [
+            ("current", None, 5),
+            ("first", Some(2066039722038768406), 3),
+            ("second", Some(6416397306146796699), 5),
+        ] {
+            let opts = ConnectOpts::new(
+                DatasetConn::Iceberg(crate::IcebergOpts {
+                    catalog_uri: "assets/iceberg".to_string(),
+                    table_identifier: "db.events".to_string(),
+                    snapshot_id,
+                }),
+                None,
+                name.to_string(),
+            );
+            backend.connect(&opts).await?;
+            let count = backend.ctx.table(name).await?.count().await?;
+            assert_eq!(count, rows, "{}", name);
+        }
+        let output = backend.sql("SELECT name FROM first ORDER BY id").await?;
+        let output = output.display(backend.settings()).await?;
+        assert_eq!(row_count(&output), 3);
+        assert_eq!(row_cells(&output, "c"), ["c"]);
+
+        let unknown = ConnectOpts::new(
+            DatasetConn::Iceberg(crate::IcebergOpts {
+                catalog_uri: "assets/iceberg".to_string(),
+                table_identifier: "db.events".to_string(),
+                snapshot_id: Some(42),
+            }),
+            None,
+            "unknown".to_string(),
+        );
+        let err = backend.connect(&unknown).await.unwrap_err();
+        assert!(err.to_string().contains("Snapshot 42 not found"), "{}", err);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_describe_uses_cache() -> anyhow::Result<()> {
+        let backend = test_backend();
+        let opts = DescribeOpts::new("t".to_string());
+        let output = backend.describe(&opts).await?;
+        let output = output.display(backend.settings()).await?;
+        assert_eq!(row_cells(&output, "total"), ["total", "10.0"]);
+
+        // replace the table behind the backend's back, the cached result is still used
+        backend.deregister_table("t")?;
+        let schema = Schema::new(vec![Field::new("id", DataType::Int64, false)]);
+        let batch = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![Arc::new(Int64Array::from_iter_values(0..3))],
+        )?;
+        backend.register_batch("t", batch)?;
+
+        let output = backend.describe(&opts).await?;
+        let output = output.display(backend.settings()).await?;
+        assert_eq!(row_cells(&output, "total"), ["total", "10.0"]);
+
+        let opts = opts.with_no_cache(true);
+        let output = backend.describe(&opts).await?;
+        let output = output.display(backend.settings()).await?;
+        assert_eq!(row_cells(&output, "total"), ["total", "3.0"]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_describe_cache_cleared_by_sql_writes() -> anyhow::Result<()> {
+        let backend = test_backend();
+        let opts = DescribeOpts::new("t".to_string());
+        let output = backend.describe(&opts).await?;
+        let output = output.display(backend.settings()).await?;
+        assert_eq!(row_cells(&output, "total"), ["total", "10.0"]);
+
+        let insert = backend.sql("INSERT INTO t VALUES (10)").await?;
+        insert.display(backend.settings()).await?;
+        let output = backend.describe(&opts).await?;
+        let output = output.display(backend.settings()).await?;
+        assert_eq!(row_cells(&output, "total"), ["total", "11.0"]);
+
+        let replace = backend
+            .sql("CREATE OR REPLACE TABLE t AS SELECT 1 AS id")
+            .await?;
+        replace.display(backend.settings()).await?;
+        let output = backend.describe(&opts).await?;
+        let output = output.display(backend.settings()).await?;
+        assert_eq!(row_cells(&output, "total"), ["total", "1.0"]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_preview_values() -> anyhow::Result<()> {
+        let backend = test_backend();
+        let mut values = backend.preview_values("t", "id", 100).await?;
+        values.sort_by_key(|v| v.parse::<i64>().unwrap());
+        let expected = (0..10).map(|i| i.to_string()).collect::<Vec<_>>();
+        assert_eq!(values, expected);
+        assert_eq!(backend.preview_values("t", "id", 3).await?.len(), 3);
+        assert!(backend.preview_values("t", "missing", 3).await.is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_preview_values_uses_cache() -> anyhow::Result<()> {
+        let backend = test_backend();
+        assert_eq!(backend.preview_values("t", "id", 100).await?.len(), 10);
+
+        backend.deregister_table("t")?;
+        let schema = Schema::new(vec![Field::new("id", DataType::Int64, false)]);
+        let batch = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![Arc::new(Int64Array::from_iter_values(0..3))],
+        )?;
+        backend.register_batch("t", batch)?;
+
+        assert_eq!(backend.preview_values("t", "id", 100).await?.len(), 10);
+        assert_eq!(backend.preview_values("t", "id", 50).await?.len(), 3);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_register_piped_input() -> anyhow::Result<()> {
+        let backend = DatafusionBackend::new();
+        let stdin = "id,name\n1,a\n2,b\n3,c\n".as_bytes();
+        backend.register_piped("piped", stdin, "csv")?;
+        let table = backend.table("piped").await?;
+        assert_eq!(table.schema().field(0).data_type(), &DataType::Int64);
+        assert_eq!(table.count().await?, 3);
+
+        let stdin = "{\"id\": 1}\n{\"id\": 2}\n".as_bytes();
+        backend.register_piped("piped_json", stdin, "json")?;
+        assert_eq!(backend.table("piped_json").await?.count().await?, 2);
+
+        let err = backend.register_piped("xml", "<a/>".as_bytes(), "xml");
+        assert!(err.is_err());
+        Ok(())
     }
 }
@@ -0,0 +1,34 @@
+use crate::OutlierMethod;
+
+/// Build a query returning the rows whose value of `column` is an outlier.
+/// `quantile` is the engine's two-argument continuous quantile function.
+pub(crate) fn outliers_sql(
+    name: &str,
+    column: &str,
+    method: OutlierMethod,
+    quantile: &str,
+) -> String {
+    let (stats, predicate) = match method {
+        OutlierMethod::Iqr => (
+            format!(
+                "{0}(\"{1}\", 0.25) AS q1, {0}(\"{1}\", 0.75) AS q3",
+                quantile, column
+            ),
+            format!(
+                "t.\"{0}\" < s.q1 - 1.5 * (s.q3 - s.q1) OR t.\"{0}\" > s.q3 + 1.5 * (s.q3 - s.q1)",
+                column
+            ),
+        ),
+        OutlierMethod::Zscore => (
+            format!(
+                "AVG(CAST(\"{0}\" AS DOUBLE)) AS mean, STDDEV(CAST(\"{0}\" AS DOUBLE)) AS sd",
+                column
+            ),
+            format!("s.sd > 0 AND ABS(t.\"{0}\" - s.mean) / s.sd > 3", column),
+        ),
+    };
+    format!(
+        "SELECT t.* FROM {0} t CROSS JOIN (SELECT {1} FROM {0}) s WHERE {2}",
+        name, stats, predicate
+    )
+}
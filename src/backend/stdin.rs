@@ -0,0 +1,73 @@
+use std::{
+    io::{self, Cursor, IsTerminal, Read, Seek},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+use anyhow::Result;
+use arrow::{
+    array::RecordBatch,
+    csv::{self, reader::Format},
+    datatypes::SchemaRef,
+    json::{self, reader::infer_json_schema_from_seekable},
+};
+use bytes::Bytes;
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+static STDIN_TAKEN: AtomicBool = AtomicBool::new(false);
+
+/// Stdin can only be read once, so a second connect of `-` fails instead of
+/// registering an empty dataset. Without piped input it is the terminal the
+/// REPL reads from, so it is never taken.
+pub(crate) fn take_stdin() -> Result<impl Read> {
+    if io::stdin().is_terminal() {
+        anyhow::bail!("Nothing is piped to stdin");
+    }
+    if STDIN_TAKEN.swap(true, Ordering::SeqCst) {
+        anyhow::bail!("Stdin was already read, piped input can only be connected once");
+    }
+    Ok(io::stdin().lock())
+}
+
+/// Read piped input as a whole and parse it by its format: csv (with a
+/// header row), newline delimited json or parquet.
+pub(crate) fn read_piped<R: Read>(
+    mut reader: R,
+    extension: &str,
+) -> Result<(SchemaRef, Vec<RecordBatch>)> {
+    let mut buf = Vec::new();
+    reader.read_to_end(&mut buf)?;
+    match extension {
+        "csv" => {
+            let mut cursor = Cursor::new(buf);
+            let (schema, _) = Format::default()
+                .with_header(true)
+                .infer_schema(&mut cursor, None)?;
+            cursor.rewind()?;
+            let schema = Arc::new(schema);
+            let batches = csv::ReaderBuilder::new(schema.clone())
+                .with_header(true)
+                .build(cursor)?
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok((schema, batches))
+        }
+        "json" | "ndjson" | "jsonl" => {
+            let mut cursor = Cursor::new(buf);
+            let (schema, _) = infer_json_schema_from_seekable(&mut cursor, None)?;
+            let schema = Arc::new(schema);
+            let batches = json::ReaderBuilder::new(schema.clone())
+                .build(cursor)?
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok((schema, batches))
+        }
+        "parquet" => {
+            let builder = ParquetRecordBatchReaderBuilder::try_new(Bytes::from(buf))?;
+            let schema = builder.schema().clone();
+            let batches = builder.build()?.collect::<Result<Vec<_>, _>>()?;
+            Ok((schema, batches))
+        }
+        v => anyhow::bail!("Unsupported format of piped input: {}", v),
+    }
+}
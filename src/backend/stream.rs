@@ -0,0 +1,34 @@
+use std::{
+    fs::File,
+    io::{Cursor, Read, Seek, SeekFrom},
+};
+
+use anyhow::Result;
+use arrow::{array::RecordBatch, csv, datatypes::SchemaRef};
+
+/// Parse the csv rows appended to a file since `offset`, returning them with
+/// the offset to continue from. The header row is skipped on the first read,
+/// and a partially written last line is left for the next one.
+pub(crate) fn read_appended_rows(
+    path: &str,
+    offset: u64,
+    schema: SchemaRef,
+) -> Result<(Vec<RecordBatch>, u64)> {
+    let mut file = File::open(path)?;
+    if file.metadata()?.len() < offset {
+        anyhow::bail!("File {} was truncated while streaming", path);
+    }
+    file.seek(SeekFrom::Start(offset))?;
+    let mut buf = Vec::new();
+    file.read_to_end(&mut buf)?;
+    let Some(end) = buf.iter().rposition(|b| *b == b'\n') else {
+        return Ok((vec![], offset));
+    };
+    buf.truncate(end + 1);
+
+    let batches = csv::ReaderBuilder::new(schema)
+        .with_header(offset == 0)
+        .build(Cursor::new(&buf))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok((batches, offset + buf.len() as u64))
+}
@@ -0,0 +1,165 @@
+use clap::{ArgMatches, Parser};
+use reedline_repl_rs::Result;
+
+use crate::{Backend, CmdExecutor, ReplContext, ReplMsg};
+
+use super::ensure_table;
+
+#[derive(Debug, Parser)]
+pub struct AntiJoinOpts {
+    #[arg(short, long, help = "the dataset whose unmatched rows are kept")]
+    left: String,
+    #[arg(short, long, help = "the dataset the rows are matched against")]
+    right: String,
+    #[arg(long, help = "the join key, present in both datasets")]
+    on: String,
+    #[arg(short, long, help = "the name of the new dataset")]
+    output: String,
+}
+
+pub fn anti_join(args: ArgMatches, context: &mut ReplContext) -> Result<Option<String>> {
+    let left = args
+        .get_one::<String>("left")
+        .expect("expect left")
+        .to_string();
+    let right = args
+        .get_one::<String>("right")
+        .expect("expect right")
+        .to_string();
+    let on = args.get_one::<String>("on").expect("expect on").to_string();
+    let output = args
+        .get_one::<String>("output")
+        .expect("expect output")
+        .to_string();
+    let opts = AntiJoinOpts::new(left, right, on, output);
+    let (msg, rx) = ReplMsg::new(opts);
+
+    Ok(context.send(msg, rx))
+}
+
+impl CmdExecutor for AntiJoinOpts {
+    async fn execute<T: Backend>(&self, backend: &mut T) -> anyhow::Result<String> {
+        for name in [&self.left, &self.right] {
+            ensure_table(backend, name).await?;
+            if !backend.column_names(name).await?.contains(&self.on) {
+                anyhow::bail!("Dataset {} has no column(s): {}", name, self.on);
+            }
+        }
+        backend
+            .create_table_from_sql(&self.output, &self.sql())
+            .await?;
+        Ok(format!(
+            "Materialized rows of {} without a match in {} as dataset {}",
+            self.left, self.right, self.output
+        ))
+    }
+}
+
+impl AntiJoinOpts {
+    pub fn new(left: String, right: String, on: String, output: String) -> Self {
+        Self {
+            left,
+            right,
+            on,
+            output,
+        }
+    }
+
+    fn sql(&self) -> String {
+        format!(
+            "SELECT l.* FROM {0} l WHERE NOT EXISTS \
+            (SELECT 1 FROM {1} r WHERE l.\"{2}\" = r.\"{2}\")",
+            self.left, self.right, self.on
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::ReplDisplay;
+    use crate::backend::DatafusionBackend;
+    use crate::backend::tests::{row_cells, row_count};
+    use arrow::array::{Int64Array, RecordBatch, StringArray};
+    use arrow::datatypes::{DataType, Field, Schema};
+
+    fn customers(ids: Vec<i64>) -> anyhow::Result<RecordBatch> {
+        let schema = Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("name", DataType::Utf8, false),
+        ]);
+        let names = ids.iter().map(|id| format!("c{}", id)).collect::<Vec<_>>();
+        Ok(RecordBatch::try_new(
+            Arc::new(schema),
+            vec![
+                Arc::new(Int64Array::from(ids)),
+                Arc::new(StringArray::from(names)),
+            ],
+        )?)
+    }
+
+    async fn unmatched(left: Vec<i64>, right: Vec<i64>) -> anyhow::Result<String> {
+        let mut backend = DatafusionBackend::new();
+        backend.register_batch("customers", customers(left)?)?;
+        backend.register_batch("orders", customers(right)?)?;
+        let opts = AntiJoinOpts::new(
+            "customers".to_string(),
+            "orders".to_string(),
+            "id".to_string(),
+            "inactive".to_string(),
+        );
+        let output = opts.execute(&mut backend).await?;
+        assert_eq!(
+            output,
+            "Materialized rows of customers without a match in orders as dataset inactive"
+        );
+        backend
+            .sql("SELECT * FROM inactive ORDER BY id")
+            .await?
+            .display(backend.settings())
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_anti_join() -> anyhow::Result<()> {
+        let output = unmatched(vec![1, 2, 3, 4], vec![2, 4, 4, 5]).await?;
+        assert_eq!(row_count(&output), 2);
+        assert_eq!(row_cells(&output, "1"), ["1", "c1"]);
+        assert_eq!(row_cells(&output, "3"), ["3", "c3"]);
+        assert!(row_cells(&output, "2").is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_anti_join_empty_tables() -> anyhow::Result<()> {
+        // every row is kept when there is nothing to match
+        let output = unmatched(vec![1, 2], vec![]).await?;
+        assert_eq!(row_count(&output), 2);
+        let output = unmatched(vec![], vec![1, 2]).await?;
+        assert_eq!(row_count(&output), 0);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_anti_join_missing_key() -> anyhow::Result<()> {
+        let mut backend = DatafusionBackend::new();
+        backend.register_batch("customers", customers(vec![1])?)?;
+        backend.register_batch("orders", customers(vec![1])?)?;
+        let err = AntiJoinOpts::new(
+            "customers".to_string(),
+            "orders".to_string(),
+            "customer_id".to_string(),
+            "inactive".to_string(),
+        )
+        .execute(&mut backend)
+        .await
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Dataset customers has no column(s): customer_id"
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,212 @@
+use chrono::NaiveDate;
+use clap::{ArgMatches, Parser};
+use reedline_repl_rs::Result;
+
+use crate::{Backend, CmdExecutor, ReplContext, ReplMsg};
+
+use super::ensure_table;
+
+/// How many distinct values of a string column are sampled to detect its type.
+const SAMPLE_SIZE: usize = 100;
+
+#[derive(Debug, Parser)]
+pub struct CastSchemaOpts {
+    #[arg(short, long, help = "the name of the dataset")]
+    name: String,
+    #[arg(
+        short,
+        long,
+        help = "cast string columns whose values look like dates or numbers"
+    )]
+    auto_detect: bool,
+    #[arg(
+        short,
+        long = "cast",
+        value_parser = parse_cast,
+        required_unless_present = "auto_detect",
+        conflicts_with = "auto_detect",
+        help = "a column and its new type as col:type, can be repeated"
+    )]
+    casts: Vec<(String, String)>,
+}
+
+fn parse_cast(s: &str) -> std::result::Result<(String, String), String> {
+    match s.split_once(':') {
+        Some((column, data_type)) if !column.is_empty() && !data_type.is_empty() => {
+            Ok((column.to_string(), data_type.to_string()))
+        }
+        _ => Err(format!("expected <column>:<type>, got {}", s)),
+    }
+}
+
+pub fn cast_schema(args: ArgMatches, context: &mut ReplContext) -> Result<Option<String>> {
+    let name = args
+        .get_one::<String>("name")
+        .expect("expect name")
+        .to_string();
+    let auto_detect = args.get_flag("auto_detect");
+    let casts = args
+        .get_many::<(String, String)>("casts")
+        .map(|casts| casts.cloned().collect())
+        .unwrap_or_default();
+    let (msg, rx) = ReplMsg::new(CastSchemaOpts::new(name, auto_detect, casts));
+
+    Ok(context.send(msg, rx))
+}
+
+impl CmdExecutor for CastSchemaOpts {
+    async fn execute<T: Backend>(&self, backend: &mut T) -> anyhow::Result<String> {
+        ensure_table(backend, &self.name).await?;
+        let casts = if self.auto_detect {
+            let mut casts = Vec::new();
+            for column in backend.string_columns(&self.name).await? {
+                let values = backend
+                    .preview_values(&self.name, &column, SAMPLE_SIZE)
+                    .await?;
+                if let Some(data_type) = detect_type(&values) {
+                    casts.push((column, data_type.to_string()));
+                }
+            }
+            casts
+        } else {
+            self.casts.clone()
+        };
+        if casts.is_empty() {
+            return Ok(format!("No columns of dataset {} to cast", self.name));
+        }
+        backend
+            .cast_schema(&self.name, &casts, !self.auto_detect)
+            .await?;
+        let casts = casts
+            .iter()
+            .map(|(column, data_type)| format!("{} AS {}", column, data_type))
+            .collect::<Vec<_>>();
+        Ok(format!(
+            "Cast columns of dataset {}: {}",
+            self.name,
+            casts.join(", ")
+        ))
+    }
+}
+
+impl CastSchemaOpts {
+    pub fn new(name: String, auto_detect: bool, casts: Vec<(String, String)>) -> Self {
+        Self {
+            name,
+            auto_detect,
+            casts,
+        }
+    }
+}
+
+/// A SQL type and whether a value parses as it.
+type Candidate = (&'static str, fn(&str) -> bool);
+
+/// The narrowest type more than 90% of the sampled values parse as.
+fn detect_type(values: &[String]) -> Option<&'static str> {
+    if values.is_empty() {
+        return None;
+    }
+    let candidates: [Candidate; 3] = [
+        ("BIGINT", |v| v.parse::<i64>().is_ok()),
+        ("DOUBLE", |v| v.parse::<f64>().is_ok()),
+        ("DATE", |v| NaiveDate::parse_from_str(v, "%Y-%m-%d").is_ok()),
+    ];
+    candidates.into_iter().find_map(|(data_type, parses)| {
+        let parsed = values.iter().filter(|v| parses(v.trim())).count();
+        (parsed * 10 > values.len() * 9).then_some(data_type)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::backend::DatafusionBackend;
+    use crate::backend::tests::backend_with;
+    use arrow::array::StringArray;
+    use arrow::datatypes::DataType;
+
+    fn orders_backend() -> DatafusionBackend {
+        backend_with(
+            "orders",
+            vec![
+                (
+                    "customer",
+                    Arc::new(StringArray::from(vec!["ann", "bob", "cid", "dan"])),
+                ),
+                (
+                    "ordered_at",
+                    Arc::new(StringArray::from(vec![
+                        Some("2024-01-01"),
+                        Some("2024-01-02"),
+                        None,
+                        Some("2024-02-29"),
+                    ])),
+                ),
+                (
+                    "amount",
+                    Arc::new(StringArray::from(vec!["1.5", "2", "3.25", "4"])),
+                ),
+            ],
+        )
+    }
+
+    async fn column_types(backend: &DatafusionBackend) -> anyhow::Result<Vec<DataType>> {
+        let table = backend.table("orders").await?;
+        Ok(table
+            .schema()
+            .fields()
+            .iter()
+            .map(|f| f.data_type().clone())
+            .collect())
+    }
+
+    #[tokio::test]
+    async fn test_cast_schema_auto_detect() -> anyhow::Result<()> {
+        let mut backend = orders_backend();
+        let output = CastSchemaOpts::new("orders".to_string(), true, vec![])
+            .execute(&mut backend)
+            .await?;
+        assert_eq!(
+            output,
+            "Cast columns of dataset orders: ordered_at AS DATE, amount AS DOUBLE"
+        );
+        assert_eq!(
+            column_types(&backend).await?,
+            [DataType::Utf8, DataType::Date32, DataType::Float64]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_cast_schema_manual() -> anyhow::Result<()> {
+        let mut backend = orders_backend();
+        let casts = vec![("ordered_at".to_string(), "DATE".to_string())];
+        CastSchemaOpts::new("orders".to_string(), false, casts)
+            .execute(&mut backend)
+            .await?;
+        assert_eq!(
+            column_types(&backend).await?,
+            [DataType::Utf8, DataType::Date32, DataType::Utf8]
+        );
+
+        let casts = vec![("missing".to_string(), "DATE".to_string())];
+        let err = CastSchemaOpts::new("orders".to_string(), false, casts)
+            .execute(&mut backend)
+            .await
+            .unwrap_err();
+        assert_eq!(err.to_string(), "Dataset orders has no column(s): missing");
+        Ok(())
+    }
+
+    #[test]
+    fn test_detect_type() {
+        let values = |v: &[&str]| v.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(detect_type(&values(&["1", "2"])), Some("BIGINT"));
+        assert_eq!(detect_type(&values(&["1", "2.5"])), Some("DOUBLE"));
+        assert_eq!(detect_type(&values(&["2024-01-01", "x"])), None);
+        assert_eq!(detect_type(&[]), None);
+    }
+}
@@ -0,0 +1,119 @@
+use clap::{ArgMatches, Parser};
+use reedline_repl_rs::Result;
+
+use crate::{Backend, CmdExecutor, ReplContext, ReplDisplay, ReplMsg};
+
+use super::ensure_table;
+
+#[derive(Debug, Parser)]
+pub struct CompareDistribOpts {
+    #[arg(
+        short,
+        long,
+        visible_alias = "name1",
+        help = "the name of the dataset of the first column"
+    )]
+    name: String,
+    #[arg(
+        long,
+        help = "the dataset of the second column, the first one by default"
+    )]
+    name2: Option<String>,
+    #[arg(long, help = "the first numeric column")]
+    col1: String,
+    #[arg(long, help = "the second numeric column")]
+    col2: String,
+}
+
+pub fn compare_distrib(args: ArgMatches, context: &mut ReplContext) -> Result<Option<String>> {
+    let name = args
+        .get_one::<String>("name")
+        .expect("expect name")
+        .to_string();
+    let name2 = args.get_one::<String>("name2").cloned();
+    let col1 = args
+        .get_one::<String>("col1")
+        .expect("expect col1")
+        .to_string();
+    let col2 = args
+        .get_one::<String>("col2")
+        .expect("expect col2")
+        .to_string();
+    let opts = CompareDistribOpts::new(name, col1, col2).with_name2(name2);
+    let (msg, rx) = ReplMsg::new(opts);
+
+    Ok(context.send(msg, rx))
+}
+
+impl CmdExecutor for CompareDistribOpts {
+    async fn execute<T: Backend>(&self, backend: &mut T) -> anyhow::Result<String> {
+        let name2 = self.name2.as_ref().unwrap_or(&self.name);
+        for (name, column) in [(&self.name, &self.col1), (name2, &self.col2)] {
+            ensure_table(backend, name).await?;
+            if !backend.numeric_columns(name).await?.contains(column) {
+                anyhow::bail!("Column {} of dataset {} is not numeric", column, name);
+            }
+        }
+        let batch = backend
+            .compare_distributions(&self.name, &self.col1, name2, &self.col2)
+            .await?;
+        batch.display(backend.settings()).await
+    }
+}
+
+impl CompareDistribOpts {
+    pub fn new(name: String, col1: String, col2: String) -> Self {
+        Self {
+            name,
+            name2: None,
+            col1,
+            col2,
+        }
+    }
+
+    pub fn with_name2(mut self, name2: Option<String>) -> Self {
+        self.name2 = name2;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::backend::DatafusionBackend;
+    use crate::backend::tests::{row_cells, test_backend};
+    use arrow::array::{AsArray, Int64Array, RecordBatch};
+    use arrow::datatypes::{DataType, Field, Float64Type, Schema};
+
+    #[tokio::test]
+    async fn test_compare_identical_columns() -> anyhow::Result<()> {
+        let mut backend = test_backend();
+        let opts = CompareDistribOpts::new("t".to_string(), "id".to_string(), "id".to_string());
+        let output = opts.execute(&mut backend).await?;
+        assert_eq!(row_cells(&output, "0.0"), ["0.0", "1.0", "10", "10"]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_compare_different_datasets() -> anyhow::Result<()> {
+        let backend = DatafusionBackend::new();
+        for (name, values) in [("low", 0..100), ("high", 1000..1050)] {
+            let schema = Schema::new(vec![Field::new("v", DataType::Int64, false)]);
+            let batch = RecordBatch::try_new(
+                Arc::new(schema),
+                vec![Arc::new(Int64Array::from_iter_values(values))],
+            )?;
+            backend.register_batch(name, batch)?;
+        }
+
+        let batch = backend
+            .compare_distributions("low", "v", "high", "v")
+            .await?;
+        let value = |i: usize| batch.column(i).as_primitive::<Float64Type>().value(0);
+        assert!((value(0) - 1.0).abs() < 1e-9);
+        assert!(value(1) < 1e-6);
+        Ok(())
+    }
+}
@@ -0,0 +1,170 @@
+use std::sync::Arc;
+
+use crate::{
+    Backend, CmdExecutor, ConnectOpts, DatasetConn, FileOpts, ReplContext, ReplDisplay, ReplMsg,
+};
+use arrow::{
+    array::{RecordBatch, StringArray},
+    datatypes::{DataType, Field, Schema},
+};
+use clap::{ArgMatches, Parser};
+use reedline_repl_rs::Result;
+
+use super::ensure_table;
+
+#[derive(Debug, Parser)]
+pub struct ConnInfoOpts {
+    #[arg(short, long, help = "the name of the dataset")]
+    name: String,
+}
+
+pub fn conn_info(args: ArgMatches, context: &mut ReplContext) -> Result<Option<String>> {
+    let name = args
+        .get_one::<String>("name")
+        .expect("expect name")
+        .to_string();
+    let (msg, rx) = ReplMsg::new(ConnInfoOpts::new(name));
+
+    Ok(context.send(msg, rx))
+}
+
+impl CmdExecutor for ConnInfoOpts {
+    async fn execute<T: Backend>(&self, backend: &mut T) -> anyhow::Result<String> {
+        ensure_table(backend, &self.name).await?;
+        // a dataset created with `create` has no connection, only a query
+        if let Some(query) = backend.source_query(&self.name).await? {
+            let batch = properties_batch(
+                ["name", "format", "query"],
+                [self.name.clone(), "query".to_string(), query],
+            )?;
+            return batch.display(backend.settings()).await;
+        }
+        let opts = backend.connection_info(&self.name).await?;
+        conn_info_batch(&opts)?.display(backend.settings()).await
+    }
+}
+
+impl ConnInfoOpts {
+    pub fn new(name: String) -> Self {
+        Self { name }
+    }
+}
+
+fn conn_info_batch(opts: &ConnectOpts) -> anyhow::Result<RecordBatch> {
+    let variant = |file: &FileOpts| format!("{:?}", file.compression.get_variant());
+    let (format, location, compression) = match &opts.conn {
+        DatasetConn::Postgres(url) => ("postgres", url, None),
+        DatasetConn::Parquet(path) => ("parquet", path, None),
+        DatasetConn::CompressedParquet(file) => (
+            "parquet",
+            &file.filename,
+            Some(file.compression.as_str().to_string()),
+        ),
+        DatasetConn::Csv(file) => ("csv", &file.filename, Some(variant(file))),
+        DatasetConn::Json(file) => ("json", &file.filename, Some(variant(file))),
+        DatasetConn::Iceberg(iceberg) => ("iceberg", &iceberg.catalog_uri, None),
+        DatasetConn::Stdin(file) => (file.extension.as_str(), &file.filename, Some(variant(file))),
+    };
+    let table = match &opts.conn {
+        DatasetConn::Iceberg(iceberg) => Some(iceberg.table_identifier.clone()),
+        _ => opts.table.clone(),
+    };
+    let compression = compression.unwrap_or_default();
+    let properties = ["name", "format", "location", "compression", "table"];
+    let values = [
+        opts.name.clone(),
+        format.to_string(),
+        location.clone(),
+        compression,
+        table.unwrap_or_default(),
+    ];
+    properties_batch(properties, values)
+}
+
+fn properties_batch<const N: usize>(
+    properties: [&str; N],
+    values: [String; N],
+) -> anyhow::Result<RecordBatch> {
+    let schema = Schema::new(vec![
+        Field::new("property", DataType::Utf8, false),
+        Field::new("value", DataType::Utf8, false),
+    ]);
+    let batch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(StringArray::from_iter_values(properties)),
+            Arc::new(StringArray::from_iter_values(values)),
+        ],
+    )?;
+    Ok(batch)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::DropOpts;
+    use crate::backend::tests::{row_cells, temp_dir, test_backend};
+    use crate::cli::connect::parse_dataset_conn;
+
+    #[tokio::test]
+    async fn test_conn_info_after_csv_connect() -> anyhow::Result<()> {
+        let dir = temp_dir()?;
+        let path = dir.path().join("conn_info.csv");
+        fs::write(&path, "id,name\n1,a\n")?;
+        let filename = path.to_string_lossy().to_string();
+
+        let mut backend = test_backend();
+        let conn = parse_dataset_conn(&filename).map_err(anyhow::Error::msg)?;
+        let opts = ConnectOpts::new(conn, None, "users".to_string());
+        backend.connect(&opts).await?;
+
+        let info = backend.connection_info("users").await?;
+        match info.conn {
+            DatasetConn::Csv(file) => assert_eq!(file.filename, filename),
+            conn => panic!("unexpected connection: {:?}", conn),
+        }
+
+        let output = ConnInfoOpts::new("users".to_string())
+            .execute(&mut backend)
+            .await?;
+        assert_eq!(row_cells(&output, "format"), ["format", "csv"]);
+        assert_eq!(
+            row_cells(&output, "location"),
+            ["location", filename.as_str()]
+        );
+
+        assert!(backend.connection_info("t").await.is_err());
+        DropOpts::new("users".to_string())
+            .execute(&mut backend)
+            .await?;
+        assert!(backend.connection_info("users").await.is_err());
+        assert!(!backend.table_exists("users").await?);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_conn_info_after_create() -> anyhow::Result<()> {
+        let mut backend = test_backend();
+        let query = "SELECT id * 2 AS double FROM t";
+        backend.create_table_from_sql("doubled", query).await?;
+        assert_eq!(
+            backend.source_query("doubled").await?.as_deref(),
+            Some(query)
+        );
+        assert_eq!(backend.source_query("t").await?, None);
+
+        let output = ConnInfoOpts::new("doubled".to_string())
+            .execute(&mut backend)
+            .await?;
+        assert_eq!(row_cells(&output, "format"), ["format", "query"]);
+        assert_eq!(row_cells(&output, "query"), ["query", query]);
+
+        DropOpts::new("doubled".to_string())
+            .execute(&mut backend)
+            .await?;
+        assert_eq!(backend.source_query("doubled").await?, None);
+        Ok(())
+    }
+}
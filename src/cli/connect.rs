@@ -1,15 +1,21 @@
+use std::path::Path;
+
 use clap::{ArgMatches, Parser};
 use datafusion::datasource::file_format::file_compression_type::FileCompressionType;
 use reedline_repl_rs::Result;
 
-use crate::{Backend, CmdExecutor, ReplContext, ReplMsg};
+use crate::{Backend, CmdExecutor, DataFormat, ReplContext, ReplMsg};
 
 #[derive(Debug, Clone)]
 pub enum DatasetConn {
     Postgres(String),
     Parquet(String),
+    CompressedParquet(CompressedParquetOpts),
     Csv(FileOpts),
     Json(FileOpts),
+    Iceberg(IcebergOpts),
+    /// Input piped through stdin, given as `-`.
+    Stdin(FileOpts),
 }
 
 #[derive(Debug, Clone)]
@@ -19,14 +25,51 @@ pub struct FileOpts {
     pub compression: FileCompressionType,
 }
 
-#[derive(Debug, Parser)]
+/// A parquet file compressed as a whole, e.g. `data.parquet.lz4`.
+#[derive(Debug, Clone)]
+pub struct CompressedParquetOpts {
+    pub filename: String,
+    pub compression: ParquetCompression,
+}
+
+/// Whole-file codecs of parquet files, DataFusion's `FileCompressionType`
+/// has no lz4 or snappy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParquetCompression {
+    Gzip,
+    Bzip2,
+    Xz,
+    Zstd,
+    Lz4,
+    Snappy,
+}
+
+/// An Apache Iceberg table, given as
+/// `iceberg://<catalog_uri>/<table_identifier>[@<snapshot_id>]`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IcebergOpts {
+    pub catalog_uri: String,
+    pub table_identifier: String,
+    pub snapshot_id: Option<i64>,
+}
+
+#[derive(Debug, Clone, Parser)]
 pub struct ConnectOpts {
-    #[arg(value_parser = parse_dataset_conn,help="Connection string to the dataset, could be postgres or local file (support parquet, csv, json)")]
+    #[arg(value_parser = parse_dataset_conn,help="Connection string to the dataset, could be postgres, local file (support parquet, csv, json) or - for stdin")]
     pub conn: DatasetConn,
     #[arg(short, long, help = "If database, the name of the table")]
     pub table: Option<String>,
     #[arg(short, long, help = "the name of the dataset")]
     pub name: String,
+    #[arg(long, help = "the catalog to register the dataset in")]
+    pub catalog: Option<String>,
+    #[arg(long, value_enum, help = "the format of piped input, csv if not given")]
+    pub format: Option<DataFormat>,
+    #[arg(
+        long,
+        help = "merge the schemas of the files of a parquet directory which gained or lost columns"
+    )]
+    pub schema_evolution: bool,
 }
 
 pub fn connect(args: ArgMatches, context: &mut ReplContext) -> Result<Option<String>> {
@@ -39,33 +82,92 @@ pub fn connect(args: ArgMatches, context: &mut ReplContext) -> Result<Option<Str
         .get_one::<String>("name")
         .expect("expect name")
         .to_string();
+    let catalog = args.get_one::<String>("catalog").cloned();
+    let format = args.get_one::<DataFormat>("format").copied();
+    let schema_evolution = args.get_flag("schema_evolution");
 
-    let (msg, rx) = ReplMsg::new(ConnectOpts::new(conn, table, name));
+    let opts = ConnectOpts::new(conn, table, name)
+        .with_catalog(catalog)
+        .with_format(format)
+        .with_schema_evolution(schema_evolution);
+    let (msg, rx) = ReplMsg::new(opts);
 
     Ok(context.send(msg, rx))
 }
 
 impl ConnectOpts {
     pub fn new(conn: DatasetConn, table: Option<String>, name: String) -> Self {
-        Self { conn, table, name }
+        Self {
+            conn,
+            table,
+            name,
+            catalog: None,
+            format: None,
+            schema_evolution: false,
+        }
+    }
+
+    /// Piped input has no file extension to tell its format by.
+    pub fn with_format(mut self, format: Option<DataFormat>) -> Self {
+        if let (DatasetConn::Stdin(file_opts), Some(format)) = (&mut self.conn, format) {
+            file_opts.extension = format.extension().to_string();
+        }
+        self.format = format;
+        self
+    }
+
+    pub fn with_catalog(mut self, catalog: Option<String>) -> Self {
+        self.catalog = catalog;
+        self
+    }
+
+    pub fn with_schema_evolution(mut self, schema_evolution: bool) -> Self {
+        self.schema_evolution = schema_evolution;
+        self
+    }
+
+    /// The name of the dataset, qualified with its catalog if any.
+    pub fn table_ref(&self) -> String {
+        match &self.catalog {
+            Some(catalog) => format!("{}.{}", catalog, self.name),
+            None => self.name.clone(),
+        }
     }
 }
 
 impl CmdExecutor for ConnectOpts {
     async fn execute<T: Backend>(&self, backend: &mut T) -> anyhow::Result<String> {
         backend.connect(self).await?;
-        Ok(format!("Connected to dataset {}", self.name))
+        Ok(format!("Connected to dataset {}", self.table_ref()))
     }
 }
 
-fn parse_dataset_conn(s: &str) -> std::result::Result<DatasetConn, String> {
+pub(crate) fn parse_dataset_conn(s: &str) -> std::result::Result<DatasetConn, String> {
     let con_str = s.to_string();
+    if con_str == "-" {
+        return Ok(DatasetConn::Stdin(FileOpts {
+            filename: con_str,
+            extension: "csv".to_string(),
+            compression: FileCompressionType::UNCOMPRESSED,
+        }));
+    }
     if con_str.starts_with("postgres://") {
         return Ok(DatasetConn::Postgres(con_str.to_string()));
     }
-    if con_str.ends_with(".parquet") {
+    if let Some(uri) = con_str.strip_prefix("iceberg://") {
+        return parse_iceberg_uri(uri).map(DatasetConn::Iceberg);
+    }
+    if con_str.ends_with(".parquet") || Path::new(s).is_dir() {
         return Ok(DatasetConn::Parquet(con_str.to_string()));
     }
+    if let Some((_, ext)) = con_str.rsplit_once(".parquet.") {
+        let compression = ParquetCompression::from_extension(ext)
+            .ok_or_else(|| format!("Invalid compression type: {}", ext))?;
+        return Ok(DatasetConn::CompressedParquet(CompressedParquetOpts {
+            filename: s.to_string(),
+            compression,
+        }));
+    }
 
     let parts = con_str.split('.').collect::<Vec<_>>();
     let len = parts.len();
@@ -109,3 +211,118 @@ fn parse_dataset_conn(s: &str) -> std::result::Result<DatasetConn, String> {
         _ => Err(format!("Unsupported dataset connection: {}", con_str)),
     }
 }
+
+impl ParquetCompression {
+    fn from_extension(ext: &str) -> Option<Self> {
+        match ext {
+            "gz" => Some(Self::Gzip),
+            "bz2" => Some(Self::Bzip2),
+            "xz" => Some(Self::Xz),
+            "zstd" => Some(Self::Zstd),
+            "lz4" => Some(Self::Lz4),
+            "snappy" => Some(Self::Snappy),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Gzip => "GZIP",
+            Self::Bzip2 => "BZIP2",
+            Self::Xz => "XZ",
+            Self::Zstd => "ZSTD",
+            Self::Lz4 => "LZ4",
+            Self::Snappy => "SNAPPY",
+        }
+    }
+}
+
+fn parse_iceberg_uri(uri: &str) -> std::result::Result<IcebergOpts, String> {
+    let (uri, snapshot_id) = match uri.rsplit_once('@') {
+        Some((uri, snapshot)) => {
+            let snapshot_id = snapshot
+                .parse()
+                .map_err(|_| format!("Invalid iceberg snapshot id: {}", snapshot))?;
+            (uri, Some(snapshot_id))
+        }
+        None => (uri, None),
+    };
+    match uri.rsplit_once('/') {
+        Some((catalog_uri, table_identifier))
+            if !catalog_uri.is_empty() && !table_identifier.is_empty() =>
+        {
+            Ok(IcebergOpts {
+                catalog_uri: catalog_uri.to_string(),
+                table_identifier: table_identifier.to_string(),
+                snapshot_id,
+            })
+        }
+        _ => Err(format!(
+            "Invalid iceberg uri, expect iceberg://<catalog_uri>/<table_identifier>: {}",
+            uri
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_compressed_parquet() {
+        for (ext, compression) in [
+            ("gz", ParquetCompression::Gzip),
+            ("bz2", ParquetCompression::Bzip2),
+            ("xz", ParquetCompression::Xz),
+            ("zstd", ParquetCompression::Zstd),
+            ("lz4", ParquetCompression::Lz4),
+            ("snappy", ParquetCompression::Snappy),
+        ] {
+            let filename = format!("assets/sample.parquet.{}", ext);
+            match parse_dataset_conn(&filename) {
+                Ok(DatasetConn::CompressedParquet(opts)) => {
+                    assert_eq!(opts.filename, filename);
+                    assert_eq!(opts.compression, compression);
+                }
+                v => panic!("unexpected result for {}: {:?}", filename, v),
+            }
+        }
+        assert!(parse_dataset_conn("assets/sample.parquet.rar").is_err());
+    }
+
+    #[test]
+    fn test_parse_iceberg_uri() {
+        let expected = IcebergOpts {
+            catalog_uri: "/tmp/warehouse".to_string(),
+            table_identifier: "db.events".to_string(),
+            snapshot_id: None,
+        };
+        match parse_dataset_conn("iceberg:///tmp/warehouse/db.events") {
+            Ok(DatasetConn::Iceberg(opts)) => assert_eq!(opts, expected),
+            v => panic!("unexpected result: {:?}", v),
+        }
+        match parse_dataset_conn("iceberg:///tmp/warehouse/db.events@42") {
+            Ok(DatasetConn::Iceberg(opts)) => assert_eq!(opts.snapshot_id, Some(42)),
+            v => panic!("unexpected result: {:?}", v),
+        }
+    }
+
+    #[test]
+    fn test_parse_invalid_iceberg_uri() {
+        assert!(parse_dataset_conn("iceberg://events").is_err());
+        assert!(parse_dataset_conn("iceberg:///tmp/warehouse/").is_err());
+        assert!(parse_dataset_conn("iceberg:///tmp/warehouse/db.events@latest").is_err());
+    }
+
+    #[test]
+    fn test_parse_stdin() {
+        let opts = match parse_dataset_conn("-") {
+            Ok(conn) => ConnectOpts::new(conn, None, "t".to_string()),
+            v => panic!("unexpected result: {:?}", v),
+        };
+        match opts.with_format(Some(DataFormat::Json)).conn {
+            DatasetConn::Stdin(file) => assert_eq!(file.extension, "json"),
+            v => panic!("unexpected conn: {:?}", v),
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use clap::{ArgMatches, Parser, ValueEnum};
+use reedline_repl_rs::Result;
+
+use crate::{Backend, CmdExecutor, ReplContext, ReplDisplay, ReplMsg};
+
+use super::ensure_table;
+
+/// How the correlation of two columns is computed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum CorrelationMethod {
+    #[default]
+    Pearson,
+    Spearman,
+}
+
+#[derive(Debug, Parser)]
+pub struct CorrelateOpts {
+    #[arg(short, long, help = "the name of the dataset")]
+    name: String,
+    #[arg(
+        short,
+        long,
+        value_enum,
+        default_value_t,
+        help = "pearson for linear, spearman for rank-based correlation"
+    )]
+    method: CorrelationMethod,
+}
+
+pub fn correlate(args: ArgMatches, context: &mut ReplContext) -> Result<Option<String>> {
+    let name = args
+        .get_one::<String>("name")
+        .expect("expect name")
+        .to_string();
+    let method = args
+        .get_one::<CorrelationMethod>("method")
+        .copied()
+        .unwrap_or_default();
+    let (msg, rx) = ReplMsg::new(CorrelateOpts::new(name, method));
+
+    Ok(context.send(msg, rx))
+}
+
+impl CmdExecutor for CorrelateOpts {
+    async fn execute<T: Backend>(&self, backend: &mut T) -> anyhow::Result<String> {
+        ensure_table(backend, &self.name).await?;
+        let batch = backend.correlate(&self.name, self.method).await?;
+        batch.display(backend.settings()).await
+    }
+}
+
+impl CorrelateOpts {
+    pub fn new(name: String, method: CorrelationMethod) -> Self {
+        Self { name, method }
+    }
+}
+
+impl CorrelationMethod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pearson => "pearson",
+            Self::Spearman => "spearman",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::backend::DatafusionBackend;
+    use crate::backend::tests::backend_with;
+    use arrow::array::{AsArray, Int64Array};
+    use arrow::datatypes::Float64Type;
+
+    fn cubes_backend() -> DatafusionBackend {
+        backend_with(
+            "cubes",
+            vec![
+                ("x", Arc::new(Int64Array::from_iter_values(1..=10))),
+                (
+                    "y",
+                    Arc::new(Int64Array::from_iter_values((1..=10).map(|x| x * x * x))),
+                ),
+            ],
+        )
+    }
+
+    #[tokio::test]
+    async fn test_spearman_beats_pearson_on_monotone_data() -> anyhow::Result<()> {
+        let backend = cubes_backend();
+        let pearson = backend
+            .correlate("cubes", CorrelationMethod::Pearson)
+            .await?;
+        let spearman = backend
+            .correlate("cubes", CorrelationMethod::Spearman)
+            .await?;
+        assert_eq!(pearson.schema().field(0).name(), "pearson");
+        assert_eq!(spearman.schema().field(0).name(), "spearman");
+
+        // row x, column y
+        let pearson = pearson.column(2).as_primitive::<Float64Type>().value(0);
+        let spearman = spearman.column(2).as_primitive::<Float64Type>().value(0);
+        assert!((spearman - 1.0).abs() < 1e-9);
+        assert!(pearson < 0.95);
+        assert!(spearman > pearson);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_correlate_output() -> anyhow::Result<()> {
+        let mut backend = cubes_backend();
+        let output = CorrelateOpts::new("cubes".to_string(), CorrelationMethod::Spearman)
+            .execute(&mut backend)
+            .await?;
+        assert!(output.contains("spearman"));
+        assert_eq!(crate::backend::tests::row_count(&output), 2);
+        Ok(())
+    }
+}
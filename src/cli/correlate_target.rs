@@ -0,0 +1,187 @@
+use clap::{ArgMatches, Parser};
+use reedline_repl_rs::Result;
+
+use crate::{Backend, CmdExecutor, CorrelationMethod, ReplContext, ReplDisplay, ReplMsg};
+
+use super::ensure_table;
+
+#[derive(Debug, Parser)]
+pub struct CorrelateTargetOpts {
+    #[arg(short, long, help = "the name of the dataset")]
+    name: String,
+    #[arg(
+        short,
+        long,
+        help = "the numeric target column to rank the features by"
+    )]
+    target: String,
+    #[arg(
+        short,
+        long,
+        value_enum,
+        default_value_t,
+        help = "pearson for linear, spearman for rank-based correlation"
+    )]
+    method: CorrelationMethod,
+}
+
+pub fn correlate_target(args: ArgMatches, context: &mut ReplContext) -> Result<Option<String>> {
+    let name = args
+        .get_one::<String>("name")
+        .expect("expect name")
+        .to_string();
+    let target = args
+        .get_one::<String>("target")
+        .expect("expect target")
+        .to_string();
+    let method = args
+        .get_one::<CorrelationMethod>("method")
+        .copied()
+        .unwrap_or_default();
+    let (msg, rx) = ReplMsg::new(CorrelateTargetOpts::new(name, target, method));
+
+    Ok(context.send(msg, rx))
+}
+
+impl CmdExecutor for CorrelateTargetOpts {
+    async fn execute<T: Backend>(&self, backend: &mut T) -> anyhow::Result<String> {
+        ensure_table(backend, &self.name).await?;
+        if !backend
+            .numeric_columns(&self.name)
+            .await?
+            .contains(&self.target)
+        {
+            anyhow::bail!(
+                "Column {} of dataset {} is not numeric",
+                self.target,
+                self.name
+            );
+        }
+        let batch = backend
+            .column_correlation_with_target(&self.name, &self.target, self.method)
+            .await?;
+        batch.display(backend.settings()).await
+    }
+}
+
+impl CorrelateTargetOpts {
+    pub fn new(name: String, target: String, method: CorrelationMethod) -> Self {
+        Self {
+            name,
+            target,
+            method,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::backend::DatafusionBackend;
+    use crate::backend::tests::backend_with;
+    use arrow::array::{Array, AsArray, Int64Array, RecordBatch, StringArray};
+    use arrow::datatypes::Float64Type;
+
+    /// `up` and `down` are exact linear functions of `label`, `cube` is
+    /// monotone but not linear and `flat` never varies.
+    fn features_backend() -> DatafusionBackend {
+        let ids = (1..=10).map(|x| format!("row{}", x)).collect::<Vec<_>>();
+        backend_with(
+            "features",
+            vec![
+                ("id", Arc::new(StringArray::from(ids))),
+                (
+                    "cube",
+                    Arc::new(Int64Array::from_iter_values((1..=10).map(|x| x * x * x))),
+                ),
+                (
+                    "down",
+                    Arc::new(Int64Array::from_iter_values((1..=10).map(|x| 100 - 3 * x))),
+                ),
+                (
+                    "flat",
+                    Arc::new(Int64Array::from_iter_values((1..=10).map(|_| 7))),
+                ),
+                ("label", Arc::new(Int64Array::from_iter_values(1..=10))),
+                (
+                    "up",
+                    Arc::new(Int64Array::from_iter_values((1..=10).map(|x| 2 * x + 1))),
+                ),
+            ],
+        )
+    }
+
+    fn features(batch: &RecordBatch) -> Vec<&str> {
+        batch
+            .column(0)
+            .as_string::<i32>()
+            .iter()
+            .flatten()
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_correlation_with_target() -> anyhow::Result<()> {
+        let backend = features_backend();
+        let batch = backend
+            .column_correlation_with_target("features", "label", CorrelationMethod::Pearson)
+            .await?;
+        assert_eq!(batch.schema().field(2).name(), "abs_correlation");
+        assert_eq!(features(&batch), ["down", "up", "cube", "flat"]);
+
+        let correlation = batch.column(1).as_primitive::<Float64Type>();
+        let abs_correlation = batch.column(2).as_primitive::<Float64Type>();
+        let value = |feature: &str| {
+            let i = features(&batch).iter().position(|f| *f == feature).unwrap();
+            (correlation.value(i), abs_correlation.value(i))
+        };
+        let (down, abs_down) = value("down");
+        assert!((down + 1.0).abs() < 1e-9);
+        assert!((abs_down - 1.0).abs() < 1e-9);
+        assert!((value("up").0 - 1.0).abs() < 1e-9);
+        let (cube, abs_cube) = value("cube");
+        assert!(cube > 0.8 && cube < 0.95);
+        assert_eq!(cube, abs_cube);
+        // a constant column has no defined correlation
+        assert!(abs_correlation.is_null(3) || abs_correlation.value(3) == 0.0);
+
+        let batch = backend
+            .column_correlation_with_target("features", "label", CorrelationMethod::Spearman)
+            .await?;
+        let correlation = batch.column(1).as_primitive::<Float64Type>();
+        for i in 0..3 {
+            assert!((correlation.value(i).abs() - 1.0).abs() < 1e-9);
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_correlate_target_non_numeric() -> anyhow::Result<()> {
+        let mut backend = features_backend();
+        let err = CorrelateTargetOpts::new(
+            "features".to_string(),
+            "id".to_string(),
+            CorrelationMethod::Pearson,
+        )
+        .execute(&mut backend)
+        .await
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Column id of dataset features is not numeric"
+        );
+
+        let output = CorrelateTargetOpts::new(
+            "features".to_string(),
+            "label".to_string(),
+            CorrelationMethod::Pearson,
+        )
+        .execute(&mut backend)
+        .await?;
+        assert!(output.contains("abs_correlation"));
+        assert_eq!(crate::backend::tests::row_count(&output), 4);
+        Ok(())
+    }
+}
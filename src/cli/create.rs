@@ -0,0 +1,67 @@
+use crate::{Backend, CmdExecutor, ReplContext, ReplMsg};
+use clap::{ArgMatches, Parser};
+use reedline_repl_rs::Result;
+
+#[derive(Debug, Parser)]
+pub struct CreateTableFromSqlOpts {
+    #[arg(short, long, help = "the name of the new dataset")]
+    name: String,
+    #[arg(short, long, help = "the SQL query whose result is stored")]
+    query: String,
+}
+
+pub fn create(args: ArgMatches, context: &mut ReplContext) -> Result<Option<String>> {
+    let name = args
+        .get_one::<String>("name")
+        .expect("expect name")
+        .to_string();
+    let query = args
+        .get_one::<String>("query")
+        .expect("expect query")
+        .to_string();
+    let (msg, rx) = ReplMsg::new(CreateTableFromSqlOpts::new(name, query));
+
+    Ok(context.send(msg, rx))
+}
+
+impl CmdExecutor for CreateTableFromSqlOpts {
+    async fn execute<T: Backend>(&self, backend: &mut T) -> anyhow::Result<String> {
+        backend
+            .create_table_from_sql(&self.name, &self.query)
+            .await?;
+        Ok(format!("Materialized dataset {}", self.name))
+    }
+}
+
+impl CreateTableFromSqlOpts {
+    pub fn new(name: String, query: String) -> Self {
+        Self { name, query }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ReplDisplay;
+    use crate::backend::tests::{row_cells, test_backend};
+
+    #[tokio::test]
+    async fn test_materialized_table_outlives_source() -> anyhow::Result<()> {
+        let mut backend = test_backend();
+        let opts = CreateTableFromSqlOpts::new(
+            "small".to_string(),
+            "SELECT id * 2 AS doubled FROM t WHERE id < 3".to_string(),
+        );
+        let output = opts.execute(&mut backend).await?;
+        assert_eq!(output, "Materialized dataset small");
+
+        backend.deregister_table("t")?;
+        let count = backend.table("small").await?.count().await?;
+        assert_eq!(count, 3);
+
+        let output = backend.list(false, None).await?;
+        let output = output.display(backend.settings()).await?;
+        assert_eq!(row_cells(&output, "small"), ["small", "MATERIALIZED"]);
+        Ok(())
+    }
+}
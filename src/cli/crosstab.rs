@@ -0,0 +1,102 @@
+use clap::{ArgMatches, Parser};
+use reedline_repl_rs::Result;
+
+use crate::{Backend, CmdExecutor, ReplContext, ReplDisplay, ReplMsg};
+
+use super::ensure_table;
+
+#[derive(Debug, Parser)]
+pub struct CrossTabOpts {
+    #[arg(short, long, help = "the name of the dataset")]
+    name: String,
+    #[arg(short, long, help = "the column whose values become the rows")]
+    row_col: String,
+    #[arg(short, long, help = "the column whose values become the columns")]
+    col_col: String,
+}
+
+pub fn crosstab(args: ArgMatches, context: &mut ReplContext) -> Result<Option<String>> {
+    let name = args
+        .get_one::<String>("name")
+        .expect("expect name")
+        .to_string();
+    let row_col = args
+        .get_one::<String>("row_col")
+        .expect("expect row_col")
+        .to_string();
+    let col_col = args
+        .get_one::<String>("col_col")
+        .expect("expect col_col")
+        .to_string();
+    let (msg, rx) = ReplMsg::new(CrossTabOpts::new(name, row_col, col_col));
+
+    Ok(context.send(msg, rx))
+}
+
+impl CmdExecutor for CrossTabOpts {
+    async fn execute<T: Backend>(&self, backend: &mut T) -> anyhow::Result<String> {
+        ensure_table(backend, &self.name).await?;
+        let batch = backend
+            .cross_tabulate(&self.name, &self.row_col, &self.col_col)
+            .await?;
+        batch.display(backend.settings()).await
+    }
+}
+
+impl CrossTabOpts {
+    pub fn new(name: String, row_col: String, col_col: String) -> Self {
+        Self {
+            name,
+            row_col,
+            col_col,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::backend::DatafusionBackend;
+    use crate::backend::tests::{row_cells, row_count};
+    use arrow::array::{RecordBatch, StringArray};
+    use arrow::datatypes::{DataType, Field, Schema};
+
+    #[tokio::test]
+    async fn test_cross_tabulate() -> anyhow::Result<()> {
+        let mut backend = DatafusionBackend::new();
+        let schema = Schema::new(vec![
+            Field::new("sex", DataType::Utf8, false),
+            Field::new("smoker", DataType::Utf8, true),
+        ]);
+        let batch = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![
+                Arc::new(StringArray::from(vec!["m", "m", "f", "m", "f", "f", "f"])),
+                Arc::new(StringArray::from(vec![
+                    Some("yes"),
+                    Some("no"),
+                    Some("yes"),
+                    Some("yes"),
+                    Some("no"),
+                    Some("no"),
+                    None,
+                ])),
+            ],
+        )?;
+        backend.register_batch("patients", batch)?;
+
+        let opts = CrossTabOpts::new(
+            "patients".to_string(),
+            "sex".to_string(),
+            "smoker".to_string(),
+        );
+        let output = opts.execute(&mut backend).await?;
+        assert_eq!(row_count(&output), 2);
+        assert_eq!(row_cells(&output, "sex"), ["sex", "no", "yes"]);
+        assert_eq!(row_cells(&output, "f"), ["f", "2", "1"]);
+        assert_eq!(row_cells(&output, "m"), ["m", "1", "2"]);
+        Ok(())
+    }
+}
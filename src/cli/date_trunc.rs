@@ -0,0 +1,211 @@
+use clap::{ArgMatches, Parser, ValueEnum};
+use reedline_repl_rs::Result;
+
+use crate::{Backend, CmdExecutor, ReplContext, ReplMsg};
+
+use super::ensure_table;
+
+/// The period the timestamps are truncated to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum TruncUnit {
+    Year,
+    #[default]
+    Month,
+    Week,
+    Day,
+    Hour,
+}
+
+/// The aggregate computed over each period.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum AggFunc {
+    #[default]
+    Sum,
+    Count,
+    Mean,
+}
+
+#[derive(Debug, Parser)]
+pub struct DateTruncateOpts {
+    #[arg(short, long, help = "the name of the dataset")]
+    name: String,
+    #[arg(short, long, help = "the timestamp or date column")]
+    time_col: String,
+    #[arg(
+        short,
+        long,
+        value_enum,
+        default_value_t,
+        help = "year, month, week, day or hour"
+    )]
+    unit: TruncUnit,
+    #[arg(
+        short,
+        long,
+        help = "the column to aggregate, optional when counting rows"
+    )]
+    agg_col: Option<String>,
+    #[arg(long, value_enum, default_value_t, help = "sum, count or mean")]
+    agg_func: AggFunc,
+    #[arg(short, long, help = "the name of the new dataset")]
+    output: String,
+}
+
+pub fn date_trunc(args: ArgMatches, context: &mut ReplContext) -> Result<Option<String>> {
+    let name = args
+        .get_one::<String>("name")
+        .expect("expect name")
+        .to_string();
+    let time_col = args
+        .get_one::<String>("time_col")
+        .expect("expect time_col")
+        .to_string();
+    let unit = args
+        .get_one::<TruncUnit>("unit")
+        .copied()
+        .unwrap_or_default();
+    let agg_col = args.get_one::<String>("agg_col").cloned();
+    let agg_func = args
+        .get_one::<AggFunc>("agg_func")
+        .copied()
+        .unwrap_or_default();
+    let output = args
+        .get_one::<String>("output")
+        .expect("expect output")
+        .to_string();
+    let opts = DateTruncateOpts::new(name, time_col, unit, output).with_agg(agg_col, agg_func);
+    let (msg, rx) = ReplMsg::new(opts);
+
+    Ok(context.send(msg, rx))
+}
+
+impl CmdExecutor for DateTruncateOpts {
+    async fn execute<T: Backend>(&self, backend: &mut T) -> anyhow::Result<String> {
+        ensure_table(backend, &self.name).await?;
+        backend
+            .create_table_from_sql(&self.output, &self.sql()?)
+            .await?;
+        Ok(format!(
+            "Materialized {} periods as dataset {}",
+            self.unit.as_str(),
+            self.output
+        ))
+    }
+}
+
+impl DateTruncateOpts {
+    pub fn new(name: String, time_col: String, unit: TruncUnit, output: String) -> Self {
+        Self {
+            name,
+            time_col,
+            unit,
+            agg_col: None,
+            agg_func: AggFunc::default(),
+            output,
+        }
+    }
+
+    pub fn with_agg(mut self, agg_col: Option<String>, agg_func: AggFunc) -> Self {
+        self.agg_col = agg_col;
+        self.agg_func = agg_func;
+        self
+    }
+
+    fn sql(&self) -> anyhow::Result<String> {
+        let value = match (self.agg_func, &self.agg_col) {
+            (AggFunc::Count, None) => "COUNT(*)".to_string(),
+            (AggFunc::Count, Some(col)) => format!("COUNT(\"{}\")", col),
+            (AggFunc::Sum, Some(col)) => format!("SUM(\"{}\")", col),
+            (AggFunc::Mean, Some(col)) => format!("AVG(CAST(\"{}\" AS DOUBLE))", col),
+            (func, None) => anyhow::bail!("The {:?} aggregate needs an --agg-col", func),
+        };
+        // dates and strings are truncated as timestamps
+        let period = format!(
+            "DATE_TRUNC('{}', CAST(\"{}\" AS TIMESTAMP))",
+            self.unit.as_str(),
+            self.time_col
+        );
+        Ok(format!(
+            "SELECT {0} AS period, {1} AS value FROM {2} GROUP BY {0} ORDER BY period",
+            period, value, self.name
+        ))
+    }
+}
+
+impl TruncUnit {
+    fn as_str(&self) -> &'static str {
+        match self {
+            TruncUnit::Year => "year",
+            TruncUnit::Month => "month",
+            TruncUnit::Week => "week",
+            TruncUnit::Day => "day",
+            TruncUnit::Hour => "hour",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::ReplDisplay;
+    use crate::backend::DatafusionBackend;
+    use crate::backend::tests::{backend_with, row_cells};
+    use arrow::array::{Int64Array, StringArray};
+
+    fn sales_backend() -> DatafusionBackend {
+        backend_with(
+            "sales",
+            vec![
+                (
+                    "sold_at",
+                    Arc::new(StringArray::from(vec![
+                        "2024-03-31T23:59:00",
+                        "2024-01-05T10:00:00",
+                        "2024-01-20T08:30:00",
+                        "2024-02-01T00:00:00",
+                        "2024-03-15T12:00:00",
+                    ])),
+                ),
+                ("amount", Arc::new(Int64Array::from(vec![5, 10, 20, 7, 1]))),
+            ],
+        )
+    }
+
+    async fn monthly(agg_col: Option<&str>, agg_func: AggFunc) -> anyhow::Result<String> {
+        let mut backend = sales_backend();
+        let opts = DateTruncateOpts::new(
+            "sales".to_string(),
+            "sold_at".to_string(),
+            TruncUnit::Month,
+            "monthly".to_string(),
+        )
+        .with_agg(agg_col.map(|c| c.to_string()), agg_func);
+        let output = opts.execute(&mut backend).await?;
+        assert_eq!(output, "Materialized month periods as dataset monthly");
+
+        let sql = "SELECT CAST(period AS DATE) AS month, value FROM monthly";
+        let batches = backend.sql(sql).await?;
+        batches.display(backend.settings()).await
+    }
+
+    #[tokio::test]
+    async fn test_date_trunc_monthly_sum() -> anyhow::Result<()> {
+        let output = monthly(Some("amount"), AggFunc::Sum).await?;
+        assert_eq!(row_cells(&output, "2024-01-01"), ["2024-01-01", "30"]);
+        assert_eq!(row_cells(&output, "2024-02-01"), ["2024-02-01", "7"]);
+        assert_eq!(row_cells(&output, "2024-03-01"), ["2024-03-01", "6"]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_date_trunc_monthly_count_and_mean() -> anyhow::Result<()> {
+        let output = monthly(None, AggFunc::Count).await?;
+        assert_eq!(row_cells(&output, "2024-01-01"), ["2024-01-01", "2"]);
+        let output = monthly(Some("amount"), AggFunc::Mean).await?;
+        assert_eq!(row_cells(&output, "2024-03-01"), ["2024-03-01", "3.0"]);
+        assert!(monthly(None, AggFunc::Sum).await.is_err());
+        Ok(())
+    }
+}
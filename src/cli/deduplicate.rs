@@ -0,0 +1,202 @@
+use clap::{ArgMatches, Parser, ValueEnum};
+use reedline_repl_rs::Result;
+
+use crate::{Backend, CmdExecutor, ReplContext, ReplMsg};
+
+use super::ensure_table;
+
+/// Which row of a group of duplicates is kept.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum KeepRow {
+    #[default]
+    First,
+    Last,
+}
+
+#[derive(Debug, Parser)]
+pub struct DeduplicateOpts {
+    #[arg(short, long, help = "the name of the dataset")]
+    name: String,
+    #[arg(
+        short,
+        long,
+        value_delimiter = ',',
+        help = "the columns identifying a row, all columns by default"
+    )]
+    key: Vec<String>,
+    #[arg(
+        long,
+        value_enum,
+        default_value_t,
+        help = "keep the first or last duplicate"
+    )]
+    keep: KeepRow,
+    #[arg(
+        short,
+        long,
+        required_unless_present = "inplace",
+        conflicts_with = "inplace",
+        help = "the name of the new dataset"
+    )]
+    output: Option<String>,
+    #[arg(long, help = "replace the dataset itself")]
+    inplace: bool,
+}
+
+pub fn deduplicate(args: ArgMatches, context: &mut ReplContext) -> Result<Option<String>> {
+    let name = args
+        .get_one::<String>("name")
+        .expect("expect name")
+        .to_string();
+    let key = args
+        .get_many::<String>("key")
+        .map(|key| key.cloned().collect())
+        .unwrap_or_default();
+    let keep = args.get_one::<KeepRow>("keep").copied().unwrap_or_default();
+    let output = args.get_one::<String>("output").cloned();
+    let opts = DeduplicateOpts::new(name, output)
+        .with_key(key)
+        .with_keep(keep);
+    let (msg, rx) = ReplMsg::new(opts);
+
+    Ok(context.send(msg, rx))
+}
+
+impl CmdExecutor for DeduplicateOpts {
+    async fn execute<T: Backend>(&self, backend: &mut T) -> anyhow::Result<String> {
+        ensure_table(backend, &self.name).await?;
+        let output = self.output.as_deref();
+        let rows = backend
+            .deduplicate(&self.name, &self.key, self.keep == KeepRow::Last, output)
+            .await?;
+        Ok(format!(
+            "Kept {} distinct row(s) in dataset {}",
+            rows,
+            output.unwrap_or(&self.name)
+        ))
+    }
+}
+
+impl DeduplicateOpts {
+    /// Without an output the dataset is deduplicated in place.
+    pub fn new(name: String, output: Option<String>) -> Self {
+        Self {
+            name,
+            key: vec![],
+            keep: KeepRow::default(),
+            inplace: output.is_none(),
+            output,
+        }
+    }
+
+    pub fn with_key(mut self, key: Vec<String>) -> Self {
+        self.key = key;
+        self
+    }
+
+    pub fn with_keep(mut self, keep: KeepRow) -> Self {
+        self.keep = keep;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::backend::DatafusionBackend;
+    use crate::backend::tests::{backend_with, connect_partitioned_csv};
+    use arrow::array::{AsArray, Int64Array, RecordBatch, StringArray};
+    use arrow::datatypes::Int64Type;
+
+    fn orders_backend() -> DatafusionBackend {
+        backend_with(
+            "orders",
+            vec![
+                ("id", Arc::new(Int64Array::from(vec![1, 1, 2, 2, 3]))),
+                (
+                    "status",
+                    Arc::new(StringArray::from(vec!["new", "new", "new", "paid", "new"])),
+                ),
+            ],
+        )
+    }
+
+    async fn statuses(backend: &DatafusionBackend, name: &str) -> anyhow::Result<Vec<String>> {
+        let batches = backend.table(name).await?.collect().await?;
+        Ok(batches
+            .iter()
+            .flat_map(|b| b.column(1).as_string::<i32>().iter().flatten())
+            .map(|s| s.to_string())
+            .collect())
+    }
+
+    #[tokio::test]
+    async fn test_deduplicate_all_columns() -> anyhow::Result<()> {
+        let mut backend = orders_backend();
+        let opts = DeduplicateOpts::new("orders".to_string(), Some("unique".to_string()));
+        let output = opts.execute(&mut backend).await?;
+        assert_eq!(output, "Kept 4 distinct row(s) in dataset unique");
+        assert_eq!(
+            statuses(&backend, "unique").await?,
+            ["new", "new", "paid", "new"]
+        );
+        // the source is left untouched
+        assert_eq!(statuses(&backend, "orders").await?.len(), 5);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_deduplicate_key_keep_last_inplace() -> anyhow::Result<()> {
+        let mut backend = orders_backend();
+        let opts = DeduplicateOpts::new("orders".to_string(), None)
+            .with_key(vec!["id".to_string()])
+            .with_keep(KeepRow::Last);
+        let output = opts.execute(&mut backend).await?;
+        assert_eq!(output, "Kept 3 distinct row(s) in dataset orders");
+        assert_eq!(statuses(&backend, "orders").await?, ["new", "paid", "new"]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_deduplicate_unknown_key() {
+        let mut backend = orders_backend();
+        let opts =
+            DeduplicateOpts::new("orders".to_string(), None).with_key(vec!["missing".to_string()]);
+        let err = opts.execute(&mut backend).await.unwrap_err();
+        assert_eq!(err.to_string(), "Dataset orders has no column(s): missing");
+    }
+
+    #[tokio::test]
+    async fn test_deduplicate_partitioned_source() -> anyhow::Result<()> {
+        let mut content = "id,key\n".to_string();
+        for id in 0..10_000 {
+            content.push_str(&format!("{},{}\n", id, id % 10));
+        }
+        let mut backend = DatafusionBackend::new();
+        let _dir = connect_partitioned_csv(&mut backend, "events", &content).await?;
+        let ids = |batches: Vec<RecordBatch>| {
+            batches
+                .iter()
+                .flat_map(|b| b.column(0).as_primitive::<Int64Type>().values().to_vec())
+                .collect::<Vec<_>>()
+        };
+
+        DeduplicateOpts::new("events".to_string(), Some("first".to_string()))
+            .with_key(vec!["key".to_string()])
+            .execute(&mut backend)
+            .await?;
+        let first = ids(backend.table("first").await?.collect().await?);
+        assert_eq!(first, (0..10).collect::<Vec<_>>());
+
+        DeduplicateOpts::new("events".to_string(), Some("last".to_string()))
+            .with_key(vec!["key".to_string()])
+            .with_keep(KeepRow::Last)
+            .execute(&mut backend)
+            .await?;
+        let last = ids(backend.table("last").await?.collect().await?);
+        assert_eq!(last, (9_990..10_000).collect::<Vec<_>>());
+        Ok(())
+    }
+}
@@ -1,11 +1,55 @@
 use crate::{Backend, CmdExecutor, ReplContext, ReplDisplay, ReplMsg};
+use arrow::{
+    array::RecordBatch,
+    json::{WriterBuilder, writer::JsonArray},
+};
 use clap::{ArgMatches, Parser};
 use reedline_repl_rs::Result;
+use serde_json::{Map, Value};
+
+use super::ensure_table;
 
 #[derive(Debug, Parser)]
 pub struct DescribeOpts {
     #[arg(short, long, help = "the name of the dataset")]
-    name: String,
+    pub name: String,
+    #[arg(
+        short,
+        long,
+        help = "compute one statistic at a time to reduce memory usage"
+    )]
+    pub chunked: bool,
+    #[arg(long, help = "describe a random sample of the given number of rows")]
+    pub sample_size: Option<usize>,
+    #[arg(
+        short,
+        long = "where",
+        help = "only describe the rows matching the predicate"
+    )]
+    pub predicate: Option<String>,
+    #[arg(long, help = "compute the statistics again instead of using the cache")]
+    pub no_cache: bool,
+    #[arg(
+        long,
+        conflicts_with = "text_only",
+        help = "only describe numeric columns"
+    )]
+    pub numeric_only: bool,
+    #[arg(
+        long,
+        visible_alias = "string-only",
+        help = "only describe string columns, by their length"
+    )]
+    pub text_only: bool,
+    #[arg(
+        long,
+        value_delimiter = ',',
+        conflicts_with_all = ["numeric_only", "text_only"],
+        help = "only describe the given columns, comma separated or repeated"
+    )]
+    pub columns: Vec<String>,
+    #[arg(long, help = "print the statistics of each column as a JSON object")]
+    pub json_output: bool,
 }
 
 pub fn describe(args: ArgMatches, context: &mut ReplContext) -> Result<Option<String>> {
@@ -13,20 +57,167 @@ pub fn describe(args: ArgMatches, context: &mut ReplContext) -> Result<Option<St
         .get_one::<String>("name")
         .expect("expect name")
         .to_string();
-    let (msg, rx) = ReplMsg::new(DescribeOpts::new(name));
+    let opts = DescribeOpts::new(name)
+        .with_chunked(args.get_flag("chunked"))
+        .with_sample_size(args.get_one::<usize>("sample_size").copied())
+        .with_predicate(args.get_one::<String>("predicate").cloned())
+        .with_no_cache(args.get_flag("no_cache"))
+        .with_numeric_only(args.get_flag("numeric_only"))
+        .with_text_only(args.get_flag("text_only"))
+        .with_columns(
+            args.get_many::<String>("columns")
+                .map(|columns| columns.cloned().collect())
+                .unwrap_or_default(),
+        )
+        .with_json_output(args.get_flag("json_output"));
+    let (msg, rx) = ReplMsg::new(opts);
 
     Ok(context.send(msg, rx))
 }
 
 impl CmdExecutor for DescribeOpts {
     async fn execute<T: Backend>(&self, backend: &mut T) -> anyhow::Result<String> {
-        let df = backend.describe(&self.name).await?;
-        df.display().await
+        ensure_table(backend, &self.name).await?;
+        let df = backend.describe(self).await?;
+        if self.json_output {
+            return describe_json(&df.into_batches().await?);
+        }
+        df.display(backend.settings()).await
     }
 }
 
 impl DescribeOpts {
     pub fn new(name: String) -> Self {
-        Self { name }
+        Self {
+            name,
+            chunked: false,
+            sample_size: None,
+            predicate: None,
+            no_cache: false,
+            numeric_only: false,
+            text_only: false,
+            columns: Vec::new(),
+            json_output: false,
+        }
+    }
+
+    pub fn with_chunked(mut self, chunked: bool) -> Self {
+        self.chunked = chunked;
+        self
+    }
+
+    pub fn with_sample_size(mut self, sample_size: Option<usize>) -> Self {
+        self.sample_size = sample_size;
+        self
+    }
+
+    pub fn with_predicate(mut self, predicate: Option<String>) -> Self {
+        self.predicate = predicate;
+        self
+    }
+
+    pub fn with_no_cache(mut self, no_cache: bool) -> Self {
+        self.no_cache = no_cache;
+        self
+    }
+
+    pub fn with_numeric_only(mut self, numeric_only: bool) -> Self {
+        self.numeric_only = numeric_only;
+        self
+    }
+
+    pub fn with_text_only(mut self, text_only: bool) -> Self {
+        self.text_only = text_only;
+        self
+    }
+
+    pub fn with_columns(mut self, columns: Vec<String>) -> Self {
+        self.columns = columns;
+        self
+    }
+
+    pub fn with_json_output(mut self, json_output: bool) -> Self {
+        self.json_output = json_output;
+        self
+    }
+}
+
+/// Pivot the describe result into `{"column": {"statistic": value}}`. Rows
+/// are either one statistic of every column, keyed by `describe`, or every
+/// statistic of one column, keyed by `column_name`.
+fn describe_json(batches: &[RecordBatch]) -> anyhow::Result<String> {
+    let mut writer = WriterBuilder::new()
+        .with_explicit_nulls(true)
+        .build::<_, JsonArray>(Vec::new());
+    for batch in batches {
+        writer.write(batch)?;
+    }
+    writer.finish()?;
+    let rows: Vec<Map<String, Value>> = serde_json::from_slice(&writer.into_inner())?;
+
+    let mut columns = Map::new();
+    for mut row in rows {
+        if let Some(Value::String(stat)) = row.remove("describe") {
+            for (column, value) in row {
+                if let Value::Object(stats) = columns
+                    .entry(column)
+                    .or_insert_with(|| Value::Object(Map::new()))
+                {
+                    stats.insert(stat.clone(), value);
+                }
+            }
+        } else if let Some(Value::String(column)) = row.remove("column_name") {
+            columns.insert(column, Value::Object(row));
+        }
+    }
+    Ok(serde_json::to_string(&Value::Object(columns))?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::backend::tests::test_backend;
+    use arrow::array::{Float64Array, StringArray};
+    use arrow::datatypes::{DataType, Field, Schema};
+    use serde_json::json;
+
+    #[test]
+    fn test_describe_json_pivots_statistics() -> anyhow::Result<()> {
+        let schema = Schema::new(vec![
+            Field::new("describe", DataType::Utf8, false),
+            Field::new("a", DataType::Float64, true),
+            Field::new("b", DataType::Float64, true),
+        ]);
+        let batch = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![
+                Arc::new(StringArray::from(vec!["mean", "min"])),
+                Arc::new(Float64Array::from(vec![1.5, 0.0])),
+                Arc::new(Float64Array::from(vec![Some(2.5), None])),
+            ],
+        )?;
+        let output = describe_json(&[batch])?;
+        let value: Value = serde_json::from_str(&output)?;
+        assert_eq!(
+            value,
+            json!({
+                "a": {"mean": 1.5, "min": 0.0},
+                "b": {"mean": 2.5, "min": null},
+            })
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_describe_json_output() -> anyhow::Result<()> {
+        let mut backend = test_backend();
+        let opts = DescribeOpts::new("t".to_string()).with_json_output(true);
+        let output = opts.execute(&mut backend).await?;
+        let value: Value = serde_json::from_str(&output)?;
+        assert_eq!(value["id"]["total"], json!(10.0));
+        assert_eq!(value["id"]["max"], json!(9.0));
+        Ok(())
     }
 }
@@ -0,0 +1,93 @@
+use clap::{ArgMatches, Parser};
+use reedline_repl_rs::Result;
+
+use crate::{Backend, CmdExecutor, ReplContext, ReplDisplay, ReplMsg};
+
+use super::ensure_table;
+
+#[derive(Debug, Parser)]
+pub struct DescribeSampleOpts {
+    #[arg(short, long, help = "the name of the dataset")]
+    name: String,
+    #[arg(
+        short,
+        long,
+        default_value_t = 10000,
+        help = "describe a random sample of this many rows of larger datasets"
+    )]
+    sample_size: usize,
+}
+
+pub fn describe_sample(args: ArgMatches, context: &mut ReplContext) -> Result<Option<String>> {
+    let name = args
+        .get_one::<String>("name")
+        .expect("expect name")
+        .to_string();
+    let sample_size = args
+        .get_one::<usize>("sample_size")
+        .copied()
+        .unwrap_or(10000);
+    let (msg, rx) = ReplMsg::new(DescribeSampleOpts::new(name, sample_size));
+
+    Ok(context.send(msg, rx))
+}
+
+impl CmdExecutor for DescribeSampleOpts {
+    async fn execute<T: Backend>(&self, backend: &mut T) -> anyhow::Result<String> {
+        ensure_table(backend, &self.name).await?;
+        let output = backend
+            .describe_sample(&self.name, self.sample_size)
+            .await?;
+        output.display(backend.settings()).await
+    }
+}
+
+impl DescribeSampleOpts {
+    pub fn new(name: String, sample_size: usize) -> Self {
+        Self { name, sample_size }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DescribeOpts;
+    use crate::backend::tests::test_backend;
+
+    /// The header and the first cell of every row, which don't depend on
+    /// the values described.
+    fn structure(output: &str) -> Vec<String> {
+        output
+            .lines()
+            .filter(|line| line.starts_with('|'))
+            .map(|line| {
+                line.split('|')
+                    .nth(1)
+                    .unwrap_or_default()
+                    .trim()
+                    .to_string()
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_describe_sample() -> anyhow::Result<()> {
+        let mut backend = test_backend();
+        let full = DescribeOpts::new("t".to_string())
+            .execute(&mut backend)
+            .await?;
+
+        let sampled = DescribeSampleOpts::new("t".to_string(), 5)
+            .execute(&mut backend)
+            .await?;
+        assert!(sampled.ends_with("(sample: 5 of 10 rows, ratio 0.5000)"));
+        assert_eq!(structure(&sampled), structure(&full));
+
+        let whole = DescribeSampleOpts::new("t".to_string(), 100)
+            .execute(&mut backend)
+            .await?;
+        assert!(whole.ends_with("(sample: 10 of 10 rows, ratio 1.0000)"));
+        assert_eq!(whole.lines().next(), full.lines().next());
+        Ok(())
+    }
+}
@@ -0,0 +1,90 @@
+use crate::{Backend, CmdExecutor, ReplContext, ReplDisplay, ReplMsg};
+use clap::{ArgMatches, Parser};
+use reedline_repl_rs::Result;
+
+use super::ensure_table;
+
+#[derive(Debug, Parser)]
+pub struct DetectDriftOpts {
+    #[arg(short, long, help = "the name of the connected dataset")]
+    name: String,
+}
+
+pub fn detect_drift(args: ArgMatches, context: &mut ReplContext) -> Result<Option<String>> {
+    let name = args
+        .get_one::<String>("name")
+        .expect("expect name")
+        .to_string();
+    let (msg, rx) = ReplMsg::new(DetectDriftOpts::new(name));
+
+    Ok(context.send(msg, rx))
+}
+
+impl CmdExecutor for DetectDriftOpts {
+    async fn execute<T: Backend>(&self, backend: &mut T) -> anyhow::Result<String> {
+        ensure_table(backend, &self.name).await?;
+        let changes = backend.detect_schema_changes(&self.name).await?;
+        if changes.num_rows() == 0 {
+            return Ok(format!("No schema changes for dataset {}", self.name));
+        }
+        changes.display(backend.settings()).await
+    }
+}
+
+impl DetectDriftOpts {
+    pub fn new(name: String) -> Self {
+        Self { name }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::ConnectOpts;
+    use crate::backend::DatafusionBackend;
+    use crate::backend::tests::{row_cells, row_count, temp_dir};
+    use crate::cli::connect::parse_dataset_conn;
+
+    #[tokio::test]
+    async fn test_detect_added_column() -> anyhow::Result<()> {
+        let dir = temp_dir()?;
+        let path = dir.path().join("detect_drift.csv");
+        fs::write(&path, "id,name,score\n1,a,1.5\n")?;
+        let filename = path.to_string_lossy().to_string();
+
+        let mut backend = DatafusionBackend::new();
+        let conn = parse_dataset_conn(&filename).map_err(anyhow::Error::msg)?;
+        backend
+            .connect(&ConnectOpts::new(conn, None, "players".to_string()))
+            .await?;
+
+        let opts = DetectDriftOpts::new("players".to_string());
+        let output = opts.execute(&mut backend).await?;
+        assert_eq!(output, "No schema changes for dataset players");
+
+        fs::write(&path, "id,name,score,team\n1,a,1.5,red\n")?;
+        let output = opts.execute(&mut backend).await?;
+        assert_eq!(row_count(&output), 1);
+        assert_eq!(
+            row_cells(&output, "team"),
+            ["team", "added", "NULL", "Utf8"]
+        );
+
+        // the registered dataset keeps its schema until connected again
+        let schema = backend.table("players").await?.schema().clone();
+        assert_eq!(schema.fields().len(), 3);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_detect_drift_requires_connect() {
+        let mut backend = crate::backend::tests::test_backend();
+        let err = DetectDriftOpts::new("t".to_string())
+            .execute(&mut backend)
+            .await
+            .unwrap_err();
+        assert_eq!(err.to_string(), "Dataset t was not registered with connect");
+    }
+}
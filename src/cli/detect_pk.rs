@@ -0,0 +1,239 @@
+use std::sync::Arc;
+
+use arrow::{
+    array::{AsArray, BooleanArray, RecordBatch, StringArray},
+    datatypes::{DataType, Field, Schema},
+};
+use clap::{ArgMatches, Parser};
+use reedline_repl_rs::Result;
+
+use crate::{Backend, CmdExecutor, ReplContext, ReplDisplay, ReplMsg};
+
+use super::ensure_table;
+
+#[derive(Debug, Parser)]
+pub struct DetectPkOpts {
+    #[arg(short, long, help = "the name of the dataset")]
+    name: String,
+    #[arg(
+        short,
+        long,
+        default_value_t = 1,
+        help = "the most columns combined into a key"
+    )]
+    max_cols: usize,
+}
+
+pub fn detect_pk(args: ArgMatches, context: &mut ReplContext) -> Result<Option<String>> {
+    let name = args
+        .get_one::<String>("name")
+        .expect("expect name")
+        .to_string();
+    let max_cols = args.get_one::<usize>("max_cols").copied().unwrap_or(1);
+    let (msg, rx) = ReplMsg::new(DetectPkOpts::new(name).with_max_cols(max_cols));
+
+    Ok(context.send(msg, rx))
+}
+
+impl CmdExecutor for DetectPkOpts {
+    async fn execute<T: Backend>(&self, backend: &mut T) -> anyhow::Result<String> {
+        ensure_table(backend, &self.name).await?;
+        let columns = backend.column_names(&self.name).await?;
+        let mut results: Vec<(Vec<String>, bool, bool)> = Vec::new();
+        for size in 1..=self.max_cols.min(columns.len()) {
+            for key in combinations(&columns, size) {
+                // a superset of a candidate is never a minimal key
+                let covered = results.iter().any(|(k, unique, not_null)| {
+                    *unique && *not_null && k.iter().all(|c| key.contains(c))
+                });
+                if covered {
+                    continue;
+                }
+                let batches = backend.sql(&self.sql(&key)).await?.into_batches().await?;
+                let batch = batches
+                    .iter()
+                    .find(|batch| batch.num_rows() > 0)
+                    .ok_or_else(|| anyhow::anyhow!("No result for key {}", key.join(", ")))?;
+                let unique = batch.column(0).as_boolean().value(0);
+                let not_null = batch.column(1).as_boolean().value(0);
+                results.push((key, unique, not_null));
+            }
+        }
+        pk_batch(&results)?.display(backend.settings()).await
+    }
+}
+
+impl DetectPkOpts {
+    pub fn new(name: String) -> Self {
+        Self { name, max_cols: 1 }
+    }
+
+    pub fn with_max_cols(mut self, max_cols: usize) -> Self {
+        self.max_cols = max_cols;
+        self
+    }
+
+    /// Whether the key has no duplicate and no null, in a single row.
+    fn sql(&self, key: &[String]) -> String {
+        let quoted = key
+            .iter()
+            .map(|column| format!("\"{}\"", column))
+            .collect::<Vec<_>>();
+        let nulls = quoted
+            .iter()
+            .map(|column| format!("{} IS NULL", column))
+            .collect::<Vec<_>>();
+        format!(
+            "SELECT d.n = a.n AS \"unique\", a.nulls = 0 AS not_null FROM \
+            (SELECT COUNT(*) AS n, COALESCE(SUM(CASE WHEN {0} THEN 1 ELSE 0 END), 0) AS nulls \
+            FROM {1}) AS a CROSS JOIN \
+            (SELECT COUNT(*) AS n FROM (SELECT DISTINCT {2} FROM {1}) AS s) AS d",
+            nulls.join(" OR "),
+            self.name,
+            quoted.join(", ")
+        )
+    }
+}
+
+/// All the ways to pick `size` columns, keeping their order.
+fn combinations(columns: &[String], size: usize) -> Vec<Vec<String>> {
+    if size == 0 {
+        return vec![vec![]];
+    }
+    (0..columns.len())
+        .flat_map(|i| {
+            combinations(&columns[i + 1..], size - 1)
+                .into_iter()
+                .map(move |mut rest| {
+                    rest.insert(0, columns[i].clone());
+                    rest
+                })
+        })
+        .collect()
+}
+
+fn pk_batch(results: &[(Vec<String>, bool, bool)]) -> anyhow::Result<RecordBatch> {
+    let schema = Schema::new(vec![
+        Field::new("column", DataType::Utf8, false),
+        Field::new("unique", DataType::Boolean, false),
+        Field::new("not_null", DataType::Boolean, false),
+        Field::new("is_pk_candidate", DataType::Boolean, false),
+    ]);
+    let names = results.iter().map(|(key, _, _)| key.join(", "));
+    let unique = results.iter().map(|(_, unique, _)| *unique);
+    let not_null = results.iter().map(|(_, _, not_null)| *not_null);
+    let candidate = results
+        .iter()
+        .map(|(_, unique, not_null)| *unique && *not_null);
+    Ok(RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(StringArray::from_iter_values(names)),
+            Arc::new(BooleanArray::from_iter(unique.map(Some))),
+            Arc::new(BooleanArray::from_iter(not_null.map(Some))),
+            Arc::new(BooleanArray::from_iter(candidate.map(Some))),
+        ],
+    )?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::DatafusionBackend;
+    use crate::backend::tests::{backend_with, row_cells, row_count};
+    use arrow::array::Int64Array;
+
+    fn enrollments_backend() -> DatafusionBackend {
+        backend_with(
+            "enrollments",
+            vec![
+                ("id", Arc::new(Int64Array::from(vec![1, 2, 3, 4]))),
+                (
+                    "student",
+                    Arc::new(StringArray::from(vec!["ann", "ann", "bob", "bob"])),
+                ),
+                (
+                    "course",
+                    Arc::new(StringArray::from(vec!["math", "art", "math", "art"])),
+                ),
+                (
+                    "email",
+                    Arc::new(StringArray::from(vec![
+                        Some("a@x"),
+                        Some("b@x"),
+                        None,
+                        Some("d@x"),
+                    ])),
+                ),
+            ],
+        )
+    }
+
+    #[tokio::test]
+    async fn test_detect_pk() -> anyhow::Result<()> {
+        let mut backend = enrollments_backend();
+        let output = DetectPkOpts::new("enrollments".to_string())
+            .execute(&mut backend)
+            .await?;
+        assert_eq!(row_count(&output), 4);
+        assert_eq!(row_cells(&output, "id"), ["id", "true", "true", "true"]);
+        assert_eq!(
+            row_cells(&output, "student"),
+            ["student", "false", "true", "false"]
+        );
+        assert_eq!(
+            row_cells(&output, "email"),
+            ["email", "true", "false", "false"]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_detect_pk_composite() -> anyhow::Result<()> {
+        let mut backend = enrollments_backend();
+        let output = DetectPkOpts::new("enrollments".to_string())
+            .with_max_cols(2)
+            .execute(&mut backend)
+            .await?;
+        assert_eq!(
+            row_cells(&output, "student, course"),
+            ["student, course", "true", "true", "true"]
+        );
+        // supersets of the id key are skipped
+        assert!(row_cells(&output, "id, student").is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_detect_pk_no_candidate() -> anyhow::Result<()> {
+        let mut backend = DatafusionBackend::new();
+        let schema = Schema::new(vec![Field::new("color", DataType::Utf8, true)]);
+        let batch = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![Arc::new(StringArray::from(vec![
+                Some("red"),
+                Some("red"),
+                None,
+            ]))],
+        )?;
+        backend.register_batch("paint", batch)?;
+        let output = DetectPkOpts::new("paint".to_string())
+            .execute(&mut backend)
+            .await?;
+        assert_eq!(
+            row_cells(&output, "color"),
+            ["color", "false", "false", "false"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_combinations() {
+        let columns = ["a", "b", "c"].map(String::from);
+        assert_eq!(combinations(&columns, 1), [["a"], ["b"], ["c"]]);
+        assert_eq!(
+            combinations(&columns, 2),
+            [["a", "b"], ["a", "c"], ["b", "c"]]
+        );
+    }
+}
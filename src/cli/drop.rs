@@ -0,0 +1,35 @@
+use crate::{Backend, CmdExecutor, ReplContext, ReplMsg};
+use clap::{ArgMatches, Parser};
+use reedline_repl_rs::Result;
+
+use super::ensure_table;
+
+#[derive(Debug, Parser)]
+pub struct DropOpts {
+    #[arg(short, long, help = "the name of the dataset")]
+    name: String,
+}
+
+pub fn drop_dataset(args: ArgMatches, context: &mut ReplContext) -> Result<Option<String>> {
+    let name = args
+        .get_one::<String>("name")
+        .expect("expect name")
+        .to_string();
+    let (msg, rx) = ReplMsg::new(DropOpts::new(name));
+
+    Ok(context.send(msg, rx))
+}
+
+impl CmdExecutor for DropOpts {
+    async fn execute<T: Backend>(&self, backend: &mut T) -> anyhow::Result<String> {
+        ensure_table(backend, &self.name).await?;
+        backend.drop_table(&self.name).await?;
+        Ok(format!("Dropped dataset {}", self.name))
+    }
+}
+
+impl DropOpts {
+    pub fn new(name: String) -> Self {
+        Self { name }
+    }
+}
@@ -0,0 +1,248 @@
+use clap::{ArgMatches, Parser, ValueEnum};
+use reedline_repl_rs::Result;
+
+use crate::{Backend, CmdExecutor, ReplContext, ReplMsg};
+
+use super::ensure_table;
+
+const DEFAULT_MAX_CATEGORIES: usize = 20;
+
+/// How the values of a categorical column are encoded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum EncodeMethod {
+    #[default]
+    Label,
+    Onehot,
+}
+
+#[derive(Debug, Parser)]
+pub struct EncodeCatOpts {
+    #[arg(short, long, help = "the name of the dataset")]
+    name: String,
+    #[arg(short, long, help = "the categorical column to encode")]
+    column: String,
+    #[arg(
+        short,
+        long,
+        value_enum,
+        default_value_t,
+        help = "label numbers the categories, onehot adds a boolean column per category, false in every one for nulls"
+    )]
+    method: EncodeMethod,
+    #[arg(
+        long,
+        default_value_t = DEFAULT_MAX_CATEGORIES,
+        help = "the most categories one-hot encoded"
+    )]
+    max_categories: usize,
+    #[arg(short, long, help = "the name of the new dataset")]
+    output: String,
+}
+
+pub fn encode_cat(args: ArgMatches, context: &mut ReplContext) -> Result<Option<String>> {
+    let name = args
+        .get_one::<String>("name")
+        .expect("expect name")
+        .to_string();
+    let column = args
+        .get_one::<String>("column")
+        .expect("expect column")
+        .to_string();
+    let method = args
+        .get_one::<EncodeMethod>("method")
+        .copied()
+        .unwrap_or_default();
+    let max_categories = args
+        .get_one::<usize>("max_categories")
+        .copied()
+        .unwrap_or(DEFAULT_MAX_CATEGORIES);
+    let output = args
+        .get_one::<String>("output")
+        .expect("expect output")
+        .to_string();
+    let opts = EncodeCatOpts::new(name, column, method, output).with_max_categories(max_categories);
+    let (msg, rx) = ReplMsg::new(opts);
+
+    Ok(context.send(msg, rx))
+}
+
+impl CmdExecutor for EncodeCatOpts {
+    async fn execute<T: Backend>(&self, backend: &mut T) -> anyhow::Result<String> {
+        ensure_table(backend, &self.name).await?;
+        if !backend
+            .column_names(&self.name)
+            .await?
+            .contains(&self.column)
+        {
+            anyhow::bail!("Dataset {} has no column(s): {}", self.name, self.column);
+        }
+        let sql = match self.method {
+            EncodeMethod::Label => self.label_sql(),
+            EncodeMethod::Onehot => {
+                // one more than the limit tells whether it is exceeded
+                let mut categories = backend
+                    .preview_values(&self.name, &self.column, self.max_categories + 1)
+                    .await?;
+                if categories.len() > self.max_categories {
+                    anyhow::bail!(
+                        "Column {} has more than {} categories",
+                        self.column,
+                        self.max_categories
+                    );
+                }
+                categories.sort();
+                self.onehot_sql(&categories)
+            }
+        };
+        backend.create_table_from_sql(&self.output, &sql).await?;
+        Ok(format!(
+            "Encoded column {} of dataset {} as dataset {}",
+            self.column, self.name, self.output
+        ))
+    }
+}
+
+impl EncodeCatOpts {
+    pub fn new(name: String, column: String, method: EncodeMethod, output: String) -> Self {
+        Self {
+            name,
+            column,
+            method,
+            max_categories: DEFAULT_MAX_CATEGORIES,
+            output,
+        }
+    }
+
+    pub fn with_max_categories(mut self, max_categories: usize) -> Self {
+        self.max_categories = max_categories;
+        self
+    }
+
+    /// Labels start at 1, following the order of the categories.
+    fn label_sql(&self) -> String {
+        format!(
+            "SELECT *, DENSE_RANK() OVER (ORDER BY \"{0}\") AS \"{0}_label\" FROM {1}",
+            self.column, self.name
+        )
+    }
+
+    /// One boolean column per category. A null matches no category, so its
+    /// row is false in all of them.
+    fn onehot_sql(&self, categories: &[String]) -> String {
+        let columns = categories
+            .iter()
+            .map(|category| {
+                format!(
+                    "COALESCE(\"{0}\" = '{1}', false) AS \"{0}_{2}\"",
+                    self.column,
+                    category.replace('\'', "''"),
+                    category.replace('"', "\"\"")
+                )
+            })
+            .collect::<Vec<_>>();
+        format!("SELECT *, {} FROM {}", columns.join(", "), self.name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::ReplDisplay;
+    use crate::backend::DatafusionBackend;
+    use crate::backend::tests::{backend_with, row_cells, row_count};
+    use arrow::array::{Int64Array, StringArray};
+
+    fn pets_backend() -> DatafusionBackend {
+        backend_with(
+            "pets",
+            vec![
+                ("id", Arc::new(Int64Array::from_iter_values(0..5))),
+                (
+                    "pet",
+                    Arc::new(StringArray::from(vec!["dog", "cat", "fish", "dog", "cat"])),
+                ),
+            ],
+        )
+    }
+
+    async fn query(backend: &DatafusionBackend, sql: &str) -> anyhow::Result<String> {
+        backend.sql(sql).await?.display(backend.settings()).await
+    }
+
+    #[tokio::test]
+    async fn test_encode_label() -> anyhow::Result<()> {
+        let mut backend = pets_backend();
+        let opts = EncodeCatOpts::new(
+            "pets".to_string(),
+            "pet".to_string(),
+            EncodeMethod::Label,
+            "encoded".to_string(),
+        );
+        let output = opts.execute(&mut backend).await?;
+        assert_eq!(
+            output,
+            "Encoded column pet of dataset pets as dataset encoded"
+        );
+
+        let sql = "SELECT pet_label, COUNT(*) AS n FROM encoded GROUP BY pet_label";
+        let labels = query(&backend, sql).await?;
+        assert_eq!(row_count(&labels), 3);
+        assert_eq!(row_cells(&labels, "1"), ["1", "2"]);
+        assert_eq!(row_cells(&labels, "2"), ["2", "2"]);
+        assert_eq!(row_cells(&labels, "3"), ["3", "1"]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_encode_onehot() -> anyhow::Result<()> {
+        let mut backend = pets_backend();
+        let opts = EncodeCatOpts::new(
+            "pets".to_string(),
+            "pet".to_string(),
+            EncodeMethod::Onehot,
+            "encoded".to_string(),
+        );
+        opts.execute(&mut backend).await?;
+
+        let sql = "SELECT CAST(pet_cat AS INT) + CAST(pet_dog AS INT) + CAST(pet_fish AS INT) \
+                   AS total, COUNT(*) AS n FROM encoded GROUP BY 1";
+        let totals = query(&backend, sql).await?;
+        assert_eq!(row_count(&totals), 1);
+        assert_eq!(row_cells(&totals, "1"), ["1", "5"]);
+
+        let opts = EncodeCatOpts::new(
+            "pets".to_string(),
+            "pet".to_string(),
+            EncodeMethod::Onehot,
+            "limited".to_string(),
+        )
+        .with_max_categories(2);
+        let err = opts.execute(&mut backend).await.unwrap_err();
+        assert_eq!(err.to_string(), "Column pet has more than 2 categories");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_encode_onehot_quotes_and_nulls() -> anyhow::Result<()> {
+        let sizes = StringArray::from(vec![Some("6\" pot"), Some("big"), None]);
+        let mut backend = backend_with("pots", vec![("size", Arc::new(sizes))]);
+        let opts = EncodeCatOpts::new(
+            "pots".to_string(),
+            "size".to_string(),
+            EncodeMethod::Onehot,
+            "encoded".to_string(),
+        );
+        opts.execute(&mut backend).await?;
+
+        let sql = "SELECT CAST(\"size_6\"\" pot\" AS INT) + CAST(size_big AS INT) AS total, \
+                   COUNT(*) AS n FROM encoded GROUP BY 1";
+        let totals = query(&backend, sql).await?;
+        assert_eq!(row_count(&totals), 2);
+        assert_eq!(row_cells(&totals, "1"), ["1", "2"]);
+        // the null row is in no category
+        assert_eq!(row_cells(&totals, "0"), ["0", "1"]);
+        Ok(())
+    }
+}
@@ -0,0 +1,166 @@
+use clap::{ArgMatches, Parser, ValueEnum};
+use reedline_repl_rs::Result;
+
+use crate::{Backend, CmdExecutor, ReplContext, ReplMsg};
+
+const HIGHLIGHT_RATIO: usize = 10;
+const RED: &str = "\x1b[31m";
+const RESET: &str = "\x1b[0m";
+
+/// How a query plan is printed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum ExplainFormat {
+    #[default]
+    Text,
+    Dot,
+}
+
+#[derive(Debug, Parser)]
+pub struct ExplainOpts {
+    #[arg(short, long, help = "the sql to explain")]
+    query: String,
+    #[arg(
+        short,
+        long,
+        help = "run the query and show the actual row counts of each node"
+    )]
+    analyze: bool,
+    #[arg(
+        short,
+        long,
+        value_enum,
+        default_value_t,
+        help = "the format of the plan, dot exports GraphViz"
+    )]
+    format: ExplainFormat,
+}
+
+pub fn explain(args: ArgMatches, context: &mut ReplContext) -> Result<Option<String>> {
+    let query = args
+        .get_one::<String>("query")
+        .expect("expect query")
+        .to_string();
+    let analyze = args.get_flag("analyze");
+    let format = args
+        .get_one::<ExplainFormat>("format")
+        .copied()
+        .unwrap_or_default();
+    let (msg, rx) = ReplMsg::new(ExplainOpts::new(query, analyze, format));
+
+    Ok(context.send(msg, rx))
+}
+
+impl CmdExecutor for ExplainOpts {
+    async fn execute<T: Backend>(&self, backend: &mut T) -> anyhow::Result<String> {
+        match (self.analyze, self.format) {
+            (true, ExplainFormat::Dot) => {
+                anyhow::bail!("--format dot could not be combined with --analyze")
+            }
+            (true, ExplainFormat::Text) => {
+                let plan = backend.explain_analyze(&self.query).await?;
+                Ok(highlight_estimates(&plan, backend.settings().color))
+            }
+            (false, ExplainFormat::Dot) => backend.explain_dot(&self.query).await,
+            (false, ExplainFormat::Text) => backend.validate_sql(&self.query).await,
+        }
+    }
+}
+
+impl ExplainOpts {
+    pub fn new(query: String, analyze: bool, format: ExplainFormat) -> Self {
+        Self {
+            query,
+            analyze,
+            format,
+        }
+    }
+}
+
+/// Color the plan nodes whose actual row count is off from the estimated one
+/// by more than `HIGHLIGHT_RATIO` times.
+fn highlight_estimates(plan: &str, color: bool) -> String {
+    if !color {
+        return plan.to_string();
+    }
+    plan.lines()
+        .map(
+            |line| match (number_after(line, "output_rows="), estimated_rows(line)) {
+                (Some(actual), Some(estimated))
+                    if actual.max(estimated) > actual.min(estimated).max(1) * HIGHLIGHT_RATIO =>
+                {
+                    format!("{}{}{}", RED, line, RESET)
+                }
+                _ => line.to_string(),
+            },
+        )
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn estimated_rows(line: &str) -> Option<usize> {
+    number_after(line, "Rows=Exact(").or_else(|| number_after(line, "Rows=Inexact("))
+}
+
+fn number_after(line: &str, pattern: &str) -> Option<usize> {
+    let start = line.find(pattern)? + pattern.len();
+    let digits = line[start..]
+        .chars()
+        .take_while(|c| c.is_ascii_digit())
+        .collect::<String>();
+    digits.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::tests::test_backend;
+
+    #[tokio::test]
+    async fn test_explain_analyze() -> anyhow::Result<()> {
+        let mut backend = test_backend();
+        let output = ExplainOpts::new("SELECT 1".to_string(), true, ExplainFormat::Text)
+            .execute(&mut backend)
+            .await?;
+        assert!(!output.trim().is_empty());
+        assert!(output.contains("output_rows"));
+        assert!(output.contains("statistics=[Rows="));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_explain_dot() -> anyhow::Result<()> {
+        let mut backend = test_backend();
+        let output = ExplainOpts::new(
+            "SELECT id FROM t WHERE id > 3".to_string(),
+            false,
+            ExplainFormat::Dot,
+        )
+        .execute(&mut backend)
+        .await?;
+        assert!(output.contains("digraph"));
+        assert!(output.contains("FilterExec"));
+
+        let opts = ExplainOpts::new("SELECT 1".to_string(), true, ExplainFormat::Dot);
+        assert!(opts.execute(&mut backend).await.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_highlight_estimates() {
+        let plan = "FilterExec: metrics=[output_rows=500], statistics=[Rows=Inexact(20)]\n\
+                    DataSourceExec: metrics=[output_rows=1000], statistics=[Rows=Exact(1000)]";
+        let lines = highlight_estimates(plan, true)
+            .lines()
+            .map(|line| line.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            lines[0],
+            format!(
+                "{}FilterExec: metrics=[output_rows=500], statistics=[Rows=Inexact(20)]{}",
+                RED, RESET
+            )
+        );
+        assert!(!lines[1].contains(RED));
+        assert_eq!(highlight_estimates(plan, false), plan);
+    }
+}
@@ -0,0 +1,451 @@
+use clap::{ArgMatches, Parser, ValueEnum};
+use reedline_repl_rs::Result;
+
+use arrow::datatypes::DataType;
+
+use crate::{Backend, CmdExecutor, ReplContext, ReplMsg, backend::sql_type};
+
+use super::ensure_table;
+
+/// What the missing values of a column are replaced with.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum FillMethod {
+    #[default]
+    Mean,
+    Median,
+    /// The most frequent value.
+    Mode,
+    /// The value given by `--value`.
+    Constant,
+    /// The last non-null value of the previous rows.
+    ForwardFill,
+}
+
+#[derive(Debug, Parser)]
+pub struct FillNullsOpts {
+    #[arg(short, long, help = "the name of the dataset")]
+    name: String,
+    #[arg(short, long, help = "the column to fill")]
+    column: String,
+    #[arg(
+        short,
+        long,
+        value_enum,
+        default_value_t,
+        help = "mean, median, mode, constant or forward-fill"
+    )]
+    method: FillMethod,
+    #[arg(
+        short,
+        long,
+        allow_hyphen_values = true,
+        help = "the value to fill with the constant method"
+    )]
+    value: Option<String>,
+    #[arg(short, long, help = "the name of the new dataset")]
+    output: String,
+}
+
+pub fn fill_nulls(args: ArgMatches, context: &mut ReplContext) -> Result<Option<String>> {
+    let name = args
+        .get_one::<String>("name")
+        .expect("expect name")
+        .to_string();
+    let column = args
+        .get_one::<String>("column")
+        .expect("expect column")
+        .to_string();
+    let method = args
+        .get_one::<FillMethod>("method")
+        .copied()
+        .unwrap_or_default();
+    let value = args.get_one::<String>("value").cloned();
+    let output = args
+        .get_one::<String>("output")
+        .expect("expect output")
+        .to_string();
+    let opts = FillNullsOpts::new(name, column, method, output).with_value(value);
+    let (msg, rx) = ReplMsg::new(opts);
+
+    Ok(context.send(msg, rx))
+}
+
+impl CmdExecutor for FillNullsOpts {
+    async fn execute<T: Backend>(&self, backend: &mut T) -> anyhow::Result<String> {
+        ensure_table(backend, &self.name).await?;
+        let all = backend.column_names(&self.name).await?;
+        if !all.contains(&self.column) {
+            anyhow::bail!("Dataset {} has no column(s): {}", self.name, self.column);
+        }
+        let data_type = backend.column_type(&self.name, &self.column).await?;
+        let needs_numeric = matches!(self.method, FillMethod::Mean | FillMethod::Median);
+        if needs_numeric && !data_type.is_numeric() {
+            anyhow::bail!(
+                "Column {} of dataset {} is not numeric",
+                self.column,
+                self.name
+            );
+        }
+        let sql = self.sql(&all, &data_type)?;
+        if self.method == FillMethod::ForwardFill {
+            // the previous row is only defined when rows are read in order
+            backend
+                .create_table_from_ordered_sql(&self.output, &sql)
+                .await?;
+        } else {
+            backend.create_table_from_sql(&self.output, &sql).await?;
+        }
+        Ok(format!("Materialized filled dataset {}", self.output))
+    }
+}
+
+impl FillNullsOpts {
+    pub fn new(name: String, column: String, method: FillMethod, output: String) -> Self {
+        Self {
+            name,
+            column,
+            method,
+            value: None,
+            output,
+        }
+    }
+
+    pub fn with_value(mut self, value: Option<String>) -> Self {
+        self.value = value;
+        self
+    }
+
+    /// Keep every column of the dataset, coalescing the filled one with a
+    /// value from a single-row sub-select cast back to the column type, or
+    /// with the previous non-null value in row order for forward fill. The
+    /// sub-select is empty when a column has no value to take the mode of,
+    /// which the left join keeps the rows through.
+    fn sql(&self, all: &[String], data_type: &DataType) -> anyhow::Result<String> {
+        let column = &self.column;
+        let fill = match self.method {
+            FillMethod::Mean => format!("SELECT AVG(CAST(\"{}\" AS DOUBLE)) AS fill", column),
+            FillMethod::Median => {
+                format!("SELECT MEDIAN(CAST(\"{}\" AS DOUBLE)) AS fill", column)
+            }
+            FillMethod::Mode => format!(
+                "SELECT \"{0}\" AS fill, COUNT(*) AS n FROM {1} WHERE \"{0}\" IS NOT NULL \
+                GROUP BY \"{0}\" ORDER BY n DESC, \"{0}\" LIMIT 1",
+                column, self.name
+            ),
+            FillMethod::Constant => {
+                let Some(value) = &self.value else {
+                    anyhow::bail!("The constant method needs a --value");
+                };
+                if data_type.is_numeric() {
+                    let number = value.parse::<f64>().map_err(|_| {
+                        anyhow::anyhow!("Column {} needs a numeric value, got {}", column, value)
+                    })?;
+                    if !number.is_finite() {
+                        anyhow::bail!("Column {} needs a finite value, got {}", column, value);
+                    }
+                    format!("SELECT {} AS fill", value)
+                } else {
+                    format!("SELECT '{}' AS fill", value.replace('\'', "''"))
+                }
+            }
+            FillMethod::ForwardFill => return Ok(self.forward_fill_sql(all)),
+        };
+        // mean and median sub-selects aggregate the dataset itself
+        let fill = match self.method {
+            FillMethod::Mean | FillMethod::Median => format!("{} FROM {}", fill, self.name),
+            _ => fill,
+        };
+        let fill_value = match sql_type(data_type) {
+            // the mean of an integer column is rounded rather than truncated
+            Some(sql_type)
+                if data_type.is_integer()
+                    && matches!(self.method, FillMethod::Mean | FillMethod::Median) =>
+            {
+                format!("CAST(ROUND(s.fill) AS {})", sql_type)
+            }
+            Some(sql_type) => format!("CAST(s.fill AS {})", sql_type),
+            None => "s.fill".to_string(),
+        };
+        let projection = all
+            .iter()
+            .map(|c| {
+                if c == column {
+                    format!("COALESCE(t.\"{0}\", {1}) AS \"{0}\"", c, fill_value)
+                } else {
+                    format!("t.\"{}\"", c)
+                }
+            })
+            .collect::<Vec<_>>();
+        Ok(format!(
+            "SELECT {} FROM {} t LEFT JOIN ({}) s ON true",
+            projection.join(", "),
+            self.name,
+            fill
+        ))
+    }
+
+    fn forward_fill_sql(&self, all: &[String]) -> String {
+        let projection = all
+            .iter()
+            .map(|c| {
+                if c == &self.column {
+                    format!(
+                        "LAST_VALUE(\"{0}\" IGNORE NULLS) OVER (ORDER BY __row \
+                        ROWS BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW) AS \"{0}\"",
+                        c
+                    )
+                } else {
+                    format!("\"{}\"", c)
+                }
+            })
+            .collect::<Vec<_>>();
+        format!(
+            "SELECT {} FROM (SELECT *, ROW_NUMBER() OVER () AS __row FROM {}) t ORDER BY __row",
+            projection.join(", "),
+            self.name
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::ReplDisplay;
+    use crate::backend::DatafusionBackend;
+    use crate::backend::tests::{backend_with, connect_partitioned_csv};
+    use arrow::array::{Array, AsArray, Float64Array, Int64Array, RecordBatch, StringArray};
+    use arrow::datatypes::{Float64Type, Int64Type};
+
+    fn readings_backend() -> DatafusionBackend {
+        backend_with(
+            "readings",
+            vec![
+                (
+                    "temp",
+                    Arc::new(Float64Array::from(vec![
+                        Some(1.0),
+                        None,
+                        Some(2.0),
+                        Some(2.0),
+                        None,
+                        Some(7.0),
+                    ])),
+                ),
+                (
+                    "sky",
+                    Arc::new(StringArray::from(vec![
+                        Some("sun"),
+                        None,
+                        Some("rain"),
+                        Some("rain"),
+                        None,
+                        Some("sun"),
+                    ])),
+                ),
+                (
+                    "visits",
+                    Arc::new(Int64Array::from(vec![
+                        Some(1),
+                        None,
+                        Some(2),
+                        Some(2),
+                        None,
+                        Some(4),
+                    ])),
+                ),
+                ("note", Arc::new(StringArray::from(vec![None::<&str>; 6]))),
+            ],
+        )
+    }
+
+    /// Fill a column and return its values in row order.
+    async fn filled(
+        column: &str,
+        method: FillMethod,
+        value: Option<&str>,
+    ) -> anyhow::Result<Vec<RecordBatch>> {
+        let mut backend = readings_backend();
+        let opts = FillNullsOpts::new(
+            "readings".to_string(),
+            column.to_string(),
+            method,
+            "filled".to_string(),
+        )
+        .with_value(value.map(|v| v.to_string()));
+        let output = opts.execute(&mut backend).await?;
+        assert_eq!(output, "Materialized filled dataset filled");
+
+        let sql = format!("SELECT \"{}\" FROM filled", column);
+        let batches = backend.sql(&sql).await?.into_batches().await?;
+        assert!(batches.iter().all(|b| b.column(0).null_count() == 0));
+        assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 6);
+        Ok(batches)
+    }
+
+    fn temps(batches: &[RecordBatch]) -> Vec<f64> {
+        batches
+            .iter()
+            .flat_map(|b| b.column(0).as_primitive::<Float64Type>().values().to_vec())
+            .collect()
+    }
+
+    fn skies(batches: &[RecordBatch]) -> Vec<String> {
+        batches
+            .iter()
+            .flat_map(|b| {
+                b.column(0)
+                    .as_string::<i32>()
+                    .iter()
+                    .flatten()
+                    .map(|s| s.to_string())
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_fill_nulls_with_statistics() -> anyhow::Result<()> {
+        let mean = temps(&filled("temp", FillMethod::Mean, None).await?);
+        assert_eq!(mean.iter().filter(|v| **v == 3.0).count(), 2);
+        let median = temps(&filled("temp", FillMethod::Median, None).await?);
+        assert_eq!(median.iter().filter(|v| **v == 2.0).count(), 4);
+        let mode = skies(&filled("sky", FillMethod::Mode, None).await?);
+        assert_eq!(mode.iter().filter(|v| *v == "rain").count(), 4);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_fill_nulls_constant() -> anyhow::Result<()> {
+        let constant = temps(&filled("temp", FillMethod::Constant, Some("-1")).await?);
+        assert_eq!(constant.iter().filter(|v| **v == -1.0).count(), 2);
+        let constant = skies(&filled("sky", FillMethod::Constant, Some("fog")).await?);
+        assert_eq!(constant.iter().filter(|v| *v == "fog").count(), 2);
+        assert!(filled("temp", FillMethod::Constant, None).await.is_err());
+        assert!(
+            filled("temp", FillMethod::Constant, Some("x"))
+                .await
+                .is_err()
+        );
+        for value in ["inf", "NaN"] {
+            let err = filled("temp", FillMethod::Constant, Some(value))
+                .await
+                .unwrap_err();
+            assert!(err.to_string().contains("needs a finite value"));
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_parse_negative_constant() -> anyhow::Result<()> {
+        let opts = FillNullsOpts::try_parse_from([
+            "fill-nulls",
+            "-n",
+            "readings",
+            "-c",
+            "temp",
+            "--method",
+            "constant",
+            "--value",
+            "-1",
+            "-o",
+            "filled",
+        ])?;
+        assert_eq!(opts.value.as_deref(), Some("-1"));
+
+        let mut backend = readings_backend();
+        opts.execute(&mut backend).await?;
+        let batches = backend
+            .sql("SELECT temp FROM filled")
+            .await?
+            .into_batches()
+            .await?;
+        assert_eq!(temps(&batches).iter().filter(|v| **v == -1.0).count(), 2);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_fill_nulls_keeps_column_type() -> anyhow::Result<()> {
+        let batches = filled("visits", FillMethod::Mean, None).await?;
+        assert_eq!(batches[0].schema().field(0).data_type(), &DataType::Int64);
+        let visits = batches
+            .iter()
+            .flat_map(|b| b.column(0).as_primitive::<Int64Type>().values().to_vec())
+            .collect::<Vec<_>>();
+        // the mean 2.25 is rounded to 2
+        assert_eq!(visits, [1, 2, 2, 2, 2, 4]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_fill_nulls_mode_of_all_null_column() -> anyhow::Result<()> {
+        let mut backend = readings_backend();
+        FillNullsOpts::new(
+            "readings".to_string(),
+            "note".to_string(),
+            FillMethod::Mode,
+            "filled".to_string(),
+        )
+        .execute(&mut backend)
+        .await?;
+        let batches = backend
+            .sql("SELECT note FROM filled")
+            .await?
+            .into_batches()
+            .await?;
+        assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 6);
+        assert!(
+            batches
+                .iter()
+                .all(|b| b.column(0).null_count() == b.num_rows())
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_fill_nulls_forward_fill() -> anyhow::Result<()> {
+        let sky = skies(&filled("sky", FillMethod::ForwardFill, None).await?);
+        assert_eq!(sky, ["sun", "sun", "rain", "rain", "rain", "sun"]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_forward_fill_partitioned_source() -> anyhow::Result<()> {
+        let mut content = "id,temp\n".to_string();
+        for id in 0..10_000 {
+            match id % 100 {
+                0 => content.push_str(&format!("{},{}\n", id, id)),
+                _ => content.push_str(&format!("{},\n", id)),
+            }
+        }
+        let mut backend = DatafusionBackend::new();
+        let _dir = connect_partitioned_csv(&mut backend, "readings", &content).await?;
+        FillNullsOpts::new(
+            "readings".to_string(),
+            "temp".to_string(),
+            FillMethod::ForwardFill,
+            "filled".to_string(),
+        )
+        .execute(&mut backend)
+        .await?;
+
+        let batches = backend.table("filled").await?.collect().await?;
+        let rows = batches
+            .iter()
+            .flat_map(|b| {
+                let ids = b.column(0).as_primitive::<Int64Type>();
+                let temps = b.column(1).as_primitive::<Int64Type>();
+                ids.values()
+                    .iter()
+                    .copied()
+                    .zip(temps.values().iter().copied())
+            })
+            .collect::<Vec<_>>();
+        let expected = (0..10_000)
+            .map(|id| (id, id - id % 100))
+            .collect::<Vec<_>>();
+        assert_eq!(rows, expected);
+        Ok(())
+    }
+}
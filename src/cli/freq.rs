@@ -0,0 +1,114 @@
+use clap::{ArgMatches, Parser};
+use reedline_repl_rs::Result;
+
+use crate::{Backend, CmdExecutor, ReplContext, ReplDisplay, ReplMsg};
+
+use super::ensure_table;
+
+#[derive(Debug, Parser)]
+pub struct FrequencyTableOpts {
+    #[arg(short, long, help = "the name of the dataset")]
+    name: String,
+    #[arg(short, long, help = "the column to count the values of")]
+    column: String,
+    #[arg(long, help = "report the fraction of rows instead of the count")]
+    normalize: bool,
+}
+
+pub fn freq(args: ArgMatches, context: &mut ReplContext) -> Result<Option<String>> {
+    let name = args
+        .get_one::<String>("name")
+        .expect("expect name")
+        .to_string();
+    let column = args
+        .get_one::<String>("column")
+        .expect("expect column")
+        .to_string();
+    let normalize = args.get_flag("normalize");
+    let (msg, rx) = ReplMsg::new(FrequencyTableOpts::new(name, column, normalize));
+
+    Ok(context.send(msg, rx))
+}
+
+impl CmdExecutor for FrequencyTableOpts {
+    async fn execute<T: Backend>(&self, backend: &mut T) -> anyhow::Result<String> {
+        ensure_table(backend, &self.name).await?;
+        let table = backend
+            .frequency_table(&self.name, &self.column, self.normalize)
+            .await?;
+        table.display(backend.settings()).await
+    }
+}
+
+impl FrequencyTableOpts {
+    pub fn new(name: String, column: String, normalize: bool) -> Self {
+        Self {
+            name,
+            column,
+            normalize,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::backend::DatafusionBackend;
+    use crate::backend::tests::{backend_with, row_cells, row_count};
+    use arrow::array::{AsArray, StringArray};
+    use arrow::datatypes::Float64Type;
+
+    fn fruits_backend() -> DatafusionBackend {
+        backend_with(
+            "fruits",
+            vec![(
+                "fruit",
+                Arc::new(StringArray::from(vec![
+                    Some("apple"),
+                    Some("pear"),
+                    Some("apple"),
+                    Some("plum"),
+                    Some("apple"),
+                    None,
+                ])),
+            )],
+        )
+    }
+
+    #[tokio::test]
+    async fn test_frequency_table_counts() -> anyhow::Result<()> {
+        let mut backend = fruits_backend();
+        let opts = FrequencyTableOpts::new("fruits".to_string(), "fruit".to_string(), false);
+        let output = opts.execute(&mut backend).await?;
+        assert_eq!(row_count(&output), 4);
+        assert_eq!(row_cells(&output, "apple"), ["apple", "3"]);
+        assert_eq!(row_cells(&output, "pear"), ["pear", "1"]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_frequency_table_normalized_sums_to_one() -> anyhow::Result<()> {
+        let backend = fruits_backend();
+        let batches = backend
+            .frequency_table("fruits", "fruit", true)
+            .await?
+            .into_batches()
+            .await?;
+        let pcts = batches
+            .iter()
+            .flat_map(|batch| {
+                batch
+                    .column(1)
+                    .as_primitive::<Float64Type>()
+                    .values()
+                    .to_vec()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(pcts.len(), 4);
+        assert_eq!(pcts[0], 0.5);
+        assert!((pcts.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+        Ok(())
+    }
+}
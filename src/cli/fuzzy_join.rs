@@ -0,0 +1,189 @@
+use clap::{ArgMatches, Parser};
+use reedline_repl_rs::Result;
+
+use crate::{Backend, CmdExecutor, ReplContext, ReplMsg};
+
+use super::ensure_table;
+
+const DEFAULT_THRESHOLD: f64 = 90.0;
+
+#[derive(Debug, Parser)]
+pub struct FuzzyJoinOpts {
+    #[arg(short, long, help = "the left dataset")]
+    left: String,
+    #[arg(short, long, help = "the right dataset")]
+    right: String,
+    #[arg(long, help = "the string column of the left dataset to match")]
+    left_col: String,
+    #[arg(long, help = "the string column of the right dataset to match")]
+    right_col: String,
+    #[arg(
+        short,
+        long,
+        default_value_t = DEFAULT_THRESHOLD,
+        help = "the minimum Jaro-Winkler similarity of a match, from 0 to 100"
+    )]
+    threshold: f64,
+    #[arg(short, long, help = "the name of the new dataset")]
+    output: String,
+}
+
+pub fn fuzzy_join(args: ArgMatches, context: &mut ReplContext) -> Result<Option<String>> {
+    let left = args
+        .get_one::<String>("left")
+        .expect("expect left")
+        .to_string();
+    let right = args
+        .get_one::<String>("right")
+        .expect("expect right")
+        .to_string();
+    let left_col = args
+        .get_one::<String>("left_col")
+        .expect("expect left_col")
+        .to_string();
+    let right_col = args
+        .get_one::<String>("right_col")
+        .expect("expect right_col")
+        .to_string();
+    let threshold = args
+        .get_one::<f64>("threshold")
+        .copied()
+        .unwrap_or(DEFAULT_THRESHOLD);
+    let output = args
+        .get_one::<String>("output")
+        .expect("expect output")
+        .to_string();
+    let opts =
+        FuzzyJoinOpts::new(left, right, left_col, right_col, output).with_threshold(threshold);
+    let (msg, rx) = ReplMsg::new(opts);
+
+    Ok(context.send(msg, rx))
+}
+
+impl CmdExecutor for FuzzyJoinOpts {
+    async fn execute<T: Backend>(&self, backend: &mut T) -> anyhow::Result<String> {
+        ensure_table(backend, &self.left).await?;
+        ensure_table(backend, &self.right).await?;
+        if !(0.0..=100.0).contains(&self.threshold) {
+            anyhow::bail!("The threshold must be between 0 and 100");
+        }
+        let rows = backend
+            .fuzzy_join(
+                &self.left,
+                &self.right,
+                &self.left_col,
+                &self.right_col,
+                self.threshold,
+                &self.output,
+            )
+            .await?;
+        Ok(format!(
+            "Matched {} pair(s) of {} and {} as dataset {}",
+            rows, self.left, self.right, self.output
+        ))
+    }
+}
+
+impl FuzzyJoinOpts {
+    pub fn new(
+        left: String,
+        right: String,
+        left_col: String,
+        right_col: String,
+        output: String,
+    ) -> Self {
+        Self {
+            left,
+            right,
+            left_col,
+            right_col,
+            threshold: DEFAULT_THRESHOLD,
+            output,
+        }
+    }
+
+    pub fn with_threshold(mut self, threshold: f64) -> Self {
+        self.threshold = threshold;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::ReplDisplay;
+    use crate::backend::DatafusionBackend;
+    use crate::backend::tests::{backend_with, row_cells, row_count};
+    use arrow::array::{ArrayRef, Int64Array, RecordBatch, StringArray};
+
+    fn people(names: Vec<&str>) -> Vec<(&'static str, ArrayRef)> {
+        let ids = (0..names.len() as i64).collect::<Vec<_>>();
+        vec![
+            ("id", Arc::new(Int64Array::from(ids))),
+            ("name", Arc::new(StringArray::from(names))),
+        ]
+    }
+
+    fn people_backend() -> DatafusionBackend {
+        let backend = backend_with("crm", people(vec!["Jonathan Smith", "Maria Garcia", "Zed"]));
+        let billing = people(vec!["Mario Garcia", "Jonathon Smith", "Xavier Quinn"]);
+        let billing = RecordBatch::try_from_iter(billing).unwrap();
+        backend.register_batch("billing", billing).unwrap();
+        backend
+    }
+
+    fn opts() -> FuzzyJoinOpts {
+        FuzzyJoinOpts::new(
+            "crm".to_string(),
+            "billing".to_string(),
+            "name".to_string(),
+            "name".to_string(),
+            "matched".to_string(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_fuzzy_join() -> anyhow::Result<()> {
+        let mut backend = people_backend();
+        let output = opts().execute(&mut backend).await?;
+        assert_eq!(
+            output,
+            "Matched 2 pair(s) of crm and billing as dataset matched"
+        );
+
+        let sql = "SELECT name, name_right FROM matched ORDER BY name";
+        let matched = backend.sql(sql).await?.display(backend.settings()).await?;
+        assert_eq!(row_count(&matched), 2);
+        assert_eq!(
+            row_cells(&matched, "Jonathan Smith"),
+            ["Jonathan Smith", "Jonathon Smith"]
+        );
+        assert_eq!(
+            row_cells(&matched, "Maria Garcia"),
+            ["Maria Garcia", "Mario Garcia"]
+        );
+        assert!(row_cells(&matched, "Zed").is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_fuzzy_join_threshold() -> anyhow::Result<()> {
+        let mut backend = people_backend();
+        // every pair matches without a threshold
+        let output = opts().with_threshold(0.0).execute(&mut backend).await?;
+        assert_eq!(
+            output,
+            "Matched 9 pair(s) of crm and billing as dataset matched"
+        );
+
+        let err = opts()
+            .with_threshold(120.0)
+            .execute(&mut backend)
+            .await
+            .unwrap_err();
+        assert_eq!(err.to_string(), "The threshold must be between 0 and 100");
+        Ok(())
+    }
+}
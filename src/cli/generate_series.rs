@@ -0,0 +1,291 @@
+use std::sync::Arc;
+
+use arrow::{
+    array::{ArrayRef, Date32Array, Float64Array, Int64Array, RecordBatch},
+    datatypes::{DataType, Field, Schema},
+};
+use chrono::{DateTime, NaiveDate};
+use clap::{ArgMatches, Parser, ValueEnum};
+use reedline_repl_rs::Result;
+
+use crate::{Backend, CmdExecutor, ReplContext, ReplMsg};
+
+/// The most rows a series may have, so a typo in the bounds or step can't
+/// exhaust the memory.
+const MAX_ROWS: usize = 10_000_000;
+
+/// The type of the generated values.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum SeriesType {
+    #[default]
+    Int,
+    Float,
+    /// Dates as YYYY-MM-DD, stepping by whole days.
+    Date,
+}
+
+#[derive(Debug, Parser)]
+pub struct GenerateSeriesOpts {
+    #[arg(short, long, help = "the name of the new dataset")]
+    name: String,
+    #[arg(long, allow_negative_numbers = true, help = "the first value")]
+    start: String,
+    #[arg(
+        long,
+        allow_negative_numbers = true,
+        help = "the last value, included if reached"
+    )]
+    stop: String,
+    #[arg(
+        long,
+        default_value = "1",
+        allow_negative_numbers = true,
+        help = "the increment, in days for dates"
+    )]
+    step: String,
+    #[arg(
+        short = 't',
+        long = "type",
+        value_enum,
+        default_value_t,
+        help = "the type of the values"
+    )]
+    series_type: SeriesType,
+}
+
+pub fn generate_series(args: ArgMatches, context: &mut ReplContext) -> Result<Option<String>> {
+    let name = args
+        .get_one::<String>("name")
+        .expect("expect name")
+        .to_string();
+    let start = args
+        .get_one::<String>("start")
+        .expect("expect start")
+        .to_string();
+    let stop = args
+        .get_one::<String>("stop")
+        .expect("expect stop")
+        .to_string();
+    let step = args
+        .get_one::<String>("step")
+        .expect("expect step")
+        .to_string();
+    let series_type = args
+        .get_one::<SeriesType>("series_type")
+        .copied()
+        .unwrap_or_default();
+    let opts = GenerateSeriesOpts::new(name, start, stop, step, series_type);
+    let (msg, rx) = ReplMsg::new(opts);
+
+    Ok(context.send(msg, rx))
+}
+
+impl CmdExecutor for GenerateSeriesOpts {
+    async fn execute<T: Backend>(&self, backend: &mut T) -> anyhow::Result<String> {
+        let batch = self.batch()?;
+        let rows = batch.num_rows();
+        backend.generate_series(&self.name, batch).await?;
+        Ok(format!(
+            "Generated dataset {} with {} rows",
+            self.name, rows
+        ))
+    }
+}
+
+impl GenerateSeriesOpts {
+    pub fn new(
+        name: String,
+        start: String,
+        stop: String,
+        step: String,
+        series_type: SeriesType,
+    ) -> Self {
+        Self {
+            name,
+            start,
+            stop,
+            step,
+            series_type,
+        }
+    }
+
+    /// Build the values from start to stop as a single `value` column.
+    fn batch(&self) -> anyhow::Result<RecordBatch> {
+        let (column, data_type): (ArrayRef, _) = match self.series_type {
+            SeriesType::Int => {
+                let values =
+                    int_series(parse(&self.start)?, parse(&self.stop)?, parse(&self.step)?)?;
+                (Arc::new(Int64Array::from(values)), DataType::Int64)
+            }
+            SeriesType::Float => {
+                let start: f64 = parse(&self.start)?;
+                let stop: f64 = parse(&self.stop)?;
+                let step: f64 = parse(&self.step)?;
+                if step == 0.0 || !step.is_finite() {
+                    anyhow::bail!("The step must be a non-zero number");
+                }
+                let count = ((stop - start) / step + 1e-9).floor();
+                if !count.is_finite() || count >= MAX_ROWS as f64 {
+                    anyhow::bail!("The series has more than {} rows", MAX_ROWS);
+                }
+                let values = (0..(count as i64 + 1).max(0))
+                    .map(|i| start + i as f64 * step)
+                    .collect::<Vec<_>>();
+                (Arc::new(Float64Array::from(values)), DataType::Float64)
+            }
+            SeriesType::Date => {
+                let values = int_series(days(&self.start)?, days(&self.stop)?, parse(&self.step)?)?;
+                let values = values.into_iter().map(|v| v as i32).collect::<Vec<_>>();
+                (Arc::new(Date32Array::from(values)), DataType::Date32)
+            }
+        };
+        if column.is_empty() {
+            anyhow::bail!("The series from {} to {} is empty", self.start, self.stop);
+        }
+        let schema = Schema::new(vec![Field::new("value", data_type, false)]);
+        Ok(RecordBatch::try_new(Arc::new(schema), vec![column])?)
+    }
+}
+
+fn parse<T: std::str::FromStr>(value: &str) -> anyhow::Result<T> {
+    value
+        .parse()
+        .map_err(|_| anyhow::anyhow!("Invalid series value: {}", value))
+}
+
+fn days(date: &str) -> anyhow::Result<i64> {
+    let date = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map_err(|_| anyhow::anyhow!("Invalid date, expect YYYY-MM-DD: {}", date))?;
+    Ok(date
+        .signed_duration_since(DateTime::UNIX_EPOCH.date_naive())
+        .num_days())
+}
+
+fn int_series(start: i64, stop: i64, step: i64) -> anyhow::Result<Vec<i64>> {
+    if step == 0 {
+        anyhow::bail!("The step must be a non-zero number");
+    }
+    let count = (stop as i128 - start as i128) / step as i128 + 1;
+    if count > MAX_ROWS as i128 {
+        anyhow::bail!("The series has more than {} rows", MAX_ROWS);
+    }
+    let mut values = Vec::new();
+    let mut value = Some(start);
+    while let Some(v) = value.filter(|v| (step > 0 && *v <= stop) || (step < 0 && *v >= stop)) {
+        values.push(v);
+        value = v.checked_add(step);
+    }
+    Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::DatafusionBackend;
+    use arrow::array::AsArray;
+    use arrow::datatypes::{Date32Type, Int64Type};
+
+    fn opts(start: &str, stop: &str, step: &str, series_type: SeriesType) -> GenerateSeriesOpts {
+        GenerateSeriesOpts::new(
+            "seq".to_string(),
+            start.to_string(),
+            stop.to_string(),
+            step.to_string(),
+            series_type,
+        )
+    }
+
+    #[test]
+    fn test_parse_negative_bounds() -> anyhow::Result<()> {
+        let opts = GenerateSeriesOpts::try_parse_from([
+            "generate-series",
+            "-n",
+            "seq",
+            "--start",
+            "-5",
+            "--stop",
+            "-10",
+            "--step",
+            "-1",
+        ])?;
+        assert_eq!(opts.start, "-5");
+        assert_eq!(opts.stop, "-10");
+        assert_eq!(opts.step, "-1");
+
+        let opts = GenerateSeriesOpts::try_parse_from([
+            "generate-series",
+            "-n",
+            "seq",
+            "--start",
+            "-0.5",
+            "--stop",
+            "0.5",
+            "-t",
+            "float",
+        ])?;
+        assert_eq!(opts.start, "-0.5");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_generate_int_series() -> anyhow::Result<()> {
+        let mut backend = DatafusionBackend::new();
+        let output = opts("1", "5", "1", SeriesType::Int)
+            .execute(&mut backend)
+            .await?;
+        assert_eq!(output, "Generated dataset seq with 5 rows");
+
+        let batches = backend.table("seq").await?.collect().await?;
+        let values = batches
+            .iter()
+            .flat_map(|batch| {
+                batch
+                    .column(0)
+                    .as_primitive::<Int64Type>()
+                    .values()
+                    .to_vec()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(values, [1, 2, 3, 4, 5]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_series_batches() -> anyhow::Result<()> {
+        let batch = opts("5", "1", "-2", SeriesType::Int).batch()?;
+        let values = batch
+            .column(0)
+            .as_primitive::<Int64Type>()
+            .values()
+            .to_vec();
+        assert_eq!(values, [5, 3, 1]);
+
+        let batch = opts("0", "1", "0.25", SeriesType::Float).batch()?;
+        assert_eq!(batch.num_rows(), 5);
+
+        let batch = opts("2024-02-27", "2024-03-02", "2", SeriesType::Date).batch()?;
+        let values = batch.column(0).as_primitive::<Date32Type>();
+        assert_eq!(values.len(), 3);
+        assert_eq!(values.value(0), 19780);
+
+        assert!(opts("1", "5", "0", SeriesType::Int).batch().is_err());
+        assert!(opts("5", "1", "1", SeriesType::Int).batch().is_err());
+        assert!(opts("0", "1e12", "1", SeriesType::Float).batch().is_err());
+        assert!(opts("0", "inf", "1", SeriesType::Float).batch().is_err());
+        assert!(
+            opts("0", "100000000", "1", SeriesType::Int)
+                .batch()
+                .is_err()
+        );
+
+        let max = i64::MAX.to_string();
+        let batch = opts("9223372036854775806", &max, "5", SeriesType::Int).batch()?;
+        assert_eq!(batch.num_rows(), 1);
+        assert!(
+            opts("2024-13-01", "2024-12-01", "1", SeriesType::Date)
+                .batch()
+                .is_err()
+        );
+        Ok(())
+    }
+}
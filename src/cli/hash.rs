@@ -0,0 +1,146 @@
+use clap::{ArgMatches, Parser, ValueEnum};
+use reedline_repl_rs::Result;
+
+use crate::{Backend, CmdExecutor, ReplContext, ReplMsg};
+
+use super::ensure_table;
+
+/// The digest a dataset is fingerprinted with.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum HashAlgorithm {
+    Md5,
+    #[default]
+    Sha256,
+    /// 64-bit XXH3, the fastest of the three.
+    Xxhash,
+}
+
+#[derive(Debug, Parser)]
+pub struct HashOpts {
+    #[arg(short, long, help = "the name of the dataset")]
+    name: String,
+    #[arg(
+        short,
+        long,
+        value_enum,
+        default_value_t,
+        help = "md5, sha256 or xxhash"
+    )]
+    algorithm: HashAlgorithm,
+}
+
+pub fn hash(args: ArgMatches, context: &mut ReplContext) -> Result<Option<String>> {
+    let name = args
+        .get_one::<String>("name")
+        .expect("expect name")
+        .to_string();
+    let algorithm = args
+        .get_one::<HashAlgorithm>("algorithm")
+        .copied()
+        .unwrap_or_default();
+    let (msg, rx) = ReplMsg::new(HashOpts::new(name, algorithm));
+
+    Ok(context.send(msg, rx))
+}
+
+impl CmdExecutor for HashOpts {
+    async fn execute<T: Backend>(&self, backend: &mut T) -> anyhow::Result<String> {
+        ensure_table(backend, &self.name).await?;
+        backend.compute_hash(&self.name, self.algorithm).await
+    }
+}
+
+impl HashOpts {
+    pub fn new(name: String, algorithm: HashAlgorithm) -> Self {
+        Self { name, algorithm }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::backend::tests::test_backend;
+    use arrow::array::{Float64Array, Int64Array, RecordBatch};
+    use arrow::buffer::NullBuffer;
+    use arrow::datatypes::{DataType, Field, Schema};
+    use datafusion::datasource::MemTable;
+
+    #[tokio::test]
+    async fn test_hash_is_stable() -> anyhow::Result<()> {
+        let mut backend = test_backend();
+        for (algorithm, len) in [
+            (HashAlgorithm::Md5, 32),
+            (HashAlgorithm::Sha256, 64),
+            (HashAlgorithm::Xxhash, 16),
+        ] {
+            let opts = HashOpts::new("t".to_string(), algorithm);
+            let first = opts.execute(&mut backend).await?;
+            assert_eq!(first.len(), len);
+            assert_eq!(opts.execute(&mut backend).await?, first);
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_hash_changes_with_data() -> anyhow::Result<()> {
+        let mut backend = test_backend();
+        let opts = HashOpts::new("t".to_string(), HashAlgorithm::Sha256);
+        let before = opts.execute(&mut backend).await?;
+
+        backend.deregister_table("t")?;
+        let schema = Schema::new(vec![Field::new("id", DataType::Int64, false)]);
+        let batch = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![Arc::new(Int64Array::from_iter_values(0..11))],
+        )?;
+        backend.register_batch("t", batch)?;
+        assert_ne!(opts.execute(&mut backend).await?, before);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_hash_ignores_batch_boundaries() -> anyhow::Result<()> {
+        let mut backend = test_backend();
+        let opts = HashOpts::new("t".to_string(), HashAlgorithm::Sha256);
+        let before = opts.execute(&mut backend).await?;
+
+        backend.deregister_table("t")?;
+        let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Int64, false)]));
+        let batches = [0..3, 3..7, 7..10]
+            .into_iter()
+            .map(|ids| {
+                RecordBatch::try_new(
+                    schema.clone(),
+                    vec![Arc::new(Int64Array::from_iter_values(ids))],
+                )
+            })
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        backend.register_table("t", Arc::new(MemTable::try_new(schema, vec![batches])?))?;
+        assert_eq!(opts.execute(&mut backend).await?, before);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_hash_ignores_values_under_nulls() -> anyhow::Result<()> {
+        let mut backend = test_backend();
+        let opts = HashOpts::new("t".to_string(), HashAlgorithm::Sha256);
+        let schema = Arc::new(Schema::new(vec![Field::new(
+            "score",
+            DataType::Float64,
+            true,
+        )]));
+        let nulls = NullBuffer::from(vec![true, false, true]);
+        let mut hashes = Vec::new();
+        for hidden in [0.0, 42.0] {
+            let scores = Float64Array::new(vec![1.5, hidden, 2.5].into(), Some(nulls.clone()));
+            let batch = RecordBatch::try_new(schema.clone(), vec![Arc::new(scores)])?;
+            backend.deregister_table("t")?;
+            backend.register_batch("t", batch)?;
+            hashes.push(opts.execute(&mut backend).await?);
+        }
+        assert_eq!(hashes[0], hashes[1]);
+        Ok(())
+    }
+}
@@ -1,13 +1,21 @@
-use crate::{Backend, CmdExecutor, ReplContext, ReplDisplay, ReplMsg};
+use crate::{Backend, CmdExecutor, DisplayFormat, ReplContext, ReplMsg};
 use clap::{ArgMatches, Parser};
 use reedline_repl_rs::Result;
 
+use super::{ensure_table, output_results};
+
 #[derive(Debug, Parser)]
 pub struct HeadOpts {
     #[arg(short, long, help = "the name of the dataset")]
     name: String,
     #[arg(short, long, help = "the number of rows to display")]
     size: Option<usize>,
+    #[arg(short, long, default_value_t = 0, help = "the number of rows to skip")]
+    offset: usize,
+    #[arg(short, long, value_enum, default_value_t, help = "the output format")]
+    format: DisplayFormat,
+    #[arg(long, help = "write the rows to this file instead of showing them")]
+    output: Option<String>,
 }
 
 pub fn head(args: ArgMatches, context: &mut ReplContext) -> Result<Option<String>> {
@@ -16,21 +24,99 @@ pub fn head(args: ArgMatches, context: &mut ReplContext) -> Result<Option<String
         .expect("expect name")
         .to_string();
     let size = args.get_one::<usize>("size").copied();
+    let offset = args.get_one::<usize>("offset").copied().unwrap_or_default();
 
-    let (msg, rx) = ReplMsg::new(HeadOpts::new(name, size));
+    let format = args
+        .get_one::<DisplayFormat>("format")
+        .copied()
+        .unwrap_or_default();
+    let output = args.get_one::<String>("output").cloned();
+    let opts = HeadOpts::new(name, size, offset)
+        .with_format(format)
+        .with_output(output);
+    let (msg, rx) = ReplMsg::new(opts);
 
     Ok(context.send(msg, rx))
 }
 
 impl CmdExecutor for HeadOpts {
     async fn execute<T: Backend>(&self, backend: &mut T) -> anyhow::Result<String> {
-        let df = backend.head(&self.name, self.size.unwrap_or(10)).await?;
-        df.display().await
+        ensure_table(backend, &self.name).await?;
+        let df = backend
+            .head(
+                &self.name,
+                self.size.unwrap_or(backend.settings().default_head_size),
+                self.offset,
+            )
+            .await?;
+        output_results(df, self.format, self.output.as_deref(), backend.settings()).await
     }
 }
 
 impl HeadOpts {
-    pub fn new(name: String, size: Option<usize>) -> Self {
-        Self { name, size }
+    pub fn new(name: String, size: Option<usize>, offset: usize) -> Self {
+        Self {
+            name,
+            size,
+            offset,
+            format: DisplayFormat::default(),
+            output: None,
+        }
+    }
+
+    pub fn with_format(mut self, format: DisplayFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    pub fn with_output(mut self, output: Option<String>) -> Self {
+        self.output = output;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+
+    use super::*;
+    use crate::SetOpts;
+    use crate::backend::tests::{row_count, temp_dir, test_backend};
+    use arrow::ipc::reader::StreamReader;
+
+    #[tokio::test]
+    async fn test_head_uses_default_head_size() -> anyhow::Result<()> {
+        let mut backend = test_backend();
+        SetOpts::new("default_head_size".to_string(), "3".to_string())
+            .execute(&mut backend)
+            .await?;
+
+        let output = HeadOpts::new("t".to_string(), None, 0)
+            .execute(&mut backend)
+            .await?;
+        assert_eq!(row_count(&output), 3);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_head_arrow_ipc_output() -> anyhow::Result<()> {
+        let mut backend = test_backend();
+        let opts = HeadOpts::new("t".to_string(), Some(3), 0).with_format(DisplayFormat::ArrowIpc);
+        let err = opts.execute(&mut backend).await.unwrap_err();
+        assert!(err.to_string().contains("--output"));
+
+        let dir = temp_dir()?;
+        let path = dir.path().join("head.arrow").to_string_lossy().to_string();
+        let output = opts
+            .with_output(Some(path.clone()))
+            .execute(&mut backend)
+            .await?;
+        assert!(output.starts_with("Wrote "));
+        let reader = StreamReader::try_new(File::open(&path)?, None)?;
+        let rows: usize = reader
+            .map(|batch| batch.map(|batch| batch.num_rows()))
+            .sum::<std::result::Result<_, _>>()?;
+        assert_eq!(rows, 3);
+        Ok(())
     }
 }
@@ -0,0 +1,173 @@
+use arrow::{
+    array::{AsArray, RecordBatch},
+    datatypes::{Float64Type, Int64Type},
+};
+use clap::{ArgMatches, Parser};
+use reedline_repl_rs::Result;
+
+use crate::{Backend, CmdExecutor, ReplContext, ReplDisplay, ReplMsg};
+
+use super::{
+    ensure_table,
+    profile::{render_bar_chart, terminal_width},
+};
+
+/// The most bins a histogram may have.
+const MAX_BINS: u64 = 10_000;
+
+#[derive(Debug, Parser)]
+pub struct HistogramOpts {
+    #[arg(short, long, help = "the name of the dataset")]
+    name: String,
+    #[arg(short, long, help = "the numeric column to bin")]
+    column: String,
+    #[arg(
+        short,
+        long,
+        default_value_t = 10,
+        value_parser = clap::value_parser!(u64).range(1..=MAX_BINS),
+        help = "the number of bins"
+    )]
+    bins: u64,
+    #[arg(long, help = "draw the bins as a bar chart")]
+    chart: bool,
+}
+
+pub fn histogram(args: ArgMatches, context: &mut ReplContext) -> Result<Option<String>> {
+    let name = args
+        .get_one::<String>("name")
+        .expect("expect name")
+        .to_string();
+    let column = args
+        .get_one::<String>("column")
+        .expect("expect column")
+        .to_string();
+    let bins = args.get_one::<u64>("bins").copied().unwrap_or(10);
+    let chart = args.get_flag("chart");
+    let (msg, rx) = ReplMsg::new(HistogramOpts::new(name, column, bins, chart));
+
+    Ok(context.send(msg, rx))
+}
+
+impl CmdExecutor for HistogramOpts {
+    async fn execute<T: Backend>(&self, backend: &mut T) -> anyhow::Result<String> {
+        ensure_table(backend, &self.name).await?;
+        if !(1..=MAX_BINS).contains(&self.bins) {
+            anyhow::bail!("The number of bins must be between 1 and {}", MAX_BINS);
+        }
+        if !backend
+            .numeric_columns(&self.name)
+            .await?
+            .contains(&self.column)
+        {
+            anyhow::bail!(
+                "Column {} of dataset {} is not numeric",
+                self.column,
+                self.name
+            );
+        }
+        let batch = backend
+            .column_histogram(&self.name, &self.column, self.bins as usize)
+            .await?;
+        if !self.chart {
+            return batch.display(backend.settings()).await;
+        }
+        Ok(render_bar_chart(&chart_rows(&batch), terminal_width()))
+    }
+}
+
+impl HistogramOpts {
+    pub fn new(name: String, column: String, bins: u64, chart: bool) -> Self {
+        Self {
+            name,
+            column,
+            bins,
+            chart,
+        }
+    }
+}
+
+/// Label every bin by its edges.
+fn chart_rows(batch: &RecordBatch) -> Vec<(String, u64)> {
+    let starts = batch.column(0).as_primitive::<Float64Type>();
+    let ends = batch.column(1).as_primitive::<Float64Type>();
+    let counts = batch.column(2).as_primitive::<Int64Type>();
+    (0..batch.num_rows())
+        .map(|i| {
+            let label = format!("[{:.2}, {:.2})", starts.value(i), ends.value(i));
+            (label, counts.value(i) as u64)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::backend::DatafusionBackend;
+    use crate::backend::tests::{backend_with, row_cells, row_count, test_backend};
+    use arrow::array::{Float64Array, Int64Array};
+    use arrow::datatypes::{DataType, Field, Schema};
+
+    #[tokio::test]
+    async fn test_histogram_uniform() -> anyhow::Result<()> {
+        let backend = DatafusionBackend::new();
+        let schema = Schema::new(vec![Field::new("v", DataType::Int64, false)]);
+        let batch = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![Arc::new(Int64Array::from_iter_values(0..1000))],
+        )?;
+        backend.register_batch("uniform", batch)?;
+
+        let batch = backend.column_histogram("uniform", "v", 10).await?;
+        assert_eq!(batch.num_rows(), 10);
+        let counts = batch.column(2).as_primitive::<Int64Type>();
+        assert_eq!(counts.iter().flatten().sum::<i64>(), 1000);
+        assert!(counts.iter().flatten().all(|c| (90..=110).contains(&c)));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_histogram_output() -> anyhow::Result<()> {
+        let mut backend = test_backend();
+        let opts = HistogramOpts::new("t".to_string(), "id".to_string(), 3, false);
+        let output = opts.execute(&mut backend).await?;
+        assert_eq!(row_count(&output), 3);
+        assert_eq!(row_cells(&output, "6.0"), ["6.0", "9.0", "4"]);
+
+        let opts = HistogramOpts::new("t".to_string(), "id".to_string(), 3, true);
+        let output = opts.execute(&mut backend).await?;
+        let lines = output.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("[0.00, 3.00) | "));
+        assert!(lines[2].ends_with(" 4"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_histogram_skips_non_finite_values() -> anyhow::Result<()> {
+        let values = [f64::NAN, f64::INFINITY, f64::NEG_INFINITY, 0.0, 4.0];
+        let backend = backend_with(
+            "floats",
+            vec![("v", Arc::new(Float64Array::from(values.to_vec())))],
+        );
+        let batch = backend.column_histogram("floats", "v", 2).await?;
+        assert_eq!(batch.num_rows(), 2);
+        assert_eq!(batch.column(1).as_primitive::<Float64Type>().value(1), 4.0);
+        let counts = batch.column(2).as_primitive::<Int64Type>();
+        assert_eq!(counts.values().to_vec(), [1, 1]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_histogram_too_many_bins() {
+        let mut backend = test_backend();
+        let opts = HistogramOpts::new("t".to_string(), "id".to_string(), MAX_BINS + 1, false);
+        let err = opts.execute(&mut backend).await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "The number of bins must be between 1 and 10000"
+        );
+    }
+}
@@ -0,0 +1,109 @@
+use std::fs;
+
+use crate::{Backend, CmdExecutor, ConnectOpts, ReplContext, ReplMsg};
+use clap::{ArgMatches, Parser};
+use reedline_repl_rs::Result;
+use serde::{Deserialize, Serialize};
+
+use super::connect::parse_dataset_conn;
+
+#[derive(Debug, Parser)]
+pub struct ImportOpts {
+    #[arg(short, long, help = "the path of the taotie session manifest (json)")]
+    file: String,
+    #[arg(short, long, help = "prefix for all imported dataset names")]
+    prefix: Option<String>,
+}
+
+/// A session manifest lists the datasets which should be registered to Taotie.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SessionManifest {
+    pub datasets: Vec<DatasetEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DatasetEntry {
+    pub name: String,
+    pub conn: String,
+    #[serde(default)]
+    pub table: Option<String>,
+}
+
+pub fn import(args: ArgMatches, context: &mut ReplContext) -> Result<Option<String>> {
+    let file = args
+        .get_one::<String>("file")
+        .expect("expect file")
+        .to_string();
+    let prefix = args.get_one::<String>("prefix").map(|s| s.to_string());
+    let (msg, rx) = ReplMsg::new(ImportOpts::new(file, prefix));
+
+    Ok(context.send(msg, rx))
+}
+
+impl CmdExecutor for ImportOpts {
+    async fn execute<T: Backend>(&self, backend: &mut T) -> anyhow::Result<String> {
+        let content = fs::read_to_string(&self.file)?;
+        let manifest: SessionManifest = serde_json::from_str(&content)?;
+        let prefix = self.prefix.as_deref().unwrap_or_default();
+
+        let mut names = Vec::with_capacity(manifest.datasets.len());
+        for entry in manifest.datasets {
+            let conn = parse_dataset_conn(&entry.conn).map_err(anyhow::Error::msg)?;
+            let name = format!("{}{}", prefix, entry.name);
+            backend
+                .connect(&ConnectOpts::new(conn, entry.table, name.clone()))
+                .await?;
+            names.push(name);
+        }
+        Ok(format!(
+            "Imported {} datasets: {}",
+            names.len(),
+            names.join(", ")
+        ))
+    }
+}
+
+impl ImportOpts {
+    pub fn new(file: String, prefix: Option<String>) -> Self {
+        Self { file, prefix }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ListOpts;
+    use crate::backend::DatafusionBackend;
+    use crate::backend::tests::temp_dir;
+
+    #[tokio::test]
+    async fn test_import_session_manifest() -> anyhow::Result<()> {
+        let manifest = SessionManifest {
+            datasets: vec![
+                DatasetEntry {
+                    name: "users".to_string(),
+                    conn: "assets/user.ndjson".to_string(),
+                    table: None,
+                },
+                DatasetEntry {
+                    name: "sample".to_string(),
+                    conn: "assets/sample.parquet".to_string(),
+                    table: None,
+                },
+            ],
+        };
+        let dir = temp_dir()?;
+        let file = dir.path().join("manifest.json");
+        fs::write(&file, serde_json::to_string(&manifest)?)?;
+
+        let mut backend = DatafusionBackend::new();
+        let opts = ImportOpts::new(file.to_string_lossy().to_string(), Some("s1_".to_string()));
+        let output = opts.execute(&mut backend).await?;
+        assert_eq!(output, "Imported 2 datasets: s1_users, s1_sample");
+
+        let output = ListOpts::new(false).execute(&mut backend).await?;
+        assert!(output.contains("s1_users"));
+        assert!(output.contains("s1_sample"));
+        Ok(())
+    }
+}
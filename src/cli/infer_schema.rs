@@ -0,0 +1,148 @@
+use clap::{ArgMatches, Parser, ValueEnum};
+use reedline_repl_rs::Result;
+
+use crate::{Backend, CmdExecutor, ReplContext, ReplDisplay, ReplMsg};
+
+/// The format of a file whose schema is inferred.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum DataFormat {
+    Csv,
+    Json,
+    Parquet,
+}
+
+impl DataFormat {
+    /// The file extension of the format.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            DataFormat::Csv => "csv",
+            DataFormat::Json => "json",
+            DataFormat::Parquet => "parquet",
+        }
+    }
+}
+
+#[derive(Debug, Parser)]
+pub struct InferSchemaOpts {
+    #[arg(short, long, help = "the path of the file")]
+    pub file: String,
+    #[arg(short = 't', long, value_enum, help = "the format of the file")]
+    pub format: DataFormat,
+    #[arg(long, help = "the csv file has no header row")]
+    pub no_header: bool,
+}
+
+pub fn infer_schema(args: ArgMatches, context: &mut ReplContext) -> Result<Option<String>> {
+    let file = args
+        .get_one::<String>("file")
+        .expect("expect file")
+        .to_string();
+    let format = *args.get_one::<DataFormat>("format").expect("expect format");
+    let no_header = args.get_flag("no_header");
+    let (msg, rx) = ReplMsg::new(InferSchemaOpts::new(file, format, no_header));
+
+    Ok(context.send(msg, rx))
+}
+
+impl CmdExecutor for InferSchemaOpts {
+    async fn execute<T: Backend>(&self, backend: &mut T) -> anyhow::Result<String> {
+        let batch = backend.infer_schema(self).await?;
+        batch.display(backend.settings()).await
+    }
+}
+
+impl InferSchemaOpts {
+    pub fn new(file: String, format: DataFormat, no_header: bool) -> Self {
+        Self {
+            file,
+            format,
+            no_header,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, sync::Arc};
+
+    use super::*;
+    use crate::backend::tests::{row_cells, temp_dir, test_backend, write_parquet};
+    use arrow::{
+        array::{Float64Array, Int64Array, RecordBatch, StringArray},
+        datatypes::{DataType, Field, Schema},
+    };
+
+    async fn infer(file: &str, format: DataFormat, no_header: bool) -> anyhow::Result<String> {
+        let mut backend = test_backend();
+        let output = InferSchemaOpts::new(file.to_string(), format, no_header)
+            .execute(&mut backend)
+            .await?;
+        // the file is never registered
+        assert_eq!(backend.table_names().await?, ["t"]);
+        Ok(output)
+    }
+
+    #[tokio::test]
+    async fn test_infer_csv_schema() -> anyhow::Result<()> {
+        let dir = temp_dir()?;
+        let path = dir.path().join("infer_header.csv");
+        fs::write(&path, "id,name,score\n1,a,1.5\n2,b,2.5\n")?;
+        let output = infer(&path.to_string_lossy(), DataFormat::Csv, false).await?;
+        assert_eq!(row_cells(&output, "id"), ["id", "Int64", "YES"]);
+        assert_eq!(row_cells(&output, "name"), ["name", "Utf8", "YES"]);
+        assert_eq!(row_cells(&output, "score"), ["score", "Float64", "YES"]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_infer_csv_schema_without_header() -> anyhow::Result<()> {
+        let dir = temp_dir()?;
+        let path = dir.path().join("infer_no_header.csv");
+        fs::write(&path, "1,a\n2,b\n")?;
+        let output = infer(&path.to_string_lossy(), DataFormat::Csv, true).await?;
+        assert_eq!(row_cells(&output, "column_1"), ["column_1", "Int64", "YES"]);
+        assert_eq!(row_cells(&output, "column_2"), ["column_2", "Utf8", "YES"]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_infer_json_schema() -> anyhow::Result<()> {
+        let dir = temp_dir()?;
+        let path = dir.path().join("infer.json");
+        fs::write(
+            &path,
+            "{\"id\": 1, \"name\": \"a\", \"ok\": true}\n{\"id\": 2, \"name\": \"b\", \"ok\": false}\n",
+        )?;
+        let output = infer(&path.to_string_lossy(), DataFormat::Json, false).await?;
+        assert_eq!(row_cells(&output, "id"), ["id", "Int64", "YES"]);
+        assert_eq!(row_cells(&output, "name"), ["name", "Utf8", "YES"]);
+        assert_eq!(row_cells(&output, "ok"), ["ok", "Boolean", "YES"]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_infer_parquet_schema() -> anyhow::Result<()> {
+        let dir = temp_dir()?;
+        let path = dir.path().join("infer.parquet");
+        let schema = Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("price", DataType::Float64, true),
+            Field::new("name", DataType::Utf8, true),
+        ]);
+        let batch = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![
+                Arc::new(Int64Array::from(vec![1])),
+                Arc::new(Float64Array::from(vec![1.5])),
+                Arc::new(StringArray::from(vec!["a"])),
+            ],
+        )?;
+        write_parquet(&path, &batch)?;
+
+        let output = infer(&path.to_string_lossy(), DataFormat::Parquet, false).await?;
+        assert_eq!(row_cells(&output, "id"), ["id", "Int64", "NO"]);
+        assert_eq!(row_cells(&output, "price"), ["price", "Float64", "YES"]);
+        assert_eq!(row_cells(&output, "name"), ["name", "Utf8View", "YES"]);
+        Ok(())
+    }
+}
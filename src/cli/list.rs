@@ -1,21 +1,142 @@
-use crate::{Backend, CmdExecutor, ReplContext, ReplDisplay, ReplMsg};
+use std::sync::Arc;
+
+use crate::{Backend, CmdExecutor, DisplayFormat, ReplContext, ReplDisplay, ReplMsg};
+use arrow::{
+    array::RecordBatch,
+    datatypes::{Field, Schema},
+    json::ArrayWriter,
+};
 use clap::{ArgMatches, Parser};
 use reedline_repl_rs::Result;
 
-use super::ReplCommands;
+use super::{ReplCommands, output_results, write_output};
 
 #[derive(Debug, Parser)]
-pub struct ListOpts;
+pub struct ListOpts {
+    #[arg(short, long, help = "show the columns of each dataset")]
+    with_schema: bool,
+    #[arg(short, long, value_enum, default_value_t, help = "the output format")]
+    format: DisplayFormat,
+    #[arg(short, long, help = "only list the datasets of the catalog")]
+    catalog: Option<String>,
+    #[arg(long, help = "write the list to this file instead of showing it")]
+    output: Option<String>,
+}
 
-pub fn list(_args: ArgMatches, context: &mut ReplContext) -> Result<Option<String>> {
-    let (msg, rx) = ReplMsg::new(ReplCommands::List(ListOpts));
+pub fn list(args: ArgMatches, context: &mut ReplContext) -> Result<Option<String>> {
+    let with_schema = args.get_flag("with_schema");
+    let format = args
+        .get_one::<DisplayFormat>("format")
+        .copied()
+        .unwrap_or_default();
+    let catalog = args.get_one::<String>("catalog").cloned();
+    let output = args.get_one::<String>("output").cloned();
+    let opts = ListOpts::new(with_schema)
+        .with_format(format)
+        .with_catalog(catalog)
+        .with_output(output);
+    let (msg, rx) = ReplMsg::new(ReplCommands::List(opts));
 
     Ok(context.send(msg, rx))
 }
 
 impl CmdExecutor for ListOpts {
     async fn execute<T: Backend>(&self, backend: &mut T) -> anyhow::Result<String> {
-        let df = backend.list().await?;
-        df.display().await
+        let df = backend
+            .list(self.with_schema, self.catalog.as_deref())
+            .await?;
+        let text = match self.format {
+            DisplayFormat::Table => df.display(backend.settings()).await?,
+            DisplayFormat::Json => list_json(&df.into_batches().await?)?,
+            DisplayFormat::ArrowIpc => {
+                let output = self.output.as_deref();
+                return output_results(df, self.format, output, backend.settings()).await;
+            }
+        };
+        match &self.output {
+            Some(output) => write_output(output, text.as_bytes()),
+            None => Ok(text),
+        }
+    }
+}
+
+impl ListOpts {
+    pub fn new(with_schema: bool) -> Self {
+        Self {
+            with_schema,
+            format: DisplayFormat::default(),
+            catalog: None,
+            output: None,
+        }
+    }
+
+    pub fn with_format(mut self, format: DisplayFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    pub fn with_catalog(mut self, catalog: Option<String>) -> Self {
+        self.catalog = catalog;
+        self
+    }
+
+    pub fn with_output(mut self, output: Option<String>) -> Self {
+        self.output = output;
+        self
+    }
+}
+
+/// Serialize the dataset list as a JSON array, with the `table_name` and
+/// `table_type` columns renamed to `name` and `type`.
+fn list_json(batches: &[RecordBatch]) -> anyhow::Result<String> {
+    let mut writer = ArrayWriter::new(Vec::new());
+    for batch in batches {
+        let fields = batch
+            .schema()
+            .fields()
+            .iter()
+            .enumerate()
+            .map(|(i, field)| match i {
+                0 => Field::new("name", field.data_type().clone(), field.is_nullable()),
+                1 => Field::new("type", field.data_type().clone(), field.is_nullable()),
+                _ => field.as_ref().clone(),
+            })
+            .collect::<Vec<_>>();
+        let batch = RecordBatch::try_new(Arc::new(Schema::new(fields)), batch.columns().to_vec())?;
+        writer.write(&batch)?;
+    }
+    writer.finish()?;
+    Ok(String::from_utf8(writer.into_inner())?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::tests::test_backend;
+    use serde_json::{Value, json};
+
+    #[tokio::test]
+    async fn test_list_json() -> anyhow::Result<()> {
+        let mut backend = test_backend();
+        let opts = ListOpts::new(false).with_format(DisplayFormat::Json);
+        let output = opts.execute(&mut backend).await?;
+        let value: Value = serde_json::from_str(&output)?;
+        assert_eq!(value, json!([{"name": "t", "type": "BASE TABLE"}]));
+
+        backend.deregister_table("t")?;
+        let output = opts.execute(&mut backend).await?;
+        assert_eq!(output, "[]");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_list_json_with_schema() -> anyhow::Result<()> {
+        let mut backend = test_backend();
+        let opts = ListOpts::new(true).with_format(DisplayFormat::Json);
+        let output = opts.execute(&mut backend).await?;
+        let value: Value = serde_json::from_str(&output)?;
+        assert_eq!(value[0]["name"], "t");
+        assert_eq!(value[0]["columns"], "id: Int64");
+        Ok(())
     }
 }
@@ -1,25 +1,177 @@
+pub use anti_join::AntiJoinOpts;
+pub use cast_schema::CastSchemaOpts;
 use clap::Parser;
-pub use connect::{ConnectOpts, DatasetConn};
+pub use compare_distrib::CompareDistribOpts;
+pub use conn_info::ConnInfoOpts;
+pub use connect::{
+    CompressedParquetOpts, ConnectOpts, DatasetConn, FileOpts, IcebergOpts, ParquetCompression,
+};
+pub use correlate::{CorrelateOpts, CorrelationMethod};
+pub use correlate_target::CorrelateTargetOpts;
+pub use create::CreateTableFromSqlOpts;
+pub use crosstab::CrossTabOpts;
+pub use date_trunc::{AggFunc, DateTruncateOpts, TruncUnit};
+pub use deduplicate::{DeduplicateOpts, KeepRow};
 pub use describe::DescribeOpts;
+pub use describe_sample::DescribeSampleOpts;
+pub use detect_drift::DetectDriftOpts;
+pub use detect_pk::DetectPkOpts;
+pub use drop::DropOpts;
+pub use encode_cat::{EncodeCatOpts, EncodeMethod};
 use enum_dispatch::enum_dispatch;
+pub use explain::{ExplainFormat, ExplainOpts};
+pub use fill_nulls::{FillMethod, FillNullsOpts};
+pub use freq::FrequencyTableOpts;
+pub use fuzzy_join::FuzzyJoinOpts;
+pub use generate_series::{GenerateSeriesOpts, SeriesType};
+pub use hash::{HashAlgorithm, HashOpts};
 pub use head::HeadOpts;
+pub use histogram::HistogramOpts;
+pub use import::{DatasetEntry, ImportOpts, SessionManifest};
+pub use infer_schema::{DataFormat, InferSchemaOpts};
 pub use list::ListOpts;
+pub use normalize::{NormalizeMethod, NormalizeOpts};
+pub use null_audit::NullAuditOpts;
+pub use outliers::{OutlierMethod, OutliersOpts};
+pub use parquet_meta::ParquetMetaOpts;
+pub use partition_info::PartitionInfoOpts;
+pub use pivot_longer::PivotLongerOpts;
+pub use preview::PreviewOpts;
+pub use profile::ProfileOpts;
+pub use quantiles::QuantilesOpts;
+pub use register_schema::RegisterSchemaOpts;
+pub use reset::ResetOpts;
+pub use row::RowOpts;
+pub use run_file::RunFileOpts;
+pub use running_total::{RunningFunction, RunningTotalOpts};
 pub use schema::SchemaOpts;
+pub use set::SetOpts;
 pub use sql::SqlOpts;
+pub(crate) use stream::FileTail;
+pub use stream::IngestStreamingOpts;
+pub use text_search::TextSearchOpts;
+pub use time_between::{GapUnit, TimeBetweenOpts};
+pub use transpose::TransposeOpts;
+pub use type_summary::TypeSummaryOpts;
+pub use use_catalog::UseOpts;
+pub use vacuum::VacuumOpts;
+pub use validate_sql::ValidateSqlOpts;
+pub use value_counts::ValueCountsOpts;
+pub use window_stats::{WindowFunction, WindowStatsOpts};
 
+mod anti_join;
+mod cast_schema;
+mod compare_distrib;
+mod conn_info;
 mod connect;
+mod correlate;
+mod correlate_target;
+mod create;
+mod crosstab;
+mod date_trunc;
+mod deduplicate;
 mod describe;
+mod describe_sample;
+mod detect_drift;
+mod detect_pk;
+mod drop;
+mod encode_cat;
+mod explain;
+mod fill_nulls;
+mod freq;
+mod fuzzy_join;
+mod generate_series;
+mod hash;
 mod head;
+mod histogram;
+mod import;
+mod infer_schema;
 mod list;
+mod normalize;
+mod null_audit;
+mod outliers;
+mod parquet_meta;
+mod partition_info;
+mod pivot_longer;
+mod preview;
+mod profile;
+mod quantiles;
+mod register_schema;
+mod reset;
+mod row;
+mod run_file;
+mod running_total;
 mod schema;
+mod set;
 mod sql;
+mod stream;
+mod text_search;
+mod time_between;
+mod transpose;
+mod type_summary;
+mod use_catalog;
+mod vacuum;
+mod validate_sql;
+mod value_counts;
+mod window_stats;
 
+pub use anti_join::anti_join;
+pub use cast_schema::cast_schema;
+pub use compare_distrib::compare_distrib;
+pub use conn_info::conn_info;
 pub use connect::connect;
+pub use correlate::correlate;
+pub use correlate_target::correlate_target;
+pub use create::create;
+pub use crosstab::crosstab;
+pub use date_trunc::date_trunc;
+pub use deduplicate::deduplicate;
 pub use describe::describe;
+pub use describe_sample::describe_sample;
+pub use detect_drift::detect_drift;
+pub use detect_pk::detect_pk;
+pub use drop::drop_dataset;
+pub use encode_cat::encode_cat;
+pub use explain::explain;
+pub use fill_nulls::fill_nulls;
+pub use freq::freq;
+pub use fuzzy_join::fuzzy_join;
+pub use generate_series::generate_series;
+pub use hash::hash;
 pub use head::head;
+pub use histogram::histogram;
+pub use import::import;
+pub use infer_schema::infer_schema;
 pub use list::list;
+pub use normalize::normalize;
+pub use null_audit::null_audit;
+pub use outliers::outliers;
+pub use parquet_meta::parquet_meta;
+pub use partition_info::partition_info;
+pub use pivot_longer::pivot_longer;
+pub use preview::preview;
+pub use profile::profile;
+pub use quantiles::quantiles;
+pub use register_schema::register_schema;
+pub use reset::reset;
+pub use row::row;
+pub use run_file::run_file;
+pub use running_total::running_total;
 pub use schema::schema;
+pub use set::set;
 pub use sql::sql;
+pub use stream::stream;
+pub use text_search::text_search;
+pub use time_between::time_between;
+pub use transpose::transpose;
+pub use type_summary::type_summary;
+pub use use_catalog::use_catalog;
+pub use vacuum::vacuum;
+pub use validate_sql::validate_sql;
+pub use value_counts::value_counts;
+pub use window_stats::window_stats;
+
+use crate::{Backend, DisplayFormat, MessagePriority, ReplDisplay, Settings};
 
 #[derive(Parser, Debug)]
 #[command(
@@ -51,4 +203,374 @@ pub enum ReplCommands {
     Sql(SqlOpts),
     #[command(about = "Show the schema of a dataset")]
     Schema(SchemaOpts),
+    #[command(
+        name = "validate-sql",
+        about = "Check a SQL query without executing it"
+    )]
+    ValidateSql(ValidateSqlOpts),
+    #[command(name = "null-audit", about = "Count the nulls of every column")]
+    NullAudit(NullAuditOpts),
+    #[command(about = "Show a single row of a dataset by index")]
+    Row(RowOpts),
+    #[command(about = "Import datasets from a taotie session manifest")]
+    Import(ImportOpts),
+    #[command(about = "Set a session variable, e.g. default_head_size")]
+    Set(SetOpts),
+    #[command(about = "Show a row of a wide dataset as field/value pairs")]
+    Transpose(TransposeOpts),
+    #[command(
+        name = "parquet-meta",
+        about = "Show the metadata of a parquet file without registering it"
+    )]
+    ParquetMeta(ParquetMetaOpts),
+    #[command(
+        name = "infer-schema",
+        about = "Preview the inferred schema of a file without registering it"
+    )]
+    InferSchema(InferSchemaOpts),
+    #[command(about = "Materialize the result of a SQL query as an in-memory dataset")]
+    Create(CreateTableFromSqlOpts),
+    #[command(about = "Remove a dataset from Taotie")]
+    Drop(DropOpts),
+    #[command(
+        name = "conn-info",
+        about = "Show how a dataset was originally connected or created"
+    )]
+    ConnInfo(ConnInfoOpts),
+    #[command(about = "Release the memory held by unused in-memory data")]
+    Vacuum(VacuumOpts),
+    #[command(about = "Show the plan of a SQL query")]
+    Explain(ExplainOpts),
+    #[command(name = "use", about = "Set the catalog of unqualified dataset names")]
+    Use(UseOpts),
+    #[command(about = "Show the correlation matrix of the numeric columns")]
+    Correlate(CorrelateOpts),
+    #[command(about = "Show the most frequent values of a column")]
+    Profile(ProfileOpts),
+    #[command(about = "Show distinct values of a column, e.g. for where predicates")]
+    Preview(PreviewOpts),
+    #[command(
+        name = "window-stats",
+        about = "Materialize a rolling statistic of a column as a new dataset"
+    )]
+    WindowStats(WindowStatsOpts),
+    #[command(about = "Show the rows whose value of a column is an outlier")]
+    Outliers(OutliersOpts),
+    #[command(name = "freq", about = "Show how often each value of a column occurs")]
+    FrequencyTable(FrequencyTableOpts),
+    #[command(about = "Show the given quantiles of a numeric column")]
+    Quantiles(QuantilesOpts),
+    #[command(
+        name = "crosstab",
+        about = "Count the rows of every pair of values of two columns"
+    )]
+    CrossTab(CrossTabOpts),
+    #[command(about = "Materialize a dataset with rescaled numeric columns")]
+    Normalize(NormalizeOpts),
+    #[command(
+        name = "generate-series",
+        about = "Create a dataset with a sequence of numbers or dates"
+    )]
+    GenerateSeries(GenerateSeriesOpts),
+    #[command(name = "run-file", about = "Run the SQL statements of a file")]
+    RunFile(RunFileOpts),
+    #[command(
+        name = "detect-drift",
+        about = "Compare the schema of a dataset with its source"
+    )]
+    DetectDrift(DetectDriftOpts),
+    #[command(
+        name = "partition-info",
+        about = "Show the partitions of a partitioned parquet dataset"
+    )]
+    PartitionInfo(PartitionInfoOpts),
+    #[command(
+        name = "cast-schema",
+        about = "Cast the columns of a dataset to new types in place"
+    )]
+    CastSchema(CastSchemaOpts),
+    #[command(about = "Drop every connected and materialized dataset")]
+    Reset(ResetOpts),
+    #[command(
+        name = "stream",
+        about = "Append the rows written to a csv file to a dataset"
+    )]
+    IngestStreaming(IngestStreamingOpts),
+    #[command(about = "Fingerprint the content of a dataset")]
+    Hash(HashOpts),
+    #[command(
+        name = "text-search",
+        about = "Find the rows whose text contains a query"
+    )]
+    TextSearch(TextSearchOpts),
+    #[command(about = "Count the values of a numeric column in equal width bins")]
+    Histogram(HistogramOpts),
+    #[command(
+        name = "compare-distrib",
+        about = "Compare the distributions of two numeric columns with a KS test"
+    )]
+    CompareDistrib(CompareDistribOpts),
+    #[command(
+        name = "fill-nulls",
+        about = "Materialize a dataset with the nulls of a column filled in"
+    )]
+    FillNulls(FillNullsOpts),
+    #[command(
+        name = "date-trunc",
+        about = "Aggregate a dataset by year, month, week, day or hour"
+    )]
+    DateTruncate(DateTruncateOpts),
+    #[command(about = "Remove the duplicate rows of a dataset")]
+    Deduplicate(DeduplicateOpts),
+    #[command(
+        name = "describe-sample",
+        about = "Describe a random sample of a large dataset"
+    )]
+    DescribeSample(DescribeSampleOpts),
+    #[command(
+        name = "type-summary",
+        about = "Count the columns of a dataset by data type"
+    )]
+    TypeSummary(TypeSummaryOpts),
+    #[command(
+        name = "pivot-longer",
+        about = "Melt the value columns of a wide dataset into long format"
+    )]
+    PivotLonger(PivotLongerOpts),
+    #[command(
+        name = "time-between",
+        about = "Materialize the time gaps between consecutive rows"
+    )]
+    TimeBetween(TimeBetweenOpts),
+    #[command(
+        name = "encode-cat",
+        about = "Label or one-hot encode a categorical column"
+    )]
+    EncodeCat(EncodeCatOpts),
+    #[command(
+        name = "running-total",
+        about = "Materialize a cumulative sum, count, mean, max or min"
+    )]
+    RunningTotal(RunningTotalOpts),
+    #[command(
+        name = "anti-join",
+        about = "Materialize the rows of a dataset without a match in another"
+    )]
+    AntiJoin(AntiJoinOpts),
+    #[command(
+        name = "value-counts",
+        about = "Count the values of every low-cardinality column"
+    )]
+    ValueCounts(ValueCountsOpts),
+    #[command(
+        name = "fuzzy-join",
+        about = "Join two datasets on approximately matching strings"
+    )]
+    FuzzyJoin(FuzzyJoinOpts),
+    #[command(
+        name = "detect-pk",
+        about = "Find the columns which could be a primary key"
+    )]
+    DetectPk(DetectPkOpts),
+    #[command(
+        name = "register-schema",
+        about = "Register an empty dataset from a JSON schema file"
+    )]
+    RegisterSchema(RegisterSchemaOpts),
+    #[command(
+        name = "correlate-target",
+        about = "Rank numeric columns by their correlation with a target column"
+    )]
+    CorrelateTarget(CorrelateTargetOpts),
+}
+
+impl ReplCommands {
+    /// Settings take effect ahead of queued queries. Everything else, drops
+    /// included, runs in the order it was sent.
+    pub fn priority(&self) -> MessagePriority {
+        match self {
+            ReplCommands::Set(_) => MessagePriority::Control,
+            _ => MessagePriority::Normal,
+        }
+    }
+}
+
+/// Fail with a friendly error if the dataset is not registered.
+async fn ensure_table<T: Backend>(backend: &T, name: &str) -> anyhow::Result<()> {
+    if !backend.table_exists(name).await? {
+        anyhow::bail!(
+            "Table '{}' not found. Use 'list' to see available tables.",
+            name
+        );
+    }
+    Ok(())
+}
+
+/// Show the results in the given format, or write them to the output file.
+/// Arrow IPC is binary, so it is only ever written to a file.
+async fn output_results<R: ReplDisplay>(
+    results: R,
+    format: DisplayFormat,
+    output: Option<&str>,
+    settings: &Settings,
+) -> anyhow::Result<String> {
+    match output {
+        Some(path) => write_output(path, &results.display_bytes(format, settings).await?),
+        None if format == DisplayFormat::ArrowIpc => {
+            anyhow::bail!("Arrow IPC results are binary, write them to a file with --output")
+        }
+        None => Ok(String::from_utf8(
+            results.display_bytes(format, settings).await?,
+        )?),
+    }
+}
+
+fn write_output(path: &str, bytes: &[u8]) -> anyhow::Result<String> {
+    std::fs::write(path, bytes)?;
+    Ok(format!("Wrote {} bytes to {}", bytes.len(), path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::tests::test_backend;
+    use crate::{CmdExecutor, callbacks_map};
+    use clap::CommandFactory;
+
+    #[test]
+    fn test_every_command_has_a_callback() {
+        let callbacks = callbacks_map();
+        let command = ReplCommand::command();
+        let names = command
+            .get_subcommands()
+            .map(|cmd| cmd.get_name())
+            .collect::<Vec<_>>();
+        for name in &names {
+            assert!(callbacks.contains_key(*name), "{} has no callback", name);
+        }
+        assert_eq!(callbacks.len(), names.len());
+
+        let parsed = ReplCommand::try_parse_from([
+            "cli",
+            "register-schema",
+            "--name",
+            "users",
+            "--schema-file",
+            "users.json",
+        ])
+        .unwrap();
+        assert!(matches!(parsed.command, ReplCommands::RegisterSchema(_)));
+    }
+
+    #[tokio::test]
+    async fn test_missing_table_error() {
+        let mut backend = test_backend();
+        let name = "missing".to_string();
+        let commands: Vec<ReplCommands> = vec![
+            DescribeOpts::new(name.clone()).into(),
+            HeadOpts::new(name.clone(), None, 0).into(),
+            NullAuditOpts::new(name.clone()).into(),
+            RowOpts::new(name.clone(), 0).into(),
+            SchemaOpts::new(Some(name.clone()), false).into(),
+            TransposeOpts::new(name.clone(), 0).into(),
+            DropOpts::new(name.clone()).into(),
+            ConnInfoOpts::new(name.clone()).into(),
+            DetectDriftOpts::new(name.clone()).into(),
+            PartitionInfoOpts::new(name.clone()).into(),
+            CastSchemaOpts::new(name.clone(), true, vec![]).into(),
+            HashOpts::new(name.clone(), HashAlgorithm::Sha256).into(),
+            TextSearchOpts::new(name.clone(), "a".to_string(), 10).into(),
+            HistogramOpts::new(name.clone(), "id".to_string(), 10, false).into(),
+            CompareDistribOpts::new(name.clone(), "id".to_string(), "id".to_string()).into(),
+            DeduplicateOpts::new(name.clone(), None).into(),
+            DescribeSampleOpts::new(name.clone(), 10).into(),
+            TypeSummaryOpts::new(name.clone()).into(),
+            PivotLongerOpts::new(name.clone(), vec![], vec![]).into(),
+            ValueCountsOpts::new(name.clone(), 100).into(),
+            DetectPkOpts::new(name.clone()).into(),
+            CorrelateTargetOpts::new(name.clone(), "id".to_string(), CorrelationMethod::Pearson)
+                .into(),
+            FuzzyJoinOpts::new(
+                name.clone(),
+                name.clone(),
+                "id".to_string(),
+                "id".to_string(),
+                "out".to_string(),
+            )
+            .into(),
+            AntiJoinOpts::new(
+                name.clone(),
+                name.clone(),
+                "id".to_string(),
+                "out".to_string(),
+            )
+            .into(),
+            RunningTotalOpts::new(
+                name.clone(),
+                "id".to_string(),
+                "id".to_string(),
+                RunningFunction::Sum,
+                "out".to_string(),
+            )
+            .into(),
+            EncodeCatOpts::new(
+                name.clone(),
+                "id".to_string(),
+                EncodeMethod::Label,
+                "out".to_string(),
+            )
+            .into(),
+            TimeBetweenOpts::new(
+                name.clone(),
+                "id".to_string(),
+                GapUnit::Seconds,
+                "out".to_string(),
+            )
+            .into(),
+            DateTruncateOpts::new(
+                name.clone(),
+                "id".to_string(),
+                TruncUnit::Month,
+                "out".to_string(),
+            )
+            .into(),
+            FillNullsOpts::new(
+                name.clone(),
+                "id".to_string(),
+                FillMethod::Mean,
+                "out".to_string(),
+            )
+            .into(),
+            IngestStreamingOpts::new(name.clone(), "missing.csv".to_string(), 0).into(),
+            CorrelateOpts::new(name.clone(), CorrelationMethod::Pearson).into(),
+            ProfileOpts::new(name.clone(), "id".to_string(), 10, false).into(),
+            PreviewOpts::new(name.clone(), "id".to_string(), 10).into(),
+            OutliersOpts::new(name.clone(), "id".to_string(), OutlierMethod::Iqr).into(),
+            FrequencyTableOpts::new(name.clone(), "id".to_string(), false).into(),
+            QuantilesOpts::new(name.clone(), "id".to_string(), vec![0.5]).into(),
+            CrossTabOpts::new(name.clone(), "id".to_string(), "id".to_string()).into(),
+            NormalizeOpts::new(
+                name.clone(),
+                vec!["id".to_string()],
+                NormalizeMethod::Minmax,
+                "out".to_string(),
+            )
+            .into(),
+            WindowStatsOpts::new(
+                name.clone(),
+                "id".to_string(),
+                "id".to_string(),
+                3,
+                WindowFunction::RollingMean,
+            )
+            .into(),
+        ];
+        for command in commands {
+            let err = command.execute(&mut backend).await.unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                "Table 'missing' not found. Use 'list' to see available tables."
+            );
+        }
+    }
 }
@@ -0,0 +1,220 @@
+use clap::{ArgMatches, Parser, ValueEnum};
+use reedline_repl_rs::Result;
+
+use crate::{Backend, CmdExecutor, ReplContext, ReplMsg};
+
+use super::ensure_table;
+
+/// How the values of a column are rescaled.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum NormalizeMethod {
+    /// Scale into [0, 1] by the minimum and maximum.
+    #[default]
+    Minmax,
+    /// Center on the mean and scale by the standard deviation.
+    Zscore,
+}
+
+#[derive(Debug, Parser)]
+pub struct NormalizeOpts {
+    #[arg(short, long, help = "the name of the dataset")]
+    name: String,
+    #[arg(
+        short,
+        long,
+        value_delimiter = ',',
+        required = true,
+        help = "the numeric columns to normalize, comma separated or repeated"
+    )]
+    columns: Vec<String>,
+    #[arg(short, long, value_enum, default_value_t, help = "minmax or zscore")]
+    method: NormalizeMethod,
+    #[arg(short, long, help = "the name of the new dataset")]
+    output: String,
+}
+
+pub fn normalize(args: ArgMatches, context: &mut ReplContext) -> Result<Option<String>> {
+    let name = args
+        .get_one::<String>("name")
+        .expect("expect name")
+        .to_string();
+    let columns = args
+        .get_many::<String>("columns")
+        .expect("expect columns")
+        .cloned()
+        .collect();
+    let method = args
+        .get_one::<NormalizeMethod>("method")
+        .copied()
+        .unwrap_or_default();
+    let output = args
+        .get_one::<String>("output")
+        .expect("expect output")
+        .to_string();
+    let (msg, rx) = ReplMsg::new(NormalizeOpts::new(name, columns, method, output));
+
+    Ok(context.send(msg, rx))
+}
+
+impl CmdExecutor for NormalizeOpts {
+    async fn execute<T: Backend>(&self, backend: &mut T) -> anyhow::Result<String> {
+        ensure_table(backend, &self.name).await?;
+        let numeric = backend.numeric_columns(&self.name).await?;
+        if let Some(column) = self.columns.iter().find(|c| !numeric.contains(c)) {
+            anyhow::bail!("Column {} of dataset {} is not numeric", column, self.name);
+        }
+        let all = backend.column_names(&self.name).await?;
+        backend
+            .create_table_from_sql(&self.output, &self.sql(&all))
+            .await?;
+        Ok(format!("Materialized normalized dataset {}", self.output))
+    }
+}
+
+impl NormalizeOpts {
+    pub fn new(
+        name: String,
+        columns: Vec<String>,
+        method: NormalizeMethod,
+        output: String,
+    ) -> Self {
+        Self {
+            name,
+            columns,
+            method,
+            output,
+        }
+    }
+
+    /// Keep every column of the dataset, replacing the normalized ones with
+    /// their rescaled values. The statistics come from a single sub-select.
+    fn sql(&self, all: &[String]) -> String {
+        let mut stats = Vec::with_capacity(self.columns.len() * 2);
+        for (i, column) in self.columns.iter().enumerate() {
+            let (low, high) = match self.method {
+                NormalizeMethod::Minmax => ("MIN", "MAX"),
+                NormalizeMethod::Zscore => ("AVG", "STDDEV"),
+            };
+            stats.push(format!("{}(CAST(\"{}\" AS DOUBLE)) AS a{}", low, column, i));
+            stats.push(format!(
+                "{}(CAST(\"{}\" AS DOUBLE)) AS b{}",
+                high, column, i
+            ));
+        }
+        let projection = all
+            .iter()
+            .map(
+                |column| match self.columns.iter().position(|c| c == column) {
+                    Some(i) => {
+                        let scale = match self.method {
+                            NormalizeMethod::Minmax => format!("s.b{0} - s.a{0}", i),
+                            NormalizeMethod::Zscore => format!("s.b{}", i),
+                        };
+                        format!(
+                            "(CAST(t.\"{0}\" AS DOUBLE) - s.a{1}) / NULLIF({2}, 0) AS \"{0}\"",
+                            column, i, scale
+                        )
+                    }
+                    None => format!("t.\"{}\"", column),
+                },
+            )
+            .collect::<Vec<_>>();
+        format!(
+            "SELECT {} FROM {2} t CROSS JOIN (SELECT {} FROM {2}) s",
+            projection.join(", "),
+            stats.join(", "),
+            self.name
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::ReplDisplay;
+    use crate::backend::DatafusionBackend;
+    use crate::backend::tests::backend_with;
+    use arrow::array::{AsArray, Int64Array, StringArray};
+    use arrow::datatypes::Float64Type;
+
+    fn scores_backend() -> DatafusionBackend {
+        backend_with(
+            "scores",
+            vec![
+                (
+                    "player",
+                    Arc::new(StringArray::from(vec!["a", "b", "c", "d", "e"])),
+                ),
+                (
+                    "score",
+                    Arc::new(Int64Array::from(vec![10, 20, 30, 40, 50])),
+                ),
+            ],
+        )
+    }
+
+    async fn normalized_stats(
+        backend: &mut DatafusionBackend,
+        method: NormalizeMethod,
+        stats: &str,
+    ) -> anyhow::Result<(f64, f64)> {
+        let opts = NormalizeOpts::new(
+            "scores".to_string(),
+            vec!["score".to_string()],
+            method,
+            "normalized".to_string(),
+        );
+        let output = opts.execute(backend).await?;
+        assert_eq!(output, "Materialized normalized dataset normalized");
+
+        let sql = format!("SELECT {} FROM normalized", stats);
+        let batches = backend.sql(&sql).await?.into_batches().await?;
+        let value = |i: usize| batches[0].column(i).as_primitive::<Float64Type>().value(0);
+        Ok((value(0), value(1)))
+    }
+
+    #[tokio::test]
+    async fn test_normalize_minmax() -> anyhow::Result<()> {
+        let mut backend = scores_backend();
+        let stats = "MIN(score), MAX(score)";
+        let (min, max) = normalized_stats(&mut backend, NormalizeMethod::Minmax, stats).await?;
+        assert_eq!((min, max), (0.0, 1.0));
+
+        // the other columns are kept as they are
+        let batches = backend
+            .sql("SELECT player FROM normalized")
+            .await?
+            .into_batches()
+            .await?;
+        assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 5);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_normalize_zscore() -> anyhow::Result<()> {
+        let mut backend = scores_backend();
+        let stats = "AVG(score), STDDEV(score)";
+        let (mean, sd) = normalized_stats(&mut backend, NormalizeMethod::Zscore, stats).await?;
+        assert!(mean.abs() < 1e-9);
+        assert!((sd - 1.0).abs() < 1e-9);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_normalize_non_numeric_column() {
+        let mut backend = scores_backend();
+        let opts = NormalizeOpts::new(
+            "scores".to_string(),
+            vec!["player".to_string()],
+            NormalizeMethod::Minmax,
+            "normalized".to_string(),
+        );
+        let err = opts.execute(&mut backend).await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Column player of dataset scores is not numeric"
+        );
+    }
+}
@@ -0,0 +1,35 @@
+use crate::{Backend, CmdExecutor, ReplContext, ReplDisplay, ReplMsg};
+use clap::{ArgMatches, Parser};
+use reedline_repl_rs::Result;
+
+use super::ensure_table;
+
+#[derive(Debug, Parser)]
+pub struct NullAuditOpts {
+    #[arg(short, long, help = "the name of the dataset")]
+    name: String,
+}
+
+pub fn null_audit(args: ArgMatches, context: &mut ReplContext) -> Result<Option<String>> {
+    let name = args
+        .get_one::<String>("name")
+        .expect("expect name")
+        .to_string();
+    let (msg, rx) = ReplMsg::new(NullAuditOpts::new(name));
+
+    Ok(context.send(msg, rx))
+}
+
+impl CmdExecutor for NullAuditOpts {
+    async fn execute<T: Backend>(&self, backend: &mut T) -> anyhow::Result<String> {
+        ensure_table(backend, &self.name).await?;
+        let batch = backend.count_nulls(&self.name).await?;
+        batch.display(backend.settings()).await
+    }
+}
+
+impl NullAuditOpts {
+    pub fn new(name: String) -> Self {
+        Self { name }
+    }
+}
@@ -0,0 +1,131 @@
+use clap::{ArgMatches, Parser, ValueEnum};
+use reedline_repl_rs::Result;
+
+use crate::{Backend, CmdExecutor, ReplContext, ReplDisplay, ReplMsg};
+
+use super::ensure_table;
+
+/// How the outliers of a column are detected.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutlierMethod {
+    /// Outside 1.5 interquartile ranges of the first and third quartiles.
+    #[default]
+    Iqr,
+    /// More than 3 standard deviations away from the mean.
+    Zscore,
+}
+
+#[derive(Debug, Parser)]
+pub struct OutliersOpts {
+    #[arg(short, long, help = "the name of the dataset")]
+    name: String,
+    #[arg(short, long, help = "the numeric column to check")]
+    column: String,
+    #[arg(
+        short,
+        long,
+        value_enum,
+        default_value_t,
+        help = "iqr for quartile fences, zscore for distance from the mean"
+    )]
+    method: OutlierMethod,
+}
+
+pub fn outliers(args: ArgMatches, context: &mut ReplContext) -> Result<Option<String>> {
+    let name = args
+        .get_one::<String>("name")
+        .expect("expect name")
+        .to_string();
+    let column = args
+        .get_one::<String>("column")
+        .expect("expect column")
+        .to_string();
+    let method = args
+        .get_one::<OutlierMethod>("method")
+        .copied()
+        .unwrap_or_default();
+    let (msg, rx) = ReplMsg::new(OutliersOpts::new(name, column, method));
+
+    Ok(context.send(msg, rx))
+}
+
+impl CmdExecutor for OutliersOpts {
+    async fn execute<T: Backend>(&self, backend: &mut T) -> anyhow::Result<String> {
+        ensure_table(backend, &self.name).await?;
+        if !backend
+            .numeric_columns(&self.name)
+            .await?
+            .contains(&self.column)
+        {
+            anyhow::bail!(
+                "Column {} of dataset {} is not numeric",
+                self.column,
+                self.name
+            );
+        }
+        let rows = backend
+            .outliers(&self.name, &self.column, self.method)
+            .await?;
+        rows.display(backend.settings()).await
+    }
+}
+
+impl OutliersOpts {
+    pub fn new(name: String, column: String, method: OutlierMethod) -> Self {
+        Self {
+            name,
+            column,
+            method,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::backend::DatafusionBackend;
+    use crate::backend::tests::{backend_with, row_cells, row_count};
+    use arrow::array::{Int64Array, StringArray};
+
+    fn readings_backend() -> DatafusionBackend {
+        let values = (10..30).chain([1000]).collect::<Vec<i64>>();
+        let sensors = values.iter().map(|v| format!("s{}", v)).collect::<Vec<_>>();
+        backend_with(
+            "readings",
+            vec![
+                ("sensor", Arc::new(StringArray::from(sensors))),
+                ("value", Arc::new(Int64Array::from(values))),
+            ],
+        )
+    }
+
+    #[tokio::test]
+    async fn test_outliers_detected_by_both_methods() -> anyhow::Result<()> {
+        let mut backend = readings_backend();
+        for method in [OutlierMethod::Iqr, OutlierMethod::Zscore] {
+            let opts = OutliersOpts::new("readings".to_string(), "value".to_string(), method);
+            let output = opts.execute(&mut backend).await?;
+            assert_eq!(row_count(&output), 1, "{:?}", method);
+            assert_eq!(row_cells(&output, "s1000"), ["s1000", "1000"]);
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_outliers_non_numeric_column() -> anyhow::Result<()> {
+        let mut backend = readings_backend();
+        let opts = OutliersOpts::new(
+            "readings".to_string(),
+            "sensor".to_string(),
+            OutlierMethod::Iqr,
+        );
+        let err = opts.execute(&mut backend).await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Column sensor of dataset readings is not numeric"
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,165 @@
+use std::{fs::File, sync::Arc};
+
+use crate::{Backend, CmdExecutor, ReplContext, ReplDisplay, ReplMsg};
+use arrow::{
+    array::{RecordBatch, StringArray},
+    datatypes::{DataType, Field, Schema},
+};
+use clap::{ArgMatches, Parser};
+use parquet::file::{metadata::ParquetMetaDataReader, statistics::Statistics};
+use reedline_repl_rs::Result;
+
+#[derive(Debug, Parser)]
+pub struct ParquetMetaOpts {
+    #[arg(short, long, help = "the path of the parquet file")]
+    file: String,
+}
+
+pub fn parquet_meta(args: ArgMatches, context: &mut ReplContext) -> Result<Option<String>> {
+    let file = args
+        .get_one::<String>("file")
+        .expect("expect file")
+        .to_string();
+    let (msg, rx) = ReplMsg::new(ParquetMetaOpts::new(file));
+
+    Ok(context.send(msg, rx))
+}
+
+impl CmdExecutor for ParquetMetaOpts {
+    async fn execute<T: Backend>(&self, backend: &mut T) -> anyhow::Result<String> {
+        let batch = parquet_meta_batch(&self.file)?;
+        batch.display(backend.settings()).await
+    }
+}
+
+impl ParquetMetaOpts {
+    pub fn new(file: String) -> Self {
+        Self { file }
+    }
+}
+
+/// Read the footer of a parquet file into `property` / `value` rows: the row
+/// group and row counts, then one row per column chunk.
+fn parquet_meta_batch(path: &str) -> anyhow::Result<RecordBatch> {
+    let file = File::open(path)?;
+    let metadata = ParquetMetaDataReader::new().parse_and_finish(&file)?;
+
+    let mut properties = vec!["row_group_count".to_string(), "total_row_count".to_string()];
+    let mut values = vec![
+        metadata.num_row_groups().to_string(),
+        metadata.file_metadata().num_rows().to_string(),
+    ];
+    for (i, row_group) in metadata.row_groups().iter().enumerate() {
+        for column in row_group.columns() {
+            let mut value = format!(
+                "compression={:?}, encodings={:?}, values={}",
+                column.compression(),
+                column.encodings(),
+                column.num_values()
+            );
+            if let Some(stats) = column.statistics() {
+                if let Some(nulls) = stats.null_count_opt() {
+                    value = format!("{}, nulls={}", value, nulls);
+                }
+                if let Some((min, max)) = min_max(stats) {
+                    value = format!("{}, min={}, max={}", value, min, max);
+                }
+            }
+            properties.push(format!("row_group_{}.{}", i, column.column_path().string()));
+            values.push(value);
+        }
+    }
+
+    let schema = Schema::new(vec![
+        Field::new("property", DataType::Utf8, false),
+        Field::new("value", DataType::Utf8, false),
+    ]);
+    let batch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(StringArray::from(properties)),
+            Arc::new(StringArray::from(values)),
+        ],
+    )?;
+    Ok(batch)
+}
+
+fn min_max(stats: &Statistics) -> Option<(String, String)> {
+    let range = match stats {
+        Statistics::Boolean(s) => (s.min_opt()?.to_string(), s.max_opt()?.to_string()),
+        Statistics::Int32(s) => (s.min_opt()?.to_string(), s.max_opt()?.to_string()),
+        Statistics::Int64(s) => (s.min_opt()?.to_string(), s.max_opt()?.to_string()),
+        Statistics::Int96(s) => (format!("{:?}", s.min_opt()?), format!("{:?}", s.max_opt()?)),
+        Statistics::Float(s) => (s.min_opt()?.to_string(), s.max_opt()?.to_string()),
+        Statistics::Double(s) => (s.min_opt()?.to_string(), s.max_opt()?.to_string()),
+        Statistics::ByteArray(s) => (
+            String::from_utf8_lossy(s.min_opt()?.data()).to_string(),
+            String::from_utf8_lossy(s.max_opt()?.data()).to_string(),
+        ),
+        Statistics::FixedLenByteArray(s) => (
+            String::from_utf8_lossy(s.min_opt()?.data()).to_string(),
+            String::from_utf8_lossy(s.max_opt()?.data()).to_string(),
+        ),
+    };
+    Some(range)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::tests::{row_cells, temp_dir, test_backend};
+    use arrow::array::Int64Array;
+    use parquet::{arrow::ArrowWriter, file::properties::WriterProperties};
+
+    #[tokio::test]
+    async fn test_parquet_meta_row_groups() -> anyhow::Result<()> {
+        let dir = temp_dir()?;
+        let path = dir.path().join("parquet_meta.parquet");
+        let schema = Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("name", DataType::Utf8, false),
+        ]);
+        let names = (0..10).map(|i| format!("n{}", i)).collect::<Vec<_>>();
+        let batch = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![
+                Arc::new(Int64Array::from_iter_values(0..10)),
+                Arc::new(StringArray::from(names)),
+            ],
+        )?;
+        let props = WriterProperties::builder()
+            .set_max_row_group_size(4)
+            .build();
+        let mut writer = ArrowWriter::try_new(File::create(&path)?, batch.schema(), Some(props))?;
+        writer.write(&batch)?;
+        writer.close()?;
+
+        let mut backend = test_backend();
+        let output = ParquetMetaOpts::new(path.to_string_lossy().to_string())
+            .execute(&mut backend)
+            .await?;
+        assert_eq!(
+            row_cells(&output, "row_group_count"),
+            ["row_group_count", "3"]
+        );
+        assert_eq!(
+            row_cells(&output, "total_row_count"),
+            ["total_row_count", "10"]
+        );
+
+        let cells = row_cells(&output, "row_group_0.id");
+        assert!(cells[1].contains("values=4"));
+        assert!(cells[1].contains("nulls=0, min=0, max=3"));
+        let cells = row_cells(&output, "row_group_2.name");
+        assert!(cells[1].contains("values=2"));
+        assert!(cells[1].contains("min=n8, max=n9"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_parquet_meta_missing_file() {
+        let mut backend = test_backend();
+        let opts = ParquetMetaOpts::new("missing.parquet".to_string());
+        assert!(opts.execute(&mut backend).await.is_err());
+    }
+}
@@ -0,0 +1,93 @@
+use crate::{Backend, CmdExecutor, ReplContext, ReplDisplay, ReplMsg};
+use clap::{ArgMatches, Parser};
+use reedline_repl_rs::Result;
+
+use super::ensure_table;
+
+#[derive(Debug, Parser)]
+pub struct PartitionInfoOpts {
+    #[arg(short, long, help = "the name of the dataset")]
+    name: String,
+}
+
+pub fn partition_info(args: ArgMatches, context: &mut ReplContext) -> Result<Option<String>> {
+    let name = args
+        .get_one::<String>("name")
+        .expect("expect name")
+        .to_string();
+    let (msg, rx) = ReplMsg::new(PartitionInfoOpts::new(name));
+
+    Ok(context.send(msg, rx))
+}
+
+impl CmdExecutor for PartitionInfoOpts {
+    async fn execute<T: Backend>(&self, backend: &mut T) -> anyhow::Result<String> {
+        ensure_table(backend, &self.name).await?;
+        let batch = backend.partition_info(&self.name).await?;
+        batch.display(backend.settings()).await
+    }
+}
+
+impl PartitionInfoOpts {
+    pub fn new(name: String) -> Self {
+        Self { name }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, sync::Arc};
+
+    use super::*;
+    use crate::backend::DatafusionBackend;
+    use crate::backend::tests::{row_cells, row_count, temp_dir, test_backend, write_parquet};
+    use crate::{ConnectOpts, DatasetConn};
+    use arrow::array::{Int64Array, RecordBatch};
+    use arrow::datatypes::{DataType, Field, Schema};
+
+    #[tokio::test]
+    async fn test_partition_info() -> anyhow::Result<()> {
+        let tmp = temp_dir()?;
+        let dir = tmp.path();
+        let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Int64, false)]));
+        for (region, ids) in [("eu", 0..3), ("us", 3..5)] {
+            let partition = dir.join(format!("region={}", region));
+            fs::create_dir_all(&partition)?;
+            let batch = RecordBatch::try_new(
+                schema.clone(),
+                vec![Arc::new(Int64Array::from_iter_values(ids))],
+            )?;
+            write_parquet(&partition.join("part-0.parquet"), &batch)?;
+        }
+
+        let mut backend = DatafusionBackend::new();
+        let path = dir.to_string_lossy().to_string();
+        let opts = ConnectOpts::new(DatasetConn::Parquet(path), None, "sales".to_string());
+        backend.connect(&opts).await?;
+
+        let output = PartitionInfoOpts::new("sales".to_string())
+            .execute(&mut backend)
+            .await?;
+        assert_eq!(row_count(&output), 2);
+        assert_eq!(row_cells(&output, "region"), ["region", "eu", "3"]);
+        let last = output.lines().rfind(|line| line.starts_with('|'));
+        let cells = last
+            .unwrap()
+            .trim_matches('|')
+            .split('|')
+            .map(|cell| cell.trim())
+            .collect::<Vec<_>>();
+        assert_eq!(cells, ["region", "us", "2"]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_partition_info_not_partitioned() {
+        let mut backend = test_backend();
+        let err = PartitionInfoOpts::new("t".to_string())
+            .execute(&mut backend)
+            .await
+            .unwrap_err();
+        assert_eq!(err.to_string(), "Dataset t is not partitioned");
+    }
+}
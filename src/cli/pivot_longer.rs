@@ -0,0 +1,170 @@
+use clap::{ArgMatches, Parser};
+use reedline_repl_rs::Result;
+
+use crate::{Backend, CmdExecutor, ReplContext, ReplDisplay, ReplMsg};
+
+use super::ensure_table;
+
+#[derive(Debug, Parser)]
+pub struct PivotLongerOpts {
+    #[arg(short, long, help = "the name of the dataset")]
+    name: String,
+    #[arg(
+        short,
+        long,
+        value_delimiter = ',',
+        help = "the columns kept as identifiers"
+    )]
+    id_cols: Vec<String>,
+    #[arg(
+        long,
+        value_delimiter = ',',
+        help = "the columns to melt, all but the id columns by default"
+    )]
+    value_cols: Vec<String>,
+    #[arg(
+        long,
+        default_value = "variable",
+        help = "the name of the column holding the melted column names"
+    )]
+    variable_col_name: String,
+    #[arg(
+        long,
+        default_value = "value",
+        help = "the name of the column holding the melted values"
+    )]
+    value_col_name: String,
+}
+
+pub fn pivot_longer(args: ArgMatches, context: &mut ReplContext) -> Result<Option<String>> {
+    let name = args
+        .get_one::<String>("name")
+        .expect("expect name")
+        .to_string();
+    let columns = |id: &str| {
+        args.get_many::<String>(id)
+            .map(|columns| columns.cloned().collect())
+            .unwrap_or_default()
+    };
+    let variable_col_name = args
+        .get_one::<String>("variable_col_name")
+        .expect("expect variable_col_name")
+        .to_string();
+    let value_col_name = args
+        .get_one::<String>("value_col_name")
+        .expect("expect value_col_name")
+        .to_string();
+    let opts = PivotLongerOpts::new(name, columns("id_cols"), columns("value_cols"))
+        .with_column_names(variable_col_name, value_col_name);
+    let (msg, rx) = ReplMsg::new(opts);
+
+    Ok(context.send(msg, rx))
+}
+
+impl CmdExecutor for PivotLongerOpts {
+    async fn execute<T: Backend>(&self, backend: &mut T) -> anyhow::Result<String> {
+        ensure_table(backend, &self.name).await?;
+        let output = backend
+            .pivot_longer(
+                &self.name,
+                &self.id_cols,
+                &self.value_cols,
+                &self.variable_col_name,
+                &self.value_col_name,
+            )
+            .await?;
+        output.display(backend.settings()).await
+    }
+}
+
+impl PivotLongerOpts {
+    pub fn new(name: String, id_cols: Vec<String>, value_cols: Vec<String>) -> Self {
+        Self {
+            name,
+            id_cols,
+            value_cols,
+            variable_col_name: "variable".to_string(),
+            value_col_name: "value".to_string(),
+        }
+    }
+
+    pub fn with_column_names(mut self, variable_col_name: String, value_col_name: String) -> Self {
+        self.variable_col_name = variable_col_name;
+        self.value_col_name = value_col_name;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::backend::DatafusionBackend;
+    use crate::backend::tests::{backend_with, row_cells, row_count};
+    use arrow::array::{Int64Array, StringArray};
+
+    fn population_backend() -> DatafusionBackend {
+        backend_with(
+            "population",
+            vec![
+                ("country", Arc::new(StringArray::from(vec!["nz", "fj"]))),
+                ("year", Arc::new(Int64Array::from(vec![2020, 2021]))),
+                ("population", Arc::new(Int64Array::from(vec![5, 1]))),
+            ],
+        )
+    }
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_pivot_longer() -> anyhow::Result<()> {
+        let mut backend = population_backend();
+        let output = PivotLongerOpts::new(
+            "population".to_string(),
+            strings(&["country", "year"]),
+            strings(&["population"]),
+        )
+        .execute(&mut backend)
+        .await?;
+        assert_eq!(row_count(&output), 2);
+        assert_eq!(
+            row_cells(&output, "country"),
+            ["country", "year", "variable", "value"]
+        );
+        assert_eq!(row_cells(&output, "nz"), ["nz", "2020", "population", "5"]);
+        assert_eq!(row_cells(&output, "fj"), ["fj", "2021", "population", "1"]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_pivot_longer_options() -> anyhow::Result<()> {
+        let mut backend = population_backend();
+        // the value columns default to all but the id columns
+        let output = PivotLongerOpts::new("population".to_string(), strings(&["country"]), vec![])
+            .with_column_names("metric".to_string(), "amount".to_string())
+            .execute(&mut backend)
+            .await?;
+        assert_eq!(row_count(&output), 4);
+        assert_eq!(
+            row_cells(&output, "country"),
+            ["country", "metric", "amount"]
+        );
+
+        let err = PivotLongerOpts::new(
+            "population".to_string(),
+            strings(&["country"]),
+            strings(&["missing"]),
+        )
+        .execute(&mut backend)
+        .await
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Dataset population has no column(s): missing"
+        );
+        Ok(())
+    }
+}
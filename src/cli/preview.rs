@@ -0,0 +1,55 @@
+use crate::{Backend, CmdExecutor, ReplContext, ReplMsg};
+use clap::{ArgMatches, Parser};
+use reedline_repl_rs::Result;
+
+use super::ensure_table;
+
+#[derive(Debug, Parser)]
+pub struct PreviewOpts {
+    #[arg(short, long, help = "the name of the dataset")]
+    name: String,
+    #[arg(short, long, help = "the column to preview")]
+    column: String,
+    #[arg(
+        short,
+        long,
+        default_value_t = 10,
+        help = "the maximum number of distinct values"
+    )]
+    limit: usize,
+}
+
+pub fn preview(args: ArgMatches, context: &mut ReplContext) -> Result<Option<String>> {
+    let name = args
+        .get_one::<String>("name")
+        .expect("expect name")
+        .to_string();
+    let column = args
+        .get_one::<String>("column")
+        .expect("expect column")
+        .to_string();
+    let limit = args.get_one::<usize>("limit").copied().unwrap_or(10);
+    let (msg, rx) = ReplMsg::new(PreviewOpts::new(name, column, limit));
+
+    Ok(context.send(msg, rx))
+}
+
+impl CmdExecutor for PreviewOpts {
+    async fn execute<T: Backend>(&self, backend: &mut T) -> anyhow::Result<String> {
+        ensure_table(backend, &self.name).await?;
+        let values = backend
+            .preview_values(&self.name, &self.column, self.limit)
+            .await?;
+        Ok(values.join("\n"))
+    }
+}
+
+impl PreviewOpts {
+    pub fn new(name: String, column: String, limit: usize) -> Self {
+        Self {
+            name,
+            column,
+            limit,
+        }
+    }
+}
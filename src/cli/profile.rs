@@ -0,0 +1,181 @@
+use std::env;
+
+use arrow::{
+    array::{Array, AsArray, RecordBatch},
+    datatypes::Int64Type,
+};
+use clap::{ArgMatches, Parser};
+use reedline_repl_rs::Result;
+
+use crate::{Backend, CmdExecutor, ReplContext, ReplDisplay, ReplMsg};
+
+use super::ensure_table;
+
+const DEFAULT_WIDTH: usize = 80;
+const BAR: char = '█';
+
+#[derive(Debug, Parser)]
+pub struct ProfileOpts {
+    #[arg(short, long, help = "the name of the dataset")]
+    name: String,
+    #[arg(short, long, help = "the column to profile")]
+    column: String,
+    #[arg(
+        short,
+        long,
+        default_value_t = 10,
+        help = "the number of most frequent values"
+    )]
+    top: usize,
+    #[arg(long, help = "draw the value distribution as a bar chart")]
+    chart: bool,
+}
+
+pub fn profile(args: ArgMatches, context: &mut ReplContext) -> Result<Option<String>> {
+    let name = args
+        .get_one::<String>("name")
+        .expect("expect name")
+        .to_string();
+    let column = args
+        .get_one::<String>("column")
+        .expect("expect column")
+        .to_string();
+    let top = args.get_one::<usize>("top").copied().unwrap_or(10);
+    let chart = args.get_flag("chart");
+    let (msg, rx) = ReplMsg::new(ProfileOpts::new(name, column, top, chart));
+
+    Ok(context.send(msg, rx))
+}
+
+impl CmdExecutor for ProfileOpts {
+    async fn execute<T: Backend>(&self, backend: &mut T) -> anyhow::Result<String> {
+        ensure_table(backend, &self.name).await?;
+        let batch = backend
+            .value_counts(&self.name, &self.column, self.top)
+            .await?;
+        if !self.chart {
+            return batch.display(backend.settings()).await;
+        }
+        let rows = chart_rows(&batch, &backend.settings().null_display);
+        Ok(render_bar_chart(&rows, terminal_width()))
+    }
+}
+
+impl ProfileOpts {
+    pub fn new(name: String, column: String, top: usize, chart: bool) -> Self {
+        Self {
+            name,
+            column,
+            top,
+            chart,
+        }
+    }
+}
+
+/// Draw one bar per value, the longest bar filling the width left after the
+/// labels and counts.
+pub(crate) fn render_bar_chart(rows: &[(String, u64)], width: usize) -> String {
+    let label_width = rows.iter().map(|(label, _)| label.chars().count()).max();
+    let count_width = rows.iter().map(|(_, count)| count.to_string().len()).max();
+    let max_count = rows.iter().map(|(_, count)| *count).max();
+    let (Some(label_width), Some(count_width), Some(max_count)) =
+        (label_width, count_width, max_count)
+    else {
+        return String::new();
+    };
+    let bar_width = width.saturating_sub(label_width + count_width + 4).max(1);
+
+    rows.iter()
+        .map(|(label, count)| {
+            let len = if max_count == 0 {
+                0
+            } else {
+                (*count as u128 * bar_width as u128 / max_count as u128) as usize
+            };
+            let bar = BAR.to_string().repeat(len);
+            let padding = label_width - label.chars().count();
+            format!("{}{} | {} {}", label, " ".repeat(padding), bar, count)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn chart_rows(batch: &RecordBatch, null_display: &str) -> Vec<(String, u64)> {
+    let values = batch.column(0).as_string::<i32>();
+    let counts = batch.column(1).as_primitive::<Int64Type>();
+    (0..batch.num_rows())
+        .map(|i| {
+            let value = if values.is_null(i) {
+                null_display.to_string()
+            } else {
+                values.value(i).to_string()
+            };
+            (value, counts.value(i) as u64)
+        })
+        .collect()
+}
+
+pub(crate) fn terminal_width() -> usize {
+    env::var("COLUMNS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_WIDTH)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::backend::DatafusionBackend;
+    use crate::backend::tests::{row_cells, row_count};
+    use arrow::array::StringArray;
+    use arrow::datatypes::{DataType, Field, Schema};
+
+    fn bar_len(line: &str) -> usize {
+        line.chars().filter(|c| *c == BAR).count()
+    }
+
+    #[test]
+    fn test_render_bar_chart_ratio() {
+        let rows = vec![("a".to_string(), 30), ("b".to_string(), 10)];
+        let chart = render_bar_chart(&rows, 67);
+        let lines = chart.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(bar_len(lines[0]), 60);
+        assert_eq!(bar_len(lines[0]), 3 * bar_len(lines[1]));
+        assert!(lines[0].starts_with("a | "));
+        assert!(lines[1].ends_with(" 10"));
+        assert_eq!(render_bar_chart(&[], 80), "");
+    }
+
+    #[tokio::test]
+    async fn test_profile_value_counts() -> anyhow::Result<()> {
+        let mut backend = DatafusionBackend::new();
+        let schema = Schema::new(vec![Field::new("color", DataType::Utf8, true)]);
+        let batch = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![Arc::new(StringArray::from(vec![
+                Some("red"),
+                Some("red"),
+                Some("red"),
+                Some("blue"),
+                None,
+            ]))],
+        )?;
+        backend.register_batch("paint", batch)?;
+
+        let opts = ProfileOpts::new("paint".to_string(), "color".to_string(), 2, false);
+        let output = opts.execute(&mut backend).await?;
+        assert_eq!(row_cells(&output, "red"), ["red", "3"]);
+        assert_eq!(row_count(&output), 2);
+
+        let opts = ProfileOpts::new("paint".to_string(), "color".to_string(), 10, true);
+        let output = opts.execute(&mut backend).await?;
+        let lines = output.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("red "));
+        assert!(bar_len(lines[0]) > bar_len(lines[1]));
+        Ok(())
+    }
+}
@@ -0,0 +1,104 @@
+use clap::{ArgMatches, Parser};
+use reedline_repl_rs::Result;
+
+use crate::{Backend, CmdExecutor, ReplContext, ReplDisplay, ReplMsg};
+
+use super::ensure_table;
+
+#[derive(Debug, Parser)]
+pub struct QuantilesOpts {
+    #[arg(short, long, help = "the name of the dataset")]
+    name: String,
+    #[arg(short, long, help = "the numeric column to compute the quantiles of")]
+    column: String,
+    #[arg(
+        short,
+        long,
+        num_args = 1..,
+        required = true,
+        help = "the quantiles between 0 and 1, e.g. 0.1 0.5 0.9"
+    )]
+    quantiles: Vec<f64>,
+}
+
+pub fn quantiles(args: ArgMatches, context: &mut ReplContext) -> Result<Option<String>> {
+    let name = args
+        .get_one::<String>("name")
+        .expect("expect name")
+        .to_string();
+    let column = args
+        .get_one::<String>("column")
+        .expect("expect column")
+        .to_string();
+    let quantiles = args
+        .get_many::<f64>("quantiles")
+        .expect("expect quantiles")
+        .copied()
+        .collect();
+    let (msg, rx) = ReplMsg::new(QuantilesOpts::new(name, column, quantiles));
+
+    Ok(context.send(msg, rx))
+}
+
+impl CmdExecutor for QuantilesOpts {
+    async fn execute<T: Backend>(&self, backend: &mut T) -> anyhow::Result<String> {
+        ensure_table(backend, &self.name).await?;
+        if let Some(q) = self.quantiles.iter().find(|q| !(0.0..=1.0).contains(*q)) {
+            anyhow::bail!("Quantile {} is not between 0 and 1", q);
+        }
+        let batch = backend
+            .quantiles(&self.name, &self.column, &self.quantiles)
+            .await?;
+        batch.display(backend.settings()).await
+    }
+}
+
+impl QuantilesOpts {
+    pub fn new(name: String, column: String, quantiles: Vec<f64>) -> Self {
+        Self {
+            name,
+            column,
+            quantiles,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::tests::{row_cells, row_count, test_backend};
+    use arrow::array::AsArray;
+    use arrow::datatypes::Float64Type;
+
+    #[tokio::test]
+    async fn test_quantiles() -> anyhow::Result<()> {
+        let mut backend = test_backend();
+        let opts = QuantilesOpts::new("t".to_string(), "id".to_string(), vec![0.0, 0.5, 1.0]);
+        let output = opts.execute(&mut backend).await?;
+        assert_eq!(row_count(&output), 3);
+        assert_eq!(row_cells(&output, "0.0"), ["0.0", "0.0"]);
+        assert_eq!(row_cells(&output, "1.0"), ["1.0", "9.0"]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_median_matches_approx_percentile_cont() -> anyhow::Result<()> {
+        let backend = test_backend();
+        let batch = backend.quantiles("t", "id", &[0.5]).await?;
+        let median = batch.column(1).as_primitive::<Float64Type>().value(0);
+
+        let sql = "SELECT approx_percentile_cont(CAST(id AS DOUBLE), 0.5, 100) FROM t";
+        let batches = backend.sql(sql).await?.into_batches().await?;
+        let expected = batches[0].column(0).as_primitive::<Float64Type>().value(0);
+        assert_eq!(median, expected);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_quantile_out_of_range() {
+        let mut backend = test_backend();
+        let opts = QuantilesOpts::new("t".to_string(), "id".to_string(), vec![0.5, 1.5]);
+        let err = opts.execute(&mut backend).await.unwrap_err();
+        assert_eq!(err.to_string(), "Quantile 1.5 is not between 0 and 1");
+    }
+}
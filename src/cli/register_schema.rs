@@ -0,0 +1,148 @@
+use std::{fs, str::FromStr, sync::Arc};
+
+use arrow::datatypes::{DataType, Field, Schema};
+use clap::{ArgMatches, Parser};
+use reedline_repl_rs::Result;
+use serde::Deserialize;
+
+use crate::{Backend, CmdExecutor, ReplContext, ReplMsg};
+
+#[derive(Debug, Parser)]
+pub struct RegisterSchemaOpts {
+    #[arg(short, long, help = "the name of the dataset")]
+    name: String,
+    #[arg(
+        short,
+        long,
+        help = "a JSON file of fields with a name, a data_type (e.g. Int64) and nullable"
+    )]
+    schema_file: String,
+}
+
+/// A schema as `{"fields": [{"name": ..., "data_type": ..., "nullable": ...}]}`,
+/// the field names arrow serializes a schema with.
+#[derive(Debug, Deserialize)]
+struct SchemaDef {
+    fields: Vec<FieldDef>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FieldDef {
+    name: String,
+    data_type: String,
+    #[serde(default = "default_nullable")]
+    nullable: bool,
+}
+
+fn default_nullable() -> bool {
+    true
+}
+
+pub fn register_schema(args: ArgMatches, context: &mut ReplContext) -> Result<Option<String>> {
+    let name = args
+        .get_one::<String>("name")
+        .expect("expect name")
+        .to_string();
+    let schema_file = args
+        .get_one::<String>("schema_file")
+        .expect("expect schema_file")
+        .to_string();
+    let (msg, rx) = ReplMsg::new(RegisterSchemaOpts::new(name, schema_file));
+
+    Ok(context.send(msg, rx))
+}
+
+impl CmdExecutor for RegisterSchemaOpts {
+    async fn execute<T: Backend>(&self, backend: &mut T) -> anyhow::Result<String> {
+        if backend.table_exists(&self.name).await? {
+            anyhow::bail!("Dataset {} already exists", self.name);
+        }
+        let schema = parse_schema(&fs::read_to_string(&self.schema_file)?)?;
+        let columns = schema.fields().len();
+        backend
+            .register_schema(&self.name, Arc::new(schema))
+            .await?;
+        Ok(format!(
+            "Registered empty dataset {} with {} column(s)",
+            self.name, columns
+        ))
+    }
+}
+
+impl RegisterSchemaOpts {
+    pub fn new(name: String, schema_file: String) -> Self {
+        Self { name, schema_file }
+    }
+}
+
+fn parse_schema(json: &str) -> anyhow::Result<Schema> {
+    let def: SchemaDef = serde_json::from_str(json)?;
+    let fields = def
+        .fields
+        .into_iter()
+        .map(|field| {
+            let data_type = DataType::from_str(&field.data_type).map_err(|_| {
+                anyhow::anyhow!(
+                    "Invalid data type {} of column {}",
+                    field.data_type,
+                    field.name
+                )
+            })?;
+            Ok(Field::new(field.name, data_type, field.nullable))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    Ok(Schema::new(fields))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::DatafusionBackend;
+    use crate::backend::tests::{row_cells, row_count, temp_dir};
+    use crate::{SchemaOpts, ValidateSqlOpts};
+
+    #[tokio::test]
+    async fn test_register_schema() -> anyhow::Result<()> {
+        let dir = temp_dir()?;
+        let path = dir.path().join("register_schema.json");
+        let json = r#"{"fields": [
+            {"name": "id", "data_type": "Int64", "nullable": false},
+            {"name": "name", "data_type": "Utf8"},
+            {"name": "signed_up", "data_type": "Date32"}
+        ]}"#;
+        fs::write(&path, json)?;
+
+        let mut backend = DatafusionBackend::new();
+        let opts = RegisterSchemaOpts::new("users".to_string(), path.to_string_lossy().to_string());
+        let output = opts.execute(&mut backend).await?;
+        assert_eq!(output, "Registered empty dataset users with 3 column(s)");
+
+        let output = SchemaOpts::new(Some("users".to_string()), false)
+            .execute(&mut backend)
+            .await?;
+        assert_eq!(row_count(&output), 3);
+        assert_eq!(row_cells(&output, "id"), ["id", "Int64", "NO"]);
+        assert_eq!(row_cells(&output, "name"), ["name", "Utf8", "YES"]);
+        assert_eq!(
+            row_cells(&output, "signed_up"),
+            ["signed_up", "Date32", "YES"]
+        );
+
+        let query = "SELECT name FROM users WHERE signed_up > '2024-01-01'".to_string();
+        let output = ValidateSqlOpts::new(query, false)
+            .execute(&mut backend)
+            .await?;
+        assert_eq!(output, "Query is valid");
+
+        let err = opts.execute(&mut backend).await.unwrap_err();
+        assert_eq!(err.to_string(), "Dataset users already exists");
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_schema_invalid_type() {
+        let json = r#"{"fields": [{"name": "id", "data_type": "Integer"}]}"#;
+        let err = parse_schema(json).unwrap_err();
+        assert_eq!(err.to_string(), "Invalid data type Integer of column id");
+    }
+}
@@ -0,0 +1,73 @@
+use crate::{Backend, CmdExecutor, ReplContext, ReplMsg};
+use clap::{ArgMatches, Parser};
+use reedline_repl_rs::Result;
+
+#[derive(Debug, Parser)]
+pub struct ResetOpts {}
+
+pub fn reset(_args: ArgMatches, context: &mut ReplContext) -> Result<Option<String>> {
+    let (msg, rx) = ReplMsg::new(ResetOpts::new());
+
+    Ok(context.send(msg, rx))
+}
+
+impl CmdExecutor for ResetOpts {
+    async fn execute<T: Backend>(&self, backend: &mut T) -> anyhow::Result<String> {
+        let dropped = backend.drop_all().await?;
+        Ok(format!("Reset dropped {} dataset(s)", dropped))
+    }
+}
+
+impl ResetOpts {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Default for ResetOpts {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::backend::DatafusionBackend;
+    use crate::backend::tests::{row_count, temp_dir};
+    use crate::cli::connect::parse_dataset_conn;
+    use crate::{ConnectOpts, ListOpts, ReplDisplay};
+
+    #[tokio::test]
+    async fn test_reset_drops_all_datasets() -> anyhow::Result<()> {
+        let dir = temp_dir()?;
+        let path = dir.path().join("reset.csv");
+        fs::write(&path, "id,name\n1,a\n2,b\n3,c\n")?;
+        let conn = parse_dataset_conn(&path.to_string_lossy()).map_err(anyhow::Error::msg)?;
+        let opts = ConnectOpts::new(conn, None, "users".to_string());
+
+        let mut backend = DatafusionBackend::new();
+        backend.connect(&opts).await?;
+        backend
+            .create_table_from_sql("small", "SELECT * FROM users WHERE id < 3")
+            .await?;
+        // created through SQL, so not tracked by the backend
+        backend
+            .sql("CREATE TABLE extra AS VALUES (1)")
+            .await?
+            .display(backend.settings())
+            .await?;
+
+        let output = ResetOpts::new().execute(&mut backend).await?;
+        assert_eq!(output, "Reset dropped 3 dataset(s)");
+        assert!(backend.table_names().await?.is_empty());
+        let list = ListOpts::new(false).execute(&mut backend).await?;
+        assert_eq!(row_count(&list), 0);
+
+        backend.connect(&opts).await?;
+        assert_eq!(backend.table("users").await?.count().await?, 3);
+        Ok(())
+    }
+}
@@ -0,0 +1,79 @@
+use crate::{Backend, CmdExecutor, ReplContext, ReplMsg, backend::format_vertical};
+use clap::{ArgMatches, Parser};
+use reedline_repl_rs::Result;
+
+use super::ensure_table;
+
+#[derive(Debug, Parser)]
+pub struct RowOpts {
+    #[arg(short, long, help = "the name of the dataset")]
+    name: String,
+    #[arg(short, long, help = "the index of the row (0-based)")]
+    index: usize,
+}
+
+pub fn row(args: ArgMatches, context: &mut ReplContext) -> Result<Option<String>> {
+    let name = args
+        .get_one::<String>("name")
+        .expect("expect name")
+        .to_string();
+    let index = *args.get_one::<usize>("index").expect("expect index");
+    let (msg, rx) = ReplMsg::new(RowOpts::new(name, index));
+
+    Ok(context.send(msg, rx))
+}
+
+impl CmdExecutor for RowOpts {
+    async fn execute<T: Backend>(&self, backend: &mut T) -> anyhow::Result<String> {
+        ensure_table(backend, &self.name).await?;
+        match backend.row_at(&self.name, self.index).await? {
+            Some(batch) => format_vertical(&batch, self.index, backend.settings()),
+            None => Ok(format!(
+                "Warning: row {} is out of range for dataset {}",
+                self.index, self.name
+            )),
+        }
+    }
+}
+
+impl RowOpts {
+    pub fn new(name: String, index: usize) -> Self {
+        Self { name, index }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::tests::test_backend;
+
+    #[tokio::test]
+    async fn test_row_first() -> anyhow::Result<()> {
+        let mut backend = test_backend();
+        let output = RowOpts::new("t".to_string(), 0)
+            .execute(&mut backend)
+            .await?;
+        assert_eq!(output, "-[ RECORD 0 ]-\nid | 0");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_row_middle() -> anyhow::Result<()> {
+        let mut backend = test_backend();
+        let output = RowOpts::new("t".to_string(), 5)
+            .execute(&mut backend)
+            .await?;
+        assert_eq!(output, "-[ RECORD 5 ]-\nid | 5");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_row_out_of_range() -> anyhow::Result<()> {
+        let mut backend = test_backend();
+        let output = RowOpts::new("t".to_string(), 20)
+            .execute(&mut backend)
+            .await?;
+        assert_eq!(output, "Warning: row 20 is out of range for dataset t");
+        Ok(())
+    }
+}
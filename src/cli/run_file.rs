@@ -0,0 +1,176 @@
+use std::fs;
+
+use crate::{Backend, CmdExecutor, ReplContext, ReplDisplay, ReplMsg};
+use clap::{ArgMatches, Parser};
+use reedline_repl_rs::Result;
+
+#[derive(Debug, Parser)]
+pub struct RunFileOpts {
+    #[arg(short, long, help = "the .sql file to run")]
+    file: String,
+    #[arg(long, help = "stop at the first failing statement")]
+    fail_fast: bool,
+}
+
+pub fn run_file(args: ArgMatches, context: &mut ReplContext) -> Result<Option<String>> {
+    let file = args
+        .get_one::<String>("file")
+        .expect("expect file")
+        .to_string();
+    let fail_fast = args.get_flag("fail_fast");
+    let (msg, rx) = ReplMsg::new(RunFileOpts::new(file, fail_fast));
+
+    Ok(context.send(msg, rx))
+}
+
+impl CmdExecutor for RunFileOpts {
+    async fn execute<T: Backend>(&self, backend: &mut T) -> anyhow::Result<String> {
+        let script = fs::read_to_string(&self.file)?;
+        let mut outputs = Vec::new();
+        for (line, statement) in split_statements(&script) {
+            let batches = match backend.sql(&statement).await {
+                Ok(df) => df.into_batches().await,
+                Err(e) => Err(e),
+            };
+            match batches {
+                Ok(batches) => {
+                    let rows = batches.iter().map(|b| b.num_rows()).sum::<usize>();
+                    let output = batches.display(backend.settings()).await?;
+                    outputs.push(format!("{}\n({} rows)", output, rows));
+                }
+                Err(e) if self.fail_fast => {
+                    anyhow::bail!("Error at line {}: {}", line, e);
+                }
+                Err(e) => outputs.push(format!("Error at line {}: {}", line, e)),
+            }
+        }
+        Ok(outputs.join("\n\n"))
+    }
+}
+
+impl RunFileOpts {
+    pub fn new(file: String, fail_fast: bool) -> Self {
+        Self { file, fail_fast }
+    }
+}
+
+/// Split a script into its statements, each with the line it starts on.
+/// Comments are dropped, and semicolons inside quotes do not end a statement.
+fn split_statements(script: &str) -> Vec<(usize, String)> {
+    let mut statements = Vec::new();
+    let mut current = String::new();
+    let mut start = None;
+    let mut line = 1;
+    let mut chars = script.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '-' if chars.peek() == Some(&'-') => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        line += 1;
+                        current.push('\n');
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut prev = ' ';
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        line += 1;
+                    }
+                    if prev == '*' && c == '/' {
+                        break;
+                    }
+                    prev = c;
+                }
+                current.push(' ');
+            }
+            '\'' | '"' => {
+                start.get_or_insert(line);
+                current.push(c);
+                for q in chars.by_ref() {
+                    if q == '\n' {
+                        line += 1;
+                    }
+                    current.push(q);
+                    if q == c {
+                        break;
+                    }
+                }
+            }
+            ';' => {
+                if let Some(start) = start.take() {
+                    statements.push((start, current.trim().to_string()));
+                }
+                current.clear();
+            }
+            c => {
+                if c == '\n' {
+                    line += 1;
+                } else if !c.is_whitespace() {
+                    start.get_or_insert(line);
+                }
+                current.push(c);
+            }
+        }
+    }
+    if let Some(start) = start {
+        statements.push((start, current.trim().to_string()));
+    }
+    statements
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::tests::{temp_dir, test_backend};
+
+    const SCRIPT: &str = "-- count the rows
+SELECT COUNT(*) AS n FROM t;
+
+/* a block
+   comment */
+SELECT 'a;b' AS s;
+SELECT * FROM missing;
+SELECT id FROM t WHERE id < 3";
+
+    #[test]
+    fn test_split_statements() {
+        let statements = split_statements(SCRIPT);
+        assert_eq!(
+            statements,
+            [
+                (2, "SELECT COUNT(*) AS n FROM t".to_string()),
+                (6, "SELECT 'a;b' AS s".to_string()),
+                (7, "SELECT * FROM missing".to_string()),
+                (8, "SELECT id FROM t WHERE id < 3".to_string()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_run_file() -> anyhow::Result<()> {
+        let dir = temp_dir()?;
+        let path = dir.path().join("run_file.sql");
+        fs::write(&path, SCRIPT)?;
+        let file = path.to_string_lossy().to_string();
+        let mut backend = test_backend();
+
+        let output = RunFileOpts::new(file.clone(), false)
+            .execute(&mut backend)
+            .await?;
+        assert!(output.contains("| 10 |"));
+        assert!(output.contains("| a;b |"));
+        assert!(output.contains("Error at line 7:"));
+        assert!(output.contains("(3 rows)"));
+
+        let err = RunFileOpts::new(file, true)
+            .execute(&mut backend)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().starts_with("Error at line 7:"));
+        Ok(())
+    }
+}
@@ -0,0 +1,257 @@
+use clap::{ArgMatches, Parser, ValueEnum};
+use reedline_repl_rs::Result;
+
+use crate::{Backend, CmdExecutor, ReplContext, ReplMsg};
+
+use super::ensure_table;
+
+/// The aggregate accumulated over all the preceding rows.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum RunningFunction {
+    #[default]
+    Sum,
+    Count,
+    Mean,
+    Max,
+    Min,
+}
+
+#[derive(Debug, Parser)]
+pub struct RunningTotalOpts {
+    #[arg(short, long, help = "the name of the dataset")]
+    name: String,
+    #[arg(short, long, help = "the column the total is computed on")]
+    value_col: String,
+    #[arg(long, help = "the column the rows are ordered by")]
+    order_col: String,
+    #[arg(
+        short,
+        long,
+        value_enum,
+        default_value_t,
+        help = "sum, count, mean, max or min"
+    )]
+    function: RunningFunction,
+    #[arg(short, long, help = "restart the total for each group of this column")]
+    group_by: Option<String>,
+    #[arg(short, long, help = "the name of the new dataset")]
+    output: String,
+}
+
+pub fn running_total(args: ArgMatches, context: &mut ReplContext) -> Result<Option<String>> {
+    let name = args
+        .get_one::<String>("name")
+        .expect("expect name")
+        .to_string();
+    let value_col = args
+        .get_one::<String>("value_col")
+        .expect("expect value_col")
+        .to_string();
+    let order_col = args
+        .get_one::<String>("order_col")
+        .expect("expect order_col")
+        .to_string();
+    let function = args
+        .get_one::<RunningFunction>("function")
+        .copied()
+        .unwrap_or_default();
+    let output = args
+        .get_one::<String>("output")
+        .expect("expect output")
+        .to_string();
+    let opts = RunningTotalOpts::new(name, value_col, order_col, function, output)
+        .with_group_by(args.get_one::<String>("group_by").cloned());
+    let (msg, rx) = ReplMsg::new(opts);
+
+    Ok(context.send(msg, rx))
+}
+
+impl CmdExecutor for RunningTotalOpts {
+    async fn execute<T: Backend>(&self, backend: &mut T) -> anyhow::Result<String> {
+        ensure_table(backend, &self.name).await?;
+        backend
+            .create_table_from_sql(&self.output, &self.sql())
+            .await?;
+        Ok(format!(
+            "Materialized running {} of {} as dataset {}",
+            self.function.as_str(),
+            self.value_col,
+            self.output
+        ))
+    }
+}
+
+impl RunningTotalOpts {
+    pub fn new(
+        name: String,
+        value_col: String,
+        order_col: String,
+        function: RunningFunction,
+        output: String,
+    ) -> Self {
+        Self {
+            name,
+            value_col,
+            order_col,
+            function,
+            group_by: None,
+            output,
+        }
+    }
+
+    pub fn with_group_by(mut self, group_by: Option<String>) -> Self {
+        self.group_by = group_by;
+        self
+    }
+
+    /// The total is cast to a double so every function yields the same type.
+    fn sql(&self) -> String {
+        let partition = self
+            .group_by
+            .as_ref()
+            .map(|group| format!("PARTITION BY \"{}\" ", group))
+            .unwrap_or_default();
+        let order = match &self.group_by {
+            Some(group) => format!("\"{}\", \"{}\"", group, self.order_col),
+            None => format!("\"{}\"", self.order_col),
+        };
+        format!(
+            "SELECT *, CAST({0}(\"{1}\") OVER ({2}ORDER BY \"{3}\" \
+            ROWS UNBOUNDED PRECEDING) AS DOUBLE) AS running_{4} FROM {5} ORDER BY {6}",
+            self.function.aggregate(),
+            self.value_col,
+            partition,
+            self.order_col,
+            self.function.as_str(),
+            self.name,
+            order
+        )
+    }
+}
+
+impl RunningFunction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Sum => "sum",
+            Self::Count => "count",
+            Self::Mean => "mean",
+            Self::Max => "max",
+            Self::Min => "min",
+        }
+    }
+
+    fn aggregate(&self) -> &'static str {
+        match self {
+            Self::Sum => "SUM",
+            Self::Count => "COUNT",
+            Self::Mean => "AVG",
+            Self::Max => "MAX",
+            Self::Min => "MIN",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::ReplDisplay;
+    use crate::backend::DatafusionBackend;
+    use arrow::array::{AsArray, Int64Array, RecordBatch, StringArray};
+    use arrow::datatypes::{DataType, Field, Float64Type, Schema};
+
+    async fn running(
+        function: RunningFunction,
+        group_by: Option<&str>,
+    ) -> anyhow::Result<Vec<f64>> {
+        let mut backend = DatafusionBackend::new();
+        let schema = Schema::new(vec![
+            Field::new("shop", DataType::Utf8, false),
+            Field::new("day", DataType::Int64, false),
+            Field::new("sales", DataType::Int64, false),
+        ]);
+        let batch = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![
+                Arc::new(StringArray::from(vec!["a", "b", "a", "b", "a"])),
+                Arc::new(Int64Array::from(vec![5, 4, 3, 2, 1])),
+                Arc::new(Int64Array::from(vec![50, 40, 30, 20, 10])),
+            ],
+        )?;
+        backend.register_batch("sales", batch)?;
+
+        let opts = RunningTotalOpts::new(
+            "sales".to_string(),
+            "sales".to_string(),
+            "day".to_string(),
+            function,
+            "totals".to_string(),
+        )
+        .with_group_by(group_by.map(|group| group.to_string()));
+        let output = opts.execute(&mut backend).await?;
+        assert_eq!(
+            output,
+            format!(
+                "Materialized running {} of sales as dataset totals",
+                function.as_str()
+            )
+        );
+
+        let order = match group_by {
+            Some(group) => format!("{}, day", group),
+            None => "day".to_string(),
+        };
+        let sql = format!(
+            "SELECT running_{} FROM totals ORDER BY {}",
+            function.as_str(),
+            order
+        );
+        let batches = backend.sql(&sql).await?.into_batches().await?;
+        Ok(batches
+            .iter()
+            .flat_map(|batch| {
+                batch
+                    .column(0)
+                    .as_primitive::<Float64Type>()
+                    .values()
+                    .to_vec()
+            })
+            .collect())
+    }
+
+    #[tokio::test]
+    async fn test_running_total() -> anyhow::Result<()> {
+        assert_eq!(
+            running(RunningFunction::Sum, None).await?,
+            [10.0, 30.0, 60.0, 100.0, 150.0]
+        );
+        assert_eq!(
+            running(RunningFunction::Count, None).await?,
+            [1.0, 2.0, 3.0, 4.0, 5.0]
+        );
+        assert_eq!(
+            running(RunningFunction::Mean, None).await?,
+            [10.0, 15.0, 20.0, 25.0, 30.0]
+        );
+        assert_eq!(
+            running(RunningFunction::Max, None).await?,
+            [10.0, 20.0, 30.0, 40.0, 50.0]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_running_total_grouped() -> anyhow::Result<()> {
+        // ordered by shop, then day: a(1, 3, 5) and b(2, 4)
+        assert_eq!(
+            running(RunningFunction::Sum, Some("shop")).await?,
+            [10.0, 40.0, 90.0, 20.0, 60.0]
+        );
+        assert_eq!(
+            running(RunningFunction::Min, Some("shop")).await?,
+            [10.0, 10.0, 10.0, 20.0, 20.0]
+        );
+        Ok(())
+    }
+}
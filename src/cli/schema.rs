@@ -2,31 +2,88 @@ use crate::{Backend, CmdExecutor, ReplContext, ReplDisplay, ReplMsg};
 use clap::{ArgMatches, Parser};
 use reedline_repl_rs::Result;
 
+use super::ensure_table;
+
 #[derive(Debug, Parser)]
 pub struct SchemaOpts {
-    #[arg(short, long, help = "the name of the dataset")]
-    name: String,
+    #[arg(
+        short,
+        long,
+        required_unless_present = "all",
+        help = "the name of the dataset"
+    )]
+    name: Option<String>,
+    #[arg(
+        short,
+        long,
+        conflicts_with = "name",
+        help = "show the schemas of all datasets"
+    )]
+    all: bool,
 }
 
 pub fn schema(args: ArgMatches, context: &mut ReplContext) -> Result<Option<String>> {
-    let name = args
-        .get_one::<String>("name")
-        .expect("expect name")
-        .to_string();
-    let (msg, rx) = ReplMsg::new(SchemaOpts::new(name));
+    let name = args.get_one::<String>("name").map(|s| s.to_string());
+    let all = args.get_flag("all");
+    let (msg, rx) = ReplMsg::new(SchemaOpts::new(name, all));
 
     Ok(context.send(msg, rx))
 }
 
 impl CmdExecutor for SchemaOpts {
     async fn execute<T: Backend>(&self, backend: &mut T) -> anyhow::Result<String> {
-        let df = backend.schema(&self.name).await?;
-        df.display().await
+        let names = match &self.name {
+            Some(name) if !self.all => vec![name.clone()],
+            _ => backend.table_names().await?,
+        };
+
+        let mut sections = Vec::with_capacity(names.len());
+        for name in names {
+            ensure_table(backend, &name).await?;
+            let df = backend.schema(&name).await?;
+            let table = df.display(backend.settings()).await?;
+            if self.all {
+                sections.push(format!("{}:\n{}", name, table));
+            } else {
+                sections.push(table);
+            }
+        }
+        Ok(sections.join("\n\n"))
     }
 }
 
 impl SchemaOpts {
-    pub fn new(name: String) -> Self {
-        Self { name }
+    pub fn new(name: Option<String>, all: bool) -> Self {
+        Self { name, all }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::backend::tests::test_backend;
+    use arrow::array::{RecordBatch, StringArray};
+    use arrow::datatypes::{DataType, Field, Schema};
+
+    #[tokio::test]
+    async fn test_schema_all() -> anyhow::Result<()> {
+        let mut backend = test_backend();
+        let schema = Schema::new(vec![Field::new("city", DataType::Utf8, false)]);
+        let batch = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![Arc::new(StringArray::from(vec!["Paris"]))],
+        )?;
+        backend.register_batch("cities", batch)?;
+
+        let output = SchemaOpts::new(None, true).execute(&mut backend).await?;
+        let sections = output.split("\n\n").collect::<Vec<_>>();
+        assert_eq!(sections.len(), 2);
+        assert!(sections[0].starts_with("cities:\n"));
+        assert!(sections[0].contains("city"));
+        assert!(sections[1].starts_with("t:\n"));
+        assert!(sections[1].contains("id"));
+        Ok(())
     }
 }
@@ -0,0 +1,38 @@
+use crate::{Backend, CmdExecutor, ReplContext, ReplMsg};
+use clap::{ArgMatches, Parser};
+use reedline_repl_rs::Result;
+
+#[derive(Debug, Parser)]
+pub struct SetOpts {
+    #[arg(help = "the name of the setting, e.g. default_head_size")]
+    key: String,
+    #[arg(help = "the value of the setting")]
+    value: String,
+}
+
+pub fn set(args: ArgMatches, context: &mut ReplContext) -> Result<Option<String>> {
+    let key = args
+        .get_one::<String>("key")
+        .expect("expect key")
+        .to_string();
+    let value = args
+        .get_one::<String>("value")
+        .expect("expect value")
+        .to_string();
+    let (msg, rx) = ReplMsg::new(SetOpts::new(key, value));
+
+    Ok(context.send(msg, rx))
+}
+
+impl CmdExecutor for SetOpts {
+    async fn execute<T: Backend>(&self, backend: &mut T) -> anyhow::Result<String> {
+        backend.settings_mut().set(&self.key, &self.value)?;
+        Ok(format!("Set {} to {}", self.key, self.value))
+    }
+}
+
+impl SetOpts {
+    pub fn new(key: String, value: String) -> Self {
+        Self { key, value }
+    }
+}
@@ -1,10 +1,17 @@
-use crate::{Backend, CmdExecutor, ReplContext, ReplDisplay, ReplMsg};
+use crate::{Backend, CmdExecutor, DisplayFormat, ReplContext, ReplMsg};
 use clap::{ArgMatches, Parser};
 use reedline_repl_rs::Result;
+
+use super::output_results;
+
 #[derive(Debug, Parser)]
 pub struct SqlOpts {
     #[arg(short, long, help = "the sql to run")]
     query: String,
+    #[arg(short, long, value_enum, default_value_t, help = "the output format")]
+    format: DisplayFormat,
+    #[arg(long, help = "write the results to this file instead of showing them")]
+    output: Option<String>,
 }
 
 pub fn sql(args: ArgMatches, context: &mut ReplContext) -> Result<Option<String>> {
@@ -12,7 +19,13 @@ pub fn sql(args: ArgMatches, context: &mut ReplContext) -> Result<Option<String>
         .get_one::<String>("query")
         .expect("expect query")
         .to_string();
-    let (msg, rx) = ReplMsg::new(SqlOpts::new(sql));
+    let format = args
+        .get_one::<DisplayFormat>("format")
+        .copied()
+        .unwrap_or_default();
+    let output = args.get_one::<String>("output").cloned();
+    let opts = SqlOpts::new(sql).with_format(format).with_output(output);
+    let (msg, rx) = ReplMsg::new(opts);
 
     Ok(context.send(msg, rx))
 }
@@ -20,12 +33,26 @@ pub fn sql(args: ArgMatches, context: &mut ReplContext) -> Result<Option<String>
 impl CmdExecutor for SqlOpts {
     async fn execute<T: Backend>(&self, backend: &mut T) -> anyhow::Result<String> {
         let df = backend.sql(&self.query).await?;
-        df.display().await
+        output_results(df, self.format, self.output.as_deref(), backend.settings()).await
     }
 }
 
 impl SqlOpts {
     pub fn new(sql: String) -> Self {
-        Self { query: sql }
+        Self {
+            query: sql,
+            format: DisplayFormat::default(),
+            output: None,
+        }
+    }
+
+    pub fn with_format(mut self, format: DisplayFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    pub fn with_output(mut self, output: Option<String>) -> Self {
+        self.output = output;
+        self
     }
 }
@@ -0,0 +1,237 @@
+use std::{path::Path, sync::mpsc, thread, time::Duration};
+
+use clap::{ArgMatches, Parser};
+use crossbeam::channel::Sender;
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use reedline_repl_rs::Result;
+
+use crate::{Backend, CmdExecutor, ReplContext, ReplMsg};
+
+use super::ensure_table;
+
+#[derive(Debug, Clone, Parser)]
+pub struct IngestStreamingOpts {
+    #[arg(short, long, help = "the name of the dataset to append to")]
+    name: String,
+    #[arg(short, long, help = "the csv file another process writes to")]
+    file: String,
+    #[arg(
+        short,
+        long,
+        default_value_t = 1000,
+        help = "how long to wait after a change for further writes before reading them"
+    )]
+    debounce_ms: u64,
+}
+
+pub fn stream(args: ArgMatches, context: &mut ReplContext) -> Result<Option<String>> {
+    let name = args
+        .get_one::<String>("name")
+        .expect("expect name")
+        .to_string();
+    let file = args
+        .get_one::<String>("file")
+        .expect("expect file")
+        .to_string();
+    let debounce_ms = args.get_one::<u64>("debounce_ms").copied().unwrap_or(1000);
+    let opts = IngestStreamingOpts::new(name, file, debounce_ms);
+    match context.stream(opts) {
+        Ok(output) => Ok(Some(output)),
+        Err(e) => {
+            eprintln!("Failed to process command: {}", e);
+            Ok(None)
+        }
+    }
+}
+
+impl CmdExecutor for IngestStreamingOpts {
+    /// Append the rows written to the file since the last read.
+    async fn execute<T: Backend>(&self, backend: &mut T) -> anyhow::Result<String> {
+        ensure_table(backend, &self.name).await?;
+        let appended = backend.ingest_streaming(&self.name, &self.file).await?;
+        Ok(format!(
+            "Appended {} row(s) to dataset {}",
+            appended, self.name
+        ))
+    }
+}
+
+impl IngestStreamingOpts {
+    pub fn new(name: String, file: String, debounce_ms: u64) -> Self {
+        Self {
+            name,
+            file,
+            debounce_ms,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// Follows a file in the background, queueing an ingest with the backend
+/// whenever it changes. Each ingest only reads the rows written since the
+/// last one, so the backend keeps serving other commands in between. The
+/// tail stops when it is dropped or an ingest fails, e.g. as the dataset
+/// was dropped.
+pub(crate) struct FileTail {
+    _watcher: RecommendedWatcher,
+}
+
+impl FileTail {
+    pub(crate) fn spawn(
+        opts: IngestStreamingOpts,
+        sender: Sender<ReplMsg>,
+    ) -> anyhow::Result<Self> {
+        let (tx, rx) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(tx)?;
+        watcher.watch(Path::new(&opts.file), RecursiveMode::NonRecursive)?;
+        let debounce = Duration::from_millis(opts.debounce_ms);
+        thread::Builder::new()
+            .name(format!("stream-{}", opts.name))
+            .spawn(move || {
+                // the watcher is dropped with the tail, which ends the events
+                while rx.recv().is_ok() {
+                    // read a burst of writes in one go
+                    thread::sleep(debounce);
+                    rx.try_iter().for_each(drop);
+                    let (msg, reply) = ReplMsg::new(opts.clone());
+                    if sender.send(msg).is_err() {
+                        break;
+                    }
+                    if let Ok(Err(e)) = reply.recv() {
+                        eprintln!("Stopped streaming {} into {}: {}", opts.file, opts.name, e);
+                        break;
+                    }
+                }
+            })?;
+        Ok(Self { _watcher: watcher })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, io::Write, sync::Arc};
+
+    use super::*;
+    use crate::backend::DatafusionBackend;
+    use crate::backend::tests::{row_cells, temp_dir};
+    use crate::cli::connect::parse_dataset_conn;
+    use crate::{Client, ConnectOpts};
+    use arrow::array::{Int64Array, RecordBatch, StringArray};
+    use arrow::datatypes::{DataType, Field, Schema};
+
+    async fn count(backend: &DatafusionBackend) -> anyhow::Result<usize> {
+        Ok(backend.table("events").await?.count().await?)
+    }
+
+    #[tokio::test]
+    async fn test_stream_appends_new_rows() -> anyhow::Result<()> {
+        let mut backend = DatafusionBackend::new();
+        let schema = Schema::new(vec![
+            Field::new("id", DataType::Int64, true),
+            Field::new("name", DataType::Utf8, true),
+        ]);
+        let batch = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![
+                Arc::new(Int64Array::from(vec![100, 101])),
+                Arc::new(StringArray::from(vec!["x", "y"])),
+            ],
+        )?;
+        backend.register_batch("events", batch)?;
+
+        let dir = temp_dir()?;
+        let path = dir.path().join("stream.csv");
+        fs::write(&path, "id,name\n1,a\n2,b\n")?;
+        let opts =
+            IngestStreamingOpts::new("events".to_string(), path.to_string_lossy().to_string(), 0);
+
+        let output = opts.execute(&mut backend).await?;
+        assert_eq!(output, "Appended 2 row(s) to dataset events");
+        assert_eq!(count(&backend).await?, 4);
+
+        // the partially written last line waits for the next read
+        let mut file = fs::OpenOptions::new().append(true).open(&path)?;
+        file.write_all(b"3,c\n4,d\n5,e\n6,")?;
+        opts.execute(&mut backend).await?;
+        assert_eq!(count(&backend).await?, 7);
+
+        file.write_all(b"f\n")?;
+        let output = opts.execute(&mut backend).await?;
+        assert_eq!(output, "Appended 1 row(s) to dataset events");
+        assert_eq!(count(&backend).await?, 8);
+
+        // another file is read from its own start
+        let other = dir.path().join("other.csv");
+        fs::write(&other, "id,name\n7,g\n")?;
+        let opts =
+            IngestStreamingOpts::new("events".to_string(), other.to_string_lossy().to_string(), 0);
+        opts.execute(&mut backend).await?;
+        assert_eq!(count(&backend).await?, 9);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_stream_skips_connected_rows() -> anyhow::Result<()> {
+        let dir = temp_dir()?;
+        let path = dir.path().join("events.csv");
+        fs::write(&path, "id,name\n1,a\n2,b\n")?;
+        let filename = path.to_string_lossy().to_string();
+        let conn = parse_dataset_conn(&filename).map_err(anyhow::Error::msg)?;
+        let mut backend = DatafusionBackend::new();
+        backend
+            .connect(&ConnectOpts::new(conn, None, "events".to_string()))
+            .await?;
+
+        let opts = IngestStreamingOpts::new("events".to_string(), filename, 0);
+        let output = opts.execute(&mut backend).await?;
+        assert_eq!(output, "Appended 0 row(s) to dataset events");
+        assert_eq!(count(&backend).await?, 2);
+
+        let mut file = fs::OpenOptions::new().append(true).open(&path)?;
+        file.write_all(b"3,c\n")?;
+        let output = opts.execute(&mut backend).await?;
+        assert_eq!(output, "Appended 1 row(s) to dataset events");
+        assert_eq!(count(&backend).await?, 3);
+        Ok(())
+    }
+
+    /// Wait for the dataset to hold `rows` rows, while the client keeps
+    /// answering queries.
+    fn wait_for_rows(client: &Client, rows: usize) -> anyhow::Result<()> {
+        let expected = rows.to_string();
+        for _ in 0..500 {
+            let output = client.sql("SELECT COUNT(*) AS n FROM events")?;
+            if row_cells(&output, &expected) == [expected.as_str()] {
+                return Ok(());
+            }
+            thread::sleep(Duration::from_millis(20));
+        }
+        anyhow::bail!("Dataset events never reached {} rows", rows)
+    }
+
+    #[test]
+    fn test_stream_follows_file_in_background() -> anyhow::Result<()> {
+        let dir = temp_dir()?;
+        let path = dir.path().join("follow.csv");
+        fs::write(&path, "id,name\n1,a\n")?;
+        let filename = path.to_string_lossy().to_string();
+        let conn = parse_dataset_conn(&filename).map_err(anyhow::Error::msg)?;
+        let client = Client::new();
+        client.connect(ConnectOpts::new(conn, None, "events".to_string()))?;
+
+        let output = client.stream(IngestStreamingOpts::new("events".to_string(), filename, 10))?;
+        assert_eq!(output, "Appended 0 row(s) to dataset events");
+
+        // the writer pausing between writes does not end the stream
+        let mut file = fs::OpenOptions::new().append(true).open(&path)?;
+        file.write_all(b"2,b\n")?;
+        wait_for_rows(&client, 2)?;
+        thread::sleep(Duration::from_millis(100));
+        file.write_all(b"3,c\n4,d\n")?;
+        wait_for_rows(&client, 4)?;
+        Ok(())
+    }
+}
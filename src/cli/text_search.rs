@@ -0,0 +1,159 @@
+use clap::{ArgMatches, Parser};
+use reedline_repl_rs::Result;
+
+use crate::{Backend, CmdExecutor, ReplContext, ReplDisplay, ReplMsg};
+
+use super::ensure_table;
+
+#[derive(Debug, Parser)]
+pub struct TextSearchOpts {
+    #[arg(short, long, help = "the name of the dataset")]
+    name: String,
+    #[arg(short, long, help = "the text to search for")]
+    query: String,
+    #[arg(
+        short,
+        long,
+        value_delimiter = ',',
+        help = "the columns to search, all string columns by default"
+    )]
+    columns: Vec<String>,
+    #[arg(short = 's', long, help = "match the case of the query")]
+    case_sensitive: bool,
+    #[arg(short, long, default_value_t = 100, help = "the maximum rows to show")]
+    limit: usize,
+}
+
+pub fn text_search(args: ArgMatches, context: &mut ReplContext) -> Result<Option<String>> {
+    let name = args
+        .get_one::<String>("name")
+        .expect("expect name")
+        .to_string();
+    let query = args
+        .get_one::<String>("query")
+        .expect("expect query")
+        .to_string();
+    let columns = args
+        .get_many::<String>("columns")
+        .map(|columns| columns.cloned().collect())
+        .unwrap_or_default();
+    let case_sensitive = args.get_flag("case_sensitive");
+    let limit = args.get_one::<usize>("limit").copied().unwrap_or(100);
+    let opts = TextSearchOpts::new(name, query, limit)
+        .with_columns(columns)
+        .with_case_sensitive(case_sensitive);
+    let (msg, rx) = ReplMsg::new(opts);
+
+    Ok(context.send(msg, rx))
+}
+
+impl CmdExecutor for TextSearchOpts {
+    async fn execute<T: Backend>(&self, backend: &mut T) -> anyhow::Result<String> {
+        ensure_table(backend, &self.name).await?;
+        let columns = if self.columns.is_empty() {
+            backend.string_columns(&self.name).await?
+        } else {
+            let names = backend.column_names(&self.name).await?;
+            if let Some(column) = self.columns.iter().find(|c| !names.contains(c)) {
+                anyhow::bail!("Dataset {} has no column(s): {}", self.name, column);
+            }
+            self.columns.clone()
+        };
+        if columns.is_empty() {
+            anyhow::bail!("Dataset {} has no string columns to search", self.name);
+        }
+        let rows = backend
+            .text_search(
+                &self.name,
+                &columns,
+                &self.query,
+                self.case_sensitive,
+                self.limit,
+            )
+            .await?;
+        rows.display(backend.settings()).await
+    }
+}
+
+impl TextSearchOpts {
+    pub fn new(name: String, query: String, limit: usize) -> Self {
+        Self {
+            name,
+            query,
+            columns: vec![],
+            case_sensitive: false,
+            limit,
+        }
+    }
+
+    pub fn with_columns(mut self, columns: Vec<String>) -> Self {
+        self.columns = columns;
+        self
+    }
+
+    pub fn with_case_sensitive(mut self, case_sensitive: bool) -> Self {
+        self.case_sensitive = case_sensitive;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::backend::DatafusionBackend;
+    use crate::backend::tests::{backend_with, row_count};
+    use arrow::array::StringArray;
+
+    fn people_backend() -> DatafusionBackend {
+        backend_with(
+            "people",
+            vec![
+                (
+                    "name",
+                    Arc::new(StringArray::from(vec!["Alice", "bob", "ALICIA", "carol"])),
+                ),
+                (
+                    "city",
+                    Arc::new(StringArray::from(vec![
+                        Some("Paris"),
+                        Some("Alicante"),
+                        None,
+                        Some("50%_off"),
+                    ])),
+                ),
+            ],
+        )
+    }
+
+    async fn search(opts: TextSearchOpts) -> anyhow::Result<usize> {
+        let mut backend = people_backend();
+        let output = opts.execute(&mut backend).await?;
+        Ok(row_count(&output))
+    }
+
+    #[tokio::test]
+    async fn test_text_search() -> anyhow::Result<()> {
+        let opts = |query: &str| TextSearchOpts::new("people".to_string(), query.to_string(), 100);
+        // Alice, bob (Alicante) and ALICIA
+        assert_eq!(search(opts("ali")).await?, 3);
+        assert_eq!(search(opts("ALI").with_case_sensitive(true)).await?, 1);
+        let columns = vec!["name".to_string()];
+        assert_eq!(search(opts("ali").with_columns(columns)).await?, 2);
+        // wildcards in the query match literally
+        assert_eq!(search(opts("%_")).await?, 1);
+        assert_eq!(search(opts("o_f")).await?, 0);
+        let limited = TextSearchOpts::new("people".to_string(), "ali".to_string(), 1);
+        assert_eq!(search(limited).await?, 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_text_search_unknown_column() {
+        let opts = TextSearchOpts::new("people".to_string(), "a".to_string(), 100)
+            .with_columns(vec!["missing".to_string()]);
+        let err = search(opts).await.unwrap_err();
+        assert_eq!(err.to_string(), "Dataset people has no column(s): missing");
+    }
+}
@@ -0,0 +1,234 @@
+use clap::{ArgMatches, Parser, ValueEnum};
+use reedline_repl_rs::Result;
+
+use crate::{Backend, CmdExecutor, ReplContext, ReplDisplay, ReplMsg};
+
+use super::ensure_table;
+
+/// The unit the gaps between consecutive rows are measured in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum GapUnit {
+    #[default]
+    Seconds,
+    Minutes,
+    Hours,
+    Days,
+}
+
+#[derive(Debug, Parser)]
+pub struct TimeBetweenOpts {
+    #[arg(short, long, help = "the name of the dataset")]
+    name: String,
+    #[arg(short, long, help = "the timestamp or date column")]
+    time_col: String,
+    #[arg(
+        short,
+        long,
+        value_enum,
+        default_value_t,
+        help = "seconds, minutes, hours or days"
+    )]
+    unit: GapUnit,
+    #[arg(
+        short,
+        long,
+        help = "measure the gaps within each group of this column"
+    )]
+    group_by: Option<String>,
+    #[arg(short, long, help = "the name of the new dataset")]
+    output: String,
+}
+
+pub fn time_between(args: ArgMatches, context: &mut ReplContext) -> Result<Option<String>> {
+    let name = args
+        .get_one::<String>("name")
+        .expect("expect name")
+        .to_string();
+    let time_col = args
+        .get_one::<String>("time_col")
+        .expect("expect time_col")
+        .to_string();
+    let unit = args.get_one::<GapUnit>("unit").copied().unwrap_or_default();
+    let group_by = args.get_one::<String>("group_by").cloned();
+    let output = args
+        .get_one::<String>("output")
+        .expect("expect output")
+        .to_string();
+    let opts = TimeBetweenOpts::new(name, time_col, unit, output).with_group_by(group_by);
+    let (msg, rx) = ReplMsg::new(opts);
+
+    Ok(context.send(msg, rx))
+}
+
+impl CmdExecutor for TimeBetweenOpts {
+    async fn execute<T: Backend>(&self, backend: &mut T) -> anyhow::Result<String> {
+        ensure_table(backend, &self.name).await?;
+        backend
+            .create_table_from_sql(&self.output, &self.sql())
+            .await?;
+        let sql = self.summary_sql();
+        let summary = backend.sql(&sql).await?;
+        Ok(format!(
+            "Materialized gaps in {} as dataset {}\n{}",
+            self.unit.as_str(),
+            self.output,
+            summary.display(backend.settings()).await?
+        ))
+    }
+}
+
+impl TimeBetweenOpts {
+    pub fn new(name: String, time_col: String, unit: GapUnit, output: String) -> Self {
+        Self {
+            name,
+            time_col,
+            unit,
+            group_by: None,
+            output,
+        }
+    }
+
+    pub fn with_group_by(mut self, group_by: Option<String>) -> Self {
+        self.group_by = group_by;
+        self
+    }
+
+    /// The gaps are computed on epoch seconds, which both backends support,
+    /// rather than on intervals whose arithmetic differs between them.
+    fn sql(&self) -> String {
+        let epoch = format!(
+            "CAST(date_part('epoch', CAST(\"{}\" AS TIMESTAMP)) AS DOUBLE)",
+            self.time_col
+        );
+        let partition = self
+            .group_by
+            .as_ref()
+            .map(|group| format!("PARTITION BY \"{}\" ", group))
+            .unwrap_or_default();
+        let order = match &self.group_by {
+            Some(group) => format!("\"{}\", \"{}\"", group, self.time_col),
+            None => format!("\"{}\"", self.time_col),
+        };
+        format!(
+            "SELECT *, ({0} - LAG({0}) OVER ({1}ORDER BY \"{2}\")) / {3} AS gap \
+            FROM {4} ORDER BY {5}",
+            epoch,
+            partition,
+            self.time_col,
+            self.unit.seconds(),
+            self.name,
+            order
+        )
+    }
+
+    fn summary_sql(&self) -> String {
+        let stats = "MIN(gap) AS min_gap, MAX(gap) AS max_gap, AVG(gap) AS avg_gap";
+        match &self.group_by {
+            Some(group) => format!(
+                "SELECT \"{0}\", {1} FROM {2} GROUP BY \"{0}\" ORDER BY \"{0}\"",
+                group, stats, self.output
+            ),
+            None => format!("SELECT {} FROM {}", stats, self.output),
+        }
+    }
+}
+
+impl GapUnit {
+    fn as_str(&self) -> &'static str {
+        match self {
+            GapUnit::Seconds => "seconds",
+            GapUnit::Minutes => "minutes",
+            GapUnit::Hours => "hours",
+            GapUnit::Days => "days",
+        }
+    }
+
+    fn seconds(&self) -> f64 {
+        match self {
+            GapUnit::Seconds => 1.0,
+            GapUnit::Minutes => 60.0,
+            GapUnit::Hours => 3600.0,
+            GapUnit::Days => 86400.0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::backend::DatafusionBackend;
+    use crate::backend::tests::{backend_with, row_cells, row_count};
+    use arrow::array::StringArray;
+
+    fn readings_backend() -> DatafusionBackend {
+        backend_with(
+            "readings",
+            vec![
+                (
+                    "sensor",
+                    Arc::new(StringArray::from(vec!["a", "b", "a", "a", "b", "a"])),
+                ),
+                (
+                    "read_at",
+                    Arc::new(StringArray::from(vec![
+                        "2024-01-01T03:00:00",
+                        "2024-01-01T00:30:00",
+                        "2024-01-01T00:00:00",
+                        "2024-01-01T01:00:00",
+                        "2024-01-01T06:30:00",
+                        "2024-01-01T04:30:00",
+                    ])),
+                ),
+            ],
+        )
+    }
+
+    #[tokio::test]
+    async fn test_time_between() -> anyhow::Result<()> {
+        let mut backend = readings_backend();
+        let opts = TimeBetweenOpts::new(
+            "readings".to_string(),
+            "read_at".to_string(),
+            GapUnit::Hours,
+            "gaps".to_string(),
+        );
+        let output = opts.execute(&mut backend).await?;
+        assert!(output.starts_with("Materialized gaps in hours as dataset gaps"));
+        assert_eq!(row_cells(&output, "0.5"), ["0.5", "2.0", "1.3"]);
+
+        let sql = "SELECT read_at, gap FROM gaps";
+        let gaps = backend.sql(sql).await?.display(backend.settings()).await?;
+        assert_eq!(row_count(&gaps), 6);
+        assert_eq!(
+            row_cells(&gaps, "2024-01-01T00:00:00"),
+            ["2024-01-01T00:00:00", "NULL"]
+        );
+        assert_eq!(
+            row_cells(&gaps, "2024-01-01T00:30:00"),
+            ["2024-01-01T00:30:00", "0.5"]
+        );
+        assert_eq!(
+            row_cells(&gaps, "2024-01-01T06:30:00"),
+            ["2024-01-01T06:30:00", "2.0"]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_time_between_grouped() -> anyhow::Result<()> {
+        let mut backend = readings_backend();
+        let opts = TimeBetweenOpts::new(
+            "readings".to_string(),
+            "read_at".to_string(),
+            GapUnit::Minutes,
+            "gaps".to_string(),
+        )
+        .with_group_by(Some("sensor".to_string()));
+        let output = opts.execute(&mut backend).await?;
+        assert_eq!(row_cells(&output, "a"), ["a", "60.0", "120.0", "90.0"]);
+        assert_eq!(row_cells(&output, "b"), ["b", "360.0", "360.0", "360.0"]);
+        Ok(())
+    }
+}
@@ -0,0 +1,99 @@
+use crate::{Backend, CmdExecutor, ReplContext, ReplDisplay, ReplMsg};
+use clap::{ArgMatches, Parser};
+use reedline_repl_rs::Result;
+
+use super::ensure_table;
+
+#[derive(Debug, Parser)]
+pub struct TransposeOpts {
+    #[arg(short, long, help = "the name of the dataset")]
+    name: String,
+    #[arg(
+        short,
+        long,
+        default_value_t = 0,
+        help = "the index of the row (0-based)"
+    )]
+    row: usize,
+}
+
+pub fn transpose(args: ArgMatches, context: &mut ReplContext) -> Result<Option<String>> {
+    let name = args
+        .get_one::<String>("name")
+        .expect("expect name")
+        .to_string();
+    let row = args.get_one::<usize>("row").copied().unwrap_or_default();
+    let (msg, rx) = ReplMsg::new(TransposeOpts::new(name, row));
+
+    Ok(context.send(msg, rx))
+}
+
+impl CmdExecutor for TransposeOpts {
+    async fn execute<T: Backend>(&self, backend: &mut T) -> anyhow::Result<String> {
+        ensure_table(backend, &self.name).await?;
+        match backend.transpose(&self.name, self.row).await? {
+            Some(batch) => batch.display(backend.settings()).await,
+            None => Ok(format!(
+                "Warning: row {} is out of range for dataset {}",
+                self.row, self.name
+            )),
+        }
+    }
+}
+
+impl TransposeOpts {
+    pub fn new(name: String, row: usize) -> Self {
+        Self { name, row }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::backend::DatafusionBackend;
+    use crate::backend::tests::{row_cells, row_count};
+    use arrow::array::{BooleanArray, Float64Array, Int64Array, RecordBatch, StringArray};
+    use arrow::datatypes::{DataType, Field, Schema};
+
+    #[tokio::test]
+    async fn test_transpose_wide_row() -> anyhow::Result<()> {
+        let mut backend = DatafusionBackend::new();
+        let schema = Schema::new(vec![
+            Field::new("a", DataType::Int64, false),
+            Field::new("b", DataType::Float64, false),
+            Field::new("c", DataType::Utf8, false),
+            Field::new("d", DataType::Boolean, false),
+            Field::new("e", DataType::Utf8, true),
+        ]);
+        let batch = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![
+                Arc::new(Int64Array::from(vec![1])),
+                Arc::new(Float64Array::from(vec![2.5])),
+                Arc::new(StringArray::from(vec!["x"])),
+                Arc::new(BooleanArray::from(vec![true])),
+                Arc::new(StringArray::from(vec![None::<&str>])),
+            ],
+        )?;
+        backend.register_batch("wide", batch)?;
+
+        let output = TransposeOpts::new("wide".to_string(), 0)
+            .execute(&mut backend)
+            .await?;
+        assert_eq!(row_count(&output), 5);
+        assert_eq!(row_cells(&output, "field"), ["field", "value"]);
+        assert_eq!(row_cells(&output, "a"), ["a", "1"]);
+        assert_eq!(row_cells(&output, "b"), ["b", "2.5"]);
+        assert_eq!(row_cells(&output, "c"), ["c", "x"]);
+        assert_eq!(row_cells(&output, "d"), ["d", "true"]);
+        assert_eq!(row_cells(&output, "e"), ["e", "NULL"]);
+
+        let output = TransposeOpts::new("wide".to_string(), 1)
+            .execute(&mut backend)
+            .await?;
+        assert_eq!(output, "Warning: row 1 is out of range for dataset wide");
+        Ok(())
+    }
+}
@@ -0,0 +1,82 @@
+use crate::{Backend, CmdExecutor, ReplContext, ReplDisplay, ReplMsg};
+use clap::{ArgMatches, Parser};
+use reedline_repl_rs::Result;
+
+use super::ensure_table;
+
+#[derive(Debug, Parser)]
+pub struct TypeSummaryOpts {
+    #[arg(short, long, help = "the name of the dataset")]
+    name: String,
+}
+
+pub fn type_summary(args: ArgMatches, context: &mut ReplContext) -> Result<Option<String>> {
+    let name = args
+        .get_one::<String>("name")
+        .expect("expect name")
+        .to_string();
+    let (msg, rx) = ReplMsg::new(TypeSummaryOpts::new(name));
+
+    Ok(context.send(msg, rx))
+}
+
+impl CmdExecutor for TypeSummaryOpts {
+    async fn execute<T: Backend>(&self, backend: &mut T) -> anyhow::Result<String> {
+        ensure_table(backend, &self.name).await?;
+        let batch = backend.column_types_summary(&self.name).await?;
+        batch.display(backend.settings()).await
+    }
+}
+
+impl TypeSummaryOpts {
+    pub fn new(name: String) -> Self {
+        Self { name }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::backend::DatafusionBackend;
+    use crate::backend::tests::{row_cells, row_count};
+    use arrow::array::{Float64Array, Int64Array, RecordBatch, StringArray};
+    use arrow::datatypes::{DataType, Field, Schema};
+
+    #[tokio::test]
+    async fn test_type_summary() -> anyhow::Result<()> {
+        let mut backend = DatafusionBackend::new();
+        let schema = Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("name", DataType::Utf8, false),
+            Field::new("score", DataType::Float64, true),
+            Field::new("city", DataType::Utf8, true),
+            Field::new("zip", DataType::Utf8, true),
+            Field::new("age", DataType::Int64, true),
+        ]);
+        let batch = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![
+                Arc::new(Int64Array::from(vec![1])),
+                Arc::new(StringArray::from(vec!["ann"])),
+                Arc::new(Float64Array::from(vec![1.5])),
+                Arc::new(StringArray::from(vec!["oslo"])),
+                Arc::new(StringArray::from(vec!["0150"])),
+                Arc::new(Int64Array::from(vec![30])),
+            ],
+        )?;
+        backend.register_batch("people", batch)?;
+
+        let output = TypeSummaryOpts::new("people".to_string())
+            .execute(&mut backend)
+            .await?;
+        assert_eq!(row_count(&output), 3);
+        assert_eq!(row_cells(&output, "Utf8"), ["Utf8", "3"]);
+        assert_eq!(row_cells(&output, "Int64"), ["Int64", "2"]);
+        assert_eq!(row_cells(&output, "Float64"), ["Float64", "1"]);
+        let first = output.lines().filter(|line| line.starts_with('|')).nth(1);
+        assert!(first.unwrap().contains("Utf8"));
+        Ok(())
+    }
+}
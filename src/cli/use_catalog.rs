@@ -0,0 +1,156 @@
+use crate::{Backend, CmdExecutor, ReplContext, ReplMsg};
+use clap::{ArgMatches, Parser};
+use reedline_repl_rs::Result;
+
+#[derive(Debug, Parser)]
+pub struct UseOpts {
+    #[arg(help = "the name of the catalog")]
+    catalog: String,
+}
+
+pub fn use_catalog(args: ArgMatches, context: &mut ReplContext) -> Result<Option<String>> {
+    let catalog = args
+        .get_one::<String>("catalog")
+        .expect("expect catalog")
+        .to_string();
+    let (msg, rx) = ReplMsg::new(UseOpts::new(catalog));
+
+    Ok(context.send(msg, rx))
+}
+
+impl CmdExecutor for UseOpts {
+    async fn execute<T: Backend>(&self, backend: &mut T) -> anyhow::Result<String> {
+        backend.use_catalog(&self.catalog).await?;
+        Ok(format!("Using catalog {}", self.catalog))
+    }
+}
+
+impl UseOpts {
+    pub fn new(catalog: String) -> Self {
+        Self { catalog }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::Path};
+
+    use super::*;
+    use crate::backend::DatafusionBackend;
+    use crate::backend::tests::{row_cells, row_count, temp_dir};
+    use crate::cli::connect::parse_dataset_conn;
+    use crate::{ConnectOpts, DescribeOpts, HeadOpts, ListOpts, VacuumOpts};
+
+    async fn connect_csv(
+        backend: &mut DatafusionBackend,
+        dir: &Path,
+        catalog: &str,
+        content: &str,
+    ) -> anyhow::Result<()> {
+        let path = dir.join(format!("{}.csv", catalog));
+        fs::write(&path, content)?;
+        let conn = parse_dataset_conn(&path.to_string_lossy()).map_err(anyhow::Error::msg)?;
+        let opts = ConnectOpts::new(conn, None, "orders".to_string())
+            .with_catalog(Some(catalog.to_string()));
+        backend.connect(&opts).await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_same_name_in_two_catalogs() -> anyhow::Result<()> {
+        let dir = temp_dir()?;
+        let mut backend = DatafusionBackend::new();
+        connect_csv(&mut backend, dir.path(), "sales", "id\n1\n2\n3\n").await?;
+        connect_csv(&mut backend, dir.path(), "hr", "id\n1\n").await?;
+
+        let head = |name: &str| HeadOpts::new(name.to_string(), None, 0);
+        let output = head("sales.orders").execute(&mut backend).await?;
+        assert_eq!(row_count(&output), 3);
+        let output = head("hr.orders").execute(&mut backend).await?;
+        assert_eq!(row_count(&output), 1);
+        assert!(head("orders").execute(&mut backend).await.is_err());
+
+        let output = UseOpts::new("hr".to_string()).execute(&mut backend).await?;
+        assert_eq!(output, "Using catalog hr");
+        let output = head("orders").execute(&mut backend).await?;
+        assert_eq!(row_count(&output), 1);
+
+        let output = ListOpts::new(false)
+            .with_catalog(Some("sales".to_string()))
+            .execute(&mut backend)
+            .await?;
+        assert_eq!(row_count(&output), 1);
+        assert!(output.contains("orders"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_describe_in_each_catalog() -> anyhow::Result<()> {
+        let mut backend = DatafusionBackend::new();
+        backend
+            .create_table_from_sql("orders", "SELECT * FROM (VALUES (1.0), (2.0)) AS t(v)")
+            .await?;
+        let describe = DescribeOpts::new("orders".to_string());
+        let output = describe.execute(&mut backend).await?;
+        assert_eq!(row_cells(&output, "max"), ["max", "2.0"]);
+
+        UseOpts::new("hr".to_string()).execute(&mut backend).await?;
+        backend
+            .create_table_from_sql("orders", "SELECT * FROM (VALUES (100.0), (200.0)) AS t(v)")
+            .await?;
+        let output = describe.execute(&mut backend).await?;
+        assert_eq!(row_cells(&output, "max"), ["max", "200.0"]);
+
+        UseOpts::new("public".to_string())
+            .execute(&mut backend)
+            .await?;
+        let output = describe.execute(&mut backend).await?;
+        assert_eq!(row_cells(&output, "max"), ["max", "2.0"]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_list_materialized_per_catalog() -> anyhow::Result<()> {
+        let dir = temp_dir()?;
+        let mut backend = DatafusionBackend::new();
+        backend
+            .create_table_from_sql("orders", "SELECT 1 AS id")
+            .await?;
+        connect_csv(&mut backend, dir.path(), "hr", "id\n1\n").await?;
+
+        let list = |catalog: &str| ListOpts::new(false).with_catalog(Some(catalog.to_string()));
+        let output = list("public").execute(&mut backend).await?;
+        assert_eq!(row_cells(&output, "orders"), ["orders", "MATERIALIZED"]);
+        let output = list("hr").execute(&mut backend).await?;
+        assert_eq!(row_cells(&output, "orders"), ["orders", "BASE TABLE"]);
+
+        backend
+            .create_table_from_sql("hr.summary", "SELECT 1 AS id")
+            .await?;
+        let output = list("hr").execute(&mut backend).await?;
+        assert_eq!(row_cells(&output, "summary"), ["summary", "MATERIALIZED"]);
+
+        let output = list("it's").execute(&mut backend).await?;
+        assert_eq!(row_count(&output), 0);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_vacuum_after_use() -> anyhow::Result<()> {
+        let dir = temp_dir()?;
+        let path = dir.path().join("orders.csv");
+        fs::write(&path, "id\n1\n2\n3\n")?;
+        let conn = parse_dataset_conn(&path.to_string_lossy()).map_err(anyhow::Error::msg)?;
+        let mut backend = DatafusionBackend::new();
+        backend
+            .connect(&ConnectOpts::new(conn, None, "orders".to_string()))
+            .await?;
+
+        UseOpts::new("hr".to_string()).execute(&mut backend).await?;
+        VacuumOpts::new().execute(&mut backend).await?;
+        assert!(!backend.table_exists("orders").await?);
+        assert!(backend.table_exists("public.orders").await?);
+        assert!(backend.connection_info("public.orders").await.is_ok());
+        Ok(())
+    }
+}
@@ -0,0 +1,106 @@
+use crate::{Backend, CmdExecutor, ReplContext, ReplMsg};
+use clap::{ArgMatches, Parser};
+use reedline_repl_rs::Result;
+
+#[derive(Debug, Parser)]
+pub struct VacuumOpts {}
+
+pub fn vacuum(_args: ArgMatches, context: &mut ReplContext) -> Result<Option<String>> {
+    let (msg, rx) = ReplMsg::new(VacuumOpts::new());
+
+    Ok(context.send(msg, rx))
+}
+
+impl CmdExecutor for VacuumOpts {
+    async fn execute<T: Backend>(&self, backend: &mut T) -> anyhow::Result<String> {
+        let freed = backend.vacuum().await?;
+        Ok(format!("Vacuum freed {} bytes", freed))
+    }
+}
+
+impl VacuumOpts {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Default for VacuumOpts {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::backend::DatafusionBackend;
+    use crate::backend::tests::temp_dir;
+    use crate::cli::connect::parse_dataset_conn;
+    use crate::{ConnectOpts, DescribeOpts, ReplDisplay};
+
+    #[tokio::test]
+    async fn test_vacuum_keeps_datasets() -> anyhow::Result<()> {
+        let dir = temp_dir()?;
+        let path = dir.path().join("vacuum.csv");
+        fs::write(&path, "id,name\n1,a\n2,b\n3,c\n")?;
+        let conn = parse_dataset_conn(&path.to_string_lossy()).map_err(anyhow::Error::msg)?;
+
+        let mut backend = DatafusionBackend::new();
+        backend
+            .connect(&ConnectOpts::new(conn, None, "users".to_string()))
+            .await?;
+        backend
+            .create_table_from_sql("small", "SELECT * FROM users WHERE id < 3")
+            .await?;
+        let opts = DescribeOpts::new("users".to_string());
+        let describe = backend.describe(&opts).await?;
+        describe.display(backend.settings()).await?;
+        let before = backend.table_names().await?;
+
+        let freed = backend.vacuum().await?;
+        assert!(freed > 0);
+        assert_eq!(backend.table_names().await?, before);
+        assert_eq!(backend.table("small").await?.count().await?, 2);
+
+        let output = VacuumOpts::new().execute(&mut backend).await?;
+        assert_eq!(output, "Vacuum freed 0 bytes");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_vacuum_keeps_untracked_tables() -> anyhow::Result<()> {
+        let mut backend = DatafusionBackend::new();
+        backend
+            .sql("CREATE TABLE extra AS VALUES (1), (2)")
+            .await?
+            .display(backend.settings())
+            .await?;
+        backend
+            .sql("CREATE SCHEMA other")
+            .await?
+            .display(backend.settings())
+            .await?;
+        backend
+            .sql("CREATE TABLE other.nested AS VALUES ('a')")
+            .await?
+            .display(backend.settings())
+            .await?;
+        let before = backend.table_names().await?;
+
+        backend.vacuum().await?;
+        assert_eq!(backend.table_names().await?, before);
+        assert_eq!(backend.table("extra").await?.count().await?, 2);
+        assert_eq!(backend.table("other.nested").await?.count().await?, 1);
+
+        // the vacuumed session still has the functions taotie registers
+        let output = backend
+            .sql("SELECT jaro_winkler('martha', 'marhta') AS score")
+            .await?
+            .display(backend.settings())
+            .await?;
+        assert!(output.contains("score"));
+        Ok(())
+    }
+}
@@ -0,0 +1,75 @@
+use crate::{Backend, CmdExecutor, ReplContext, ReplMsg};
+use clap::{ArgMatches, Parser};
+use reedline_repl_rs::Result;
+
+#[derive(Debug, Parser)]
+pub struct ValidateSqlOpts {
+    #[arg(short, long, help = "the sql to validate")]
+    query: String,
+    #[arg(short, long, help = "show the optimized logical plan")]
+    plan: bool,
+}
+
+pub fn validate_sql(args: ArgMatches, context: &mut ReplContext) -> Result<Option<String>> {
+    let query = args
+        .get_one::<String>("query")
+        .expect("expect query")
+        .to_string();
+    let plan = args.get_flag("plan");
+    let (msg, rx) = ReplMsg::new(ValidateSqlOpts::new(query, plan));
+
+    Ok(context.send(msg, rx))
+}
+
+impl CmdExecutor for ValidateSqlOpts {
+    async fn execute<T: Backend>(&self, backend: &mut T) -> anyhow::Result<String> {
+        let plan = backend
+            .validate_sql(&self.query)
+            .await
+            .map_err(|e| anyhow::anyhow!("Query is invalid: {}", e))?;
+        if self.plan {
+            Ok(format!("Query is valid\n\n{}", plan))
+        } else {
+            Ok("Query is valid".to_string())
+        }
+    }
+}
+
+impl ValidateSqlOpts {
+    pub fn new(query: String, plan: bool) -> Self {
+        Self { query, plan }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::tests::test_backend;
+
+    #[tokio::test]
+    async fn test_validate_valid_sql() -> anyhow::Result<()> {
+        let mut backend = test_backend();
+        let opts = ValidateSqlOpts::new("SELECT id FROM t WHERE id > 3".to_string(), false);
+        assert_eq!(opts.execute(&mut backend).await?, "Query is valid");
+
+        let opts = ValidateSqlOpts::new("SELECT id FROM t WHERE id > 3".to_string(), true);
+        let output = opts.execute(&mut backend).await?;
+        assert!(output.starts_with("Query is valid"));
+        assert!(output.contains("TableScan: t"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_validate_invalid_sql() {
+        let mut backend = test_backend();
+        for query in [
+            "SELEC id FROM t",
+            "SELECT missing FROM t",
+            "SELECT * FROM missing",
+        ] {
+            let opts = ValidateSqlOpts::new(query.to_string(), false);
+            let err = opts.execute(&mut backend).await.unwrap_err();
+            assert!(err.to_string().starts_with("Query is invalid"));
+        }
+    }
+}
@@ -0,0 +1,170 @@
+use std::sync::Arc;
+
+use arrow::{
+    array::{AsArray, Int64Array, RecordBatch, StringArray},
+    datatypes::{DataType, Field, Int64Type, Schema},
+};
+use clap::{ArgMatches, Parser};
+use reedline_repl_rs::Result;
+
+use crate::{Backend, CmdExecutor, ReplContext, ReplDisplay, ReplMsg};
+
+use super::ensure_table;
+
+const DEFAULT_MAX_DISTINCT: usize = 100;
+
+#[derive(Debug, Parser)]
+pub struct ValueCountsOpts {
+    #[arg(short, long, help = "the name of the dataset")]
+    name: String,
+    #[arg(
+        short,
+        long,
+        default_value_t = DEFAULT_MAX_DISTINCT,
+        help = "skip the columns with more distinct values than this"
+    )]
+    max_distinct: usize,
+}
+
+pub fn value_counts(args: ArgMatches, context: &mut ReplContext) -> Result<Option<String>> {
+    let name = args
+        .get_one::<String>("name")
+        .expect("expect name")
+        .to_string();
+    let max_distinct = args
+        .get_one::<usize>("max_distinct")
+        .copied()
+        .unwrap_or(DEFAULT_MAX_DISTINCT);
+    let (msg, rx) = ReplMsg::new(ValueCountsOpts::new(name, max_distinct));
+
+    Ok(context.send(msg, rx))
+}
+
+impl CmdExecutor for ValueCountsOpts {
+    async fn execute<T: Backend>(&self, backend: &mut T) -> anyhow::Result<String> {
+        ensure_table(backend, &self.name).await?;
+        let mut counts = Vec::new();
+        let mut skipped = Vec::new();
+        for column in backend.column_names(&self.name).await? {
+            // one more than the limit tells whether it is exceeded
+            let batch = backend
+                .value_counts(&self.name, &column, self.max_distinct + 1)
+                .await?;
+            if batch.num_rows() > self.max_distinct {
+                skipped.push(column);
+            } else {
+                counts.push((column, batch));
+            }
+        }
+        let output = stack_counts(&counts)?.display(backend.settings()).await?;
+        if skipped.is_empty() {
+            return Ok(output);
+        }
+        Ok(format!(
+            "{}\n(skipped, more than {} distinct values: {})",
+            output,
+            self.max_distinct,
+            skipped.join(", ")
+        ))
+    }
+}
+
+impl ValueCountsOpts {
+    pub fn new(name: String, max_distinct: usize) -> Self {
+        Self { name, max_distinct }
+    }
+}
+
+/// Stack the value counts of every column under a `column_name` column.
+fn stack_counts(counts: &[(String, RecordBatch)]) -> anyhow::Result<RecordBatch> {
+    let mut names = Vec::new();
+    let mut values = Vec::new();
+    let mut totals: Vec<i64> = Vec::new();
+    for (column, batch) in counts {
+        names.extend(std::iter::repeat_n(column.as_str(), batch.num_rows()));
+        values.extend(batch.column(0).as_string::<i32>().iter());
+        totals.extend(
+            batch
+                .column(1)
+                .as_primitive::<Int64Type>()
+                .values()
+                .iter()
+                .copied(),
+        );
+    }
+    let schema = Schema::new(vec![
+        Field::new("column_name", DataType::Utf8, false),
+        Field::new("value", DataType::Utf8, true),
+        Field::new("count", DataType::Int64, false),
+    ]);
+    Ok(RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(StringArray::from(names)),
+            Arc::new(StringArray::from(values)),
+            Arc::new(Int64Array::from(totals)),
+        ],
+    )?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::DatafusionBackend;
+    use crate::backend::tests::row_count;
+
+    #[tokio::test]
+    async fn test_value_counts() -> anyhow::Result<()> {
+        let mut backend = DatafusionBackend::new();
+        let schema = Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("color", DataType::Utf8, true),
+        ]);
+        let batch = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![
+                Arc::new(Int64Array::from_iter_values(0..5)),
+                Arc::new(StringArray::from(vec![
+                    Some("red"),
+                    Some("blue"),
+                    Some("red"),
+                    None,
+                    Some("red"),
+                ])),
+            ],
+        )?;
+        backend.register_batch("paint", batch)?;
+
+        let output = ValueCountsOpts::new("paint".to_string(), 3)
+            .execute(&mut backend)
+            .await?;
+        assert_eq!(row_count(&output), 3);
+        let rows = output
+            .lines()
+            .filter(|line| line.starts_with('|'))
+            .skip(1)
+            .map(|line| {
+                line.trim_matches('|')
+                    .split('|')
+                    .map(|cell| cell.trim().to_string())
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            rows,
+            [
+                ["color", "red", "3"],
+                ["color", "blue", "1"],
+                ["color", "NULL", "1"],
+            ]
+        );
+        assert!(output.ends_with("(skipped, more than 3 distinct values: id)"));
+
+        let output = ValueCountsOpts::new("paint".to_string(), 5)
+            .execute(&mut backend)
+            .await?;
+        assert_eq!(row_count(&output), 8);
+        assert!(!output.contains("skipped"));
+        Ok(())
+    }
+}
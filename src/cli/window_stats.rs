@@ -0,0 +1,248 @@
+use clap::{ArgMatches, Parser, ValueEnum};
+use reedline_repl_rs::Result;
+
+use crate::{Backend, CmdExecutor, ReplContext, ReplMsg};
+
+use super::ensure_table;
+
+/// The aggregate computed over each window of rows.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum WindowFunction {
+    #[default]
+    #[value(name = "rolling_mean")]
+    RollingMean,
+    #[value(name = "rolling_sum")]
+    RollingSum,
+    #[value(name = "rolling_min")]
+    RollingMin,
+    #[value(name = "rolling_max")]
+    RollingMax,
+}
+
+#[derive(Debug, Parser)]
+pub struct WindowStatsOpts {
+    #[arg(short, long, help = "the name of the dataset")]
+    name: String,
+    #[arg(short, long, help = "the column the rows are ordered by")]
+    time_col: String,
+    #[arg(short, long, help = "the column the statistic is computed on")]
+    value_col: String,
+    #[arg(short, long, help = "the number of rows in each window")]
+    window_size: usize,
+    #[arg(
+        short,
+        long,
+        value_enum,
+        default_value_t,
+        help = "the statistic to compute"
+    )]
+    function: WindowFunction,
+    #[arg(
+        short,
+        long,
+        help = "the name of the new dataset, default <name>_<function>"
+    )]
+    output: Option<String>,
+}
+
+pub fn window_stats(args: ArgMatches, context: &mut ReplContext) -> Result<Option<String>> {
+    let name = args
+        .get_one::<String>("name")
+        .expect("expect name")
+        .to_string();
+    let time_col = args
+        .get_one::<String>("time_col")
+        .expect("expect time_col")
+        .to_string();
+    let value_col = args
+        .get_one::<String>("value_col")
+        .expect("expect value_col")
+        .to_string();
+    let window_size = *args
+        .get_one::<usize>("window_size")
+        .expect("expect window_size");
+    let function = args
+        .get_one::<WindowFunction>("function")
+        .copied()
+        .unwrap_or_default();
+    let opts = WindowStatsOpts::new(name, time_col, value_col, window_size, function)
+        .with_output(args.get_one::<String>("output").cloned());
+    let (msg, rx) = ReplMsg::new(opts);
+
+    Ok(context.send(msg, rx))
+}
+
+impl CmdExecutor for WindowStatsOpts {
+    async fn execute<T: Backend>(&self, backend: &mut T) -> anyhow::Result<String> {
+        ensure_table(backend, &self.name).await?;
+        if self.window_size == 0 {
+            anyhow::bail!("The window size must be at least 1");
+        }
+        let output = self.output_name();
+        backend.create_table_from_sql(&output, &self.sql()).await?;
+        Ok(format!(
+            "Materialized {} of {} as dataset {}",
+            self.function.as_str(),
+            self.value_col,
+            output
+        ))
+    }
+}
+
+impl WindowStatsOpts {
+    pub fn new(
+        name: String,
+        time_col: String,
+        value_col: String,
+        window_size: usize,
+        function: WindowFunction,
+    ) -> Self {
+        Self {
+            name,
+            time_col,
+            value_col,
+            window_size,
+            function,
+            output: None,
+        }
+    }
+
+    pub fn with_output(mut self, output: Option<String>) -> Self {
+        self.output = output;
+        self
+    }
+
+    fn output_name(&self) -> String {
+        self.output
+            .clone()
+            .unwrap_or_else(|| format!("{}_{}", self.name, self.function.as_str()))
+    }
+
+    /// The statistic is cast to a double so every function yields the same type.
+    fn sql(&self) -> String {
+        format!(
+            "SELECT \"{1}\", \"{2}\", CAST({3}(\"{2}\") OVER (ORDER BY \"{1}\" \
+            ROWS BETWEEN {4} PRECEDING AND CURRENT ROW) AS DOUBLE) AS {5} \
+            FROM {0} ORDER BY \"{1}\"",
+            self.name,
+            self.time_col,
+            self.value_col,
+            self.function.aggregate(),
+            self.window_size - 1,
+            self.function.as_str()
+        )
+    }
+}
+
+impl WindowFunction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::RollingMean => "rolling_mean",
+            Self::RollingSum => "rolling_sum",
+            Self::RollingMin => "rolling_min",
+            Self::RollingMax => "rolling_max",
+        }
+    }
+
+    fn aggregate(&self) -> &'static str {
+        match self {
+            Self::RollingMean => "AVG",
+            Self::RollingSum => "SUM",
+            Self::RollingMin => "MIN",
+            Self::RollingMax => "MAX",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::ReplDisplay;
+    use crate::backend::DatafusionBackend;
+    use arrow::array::{AsArray, Int64Array, RecordBatch};
+    use arrow::datatypes::{DataType, Field, Float64Type, Schema};
+
+    async fn rolling(function: WindowFunction) -> anyhow::Result<Vec<f64>> {
+        let mut backend = DatafusionBackend::new();
+        let schema = Schema::new(vec![
+            Field::new("ts", DataType::Int64, false),
+            Field::new("value", DataType::Int64, false),
+        ]);
+        let batch = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![
+                Arc::new(Int64Array::from(vec![5, 4, 3, 2, 1])),
+                Arc::new(Int64Array::from(vec![5, 4, 3, 2, 1])),
+            ],
+        )?;
+        backend.register_batch("series", batch)?;
+
+        let opts = WindowStatsOpts::new(
+            "series".to_string(),
+            "ts".to_string(),
+            "value".to_string(),
+            3,
+            function,
+        );
+        let output = opts.execute(&mut backend).await?;
+        let name = format!("series_{}", function.as_str());
+        assert_eq!(
+            output,
+            format!(
+                "Materialized {} of value as dataset {}",
+                function.as_str(),
+                name
+            )
+        );
+
+        let sql = format!("SELECT * FROM {} ORDER BY ts", name);
+        let batches = backend.sql(&sql).await?.into_batches().await?;
+        Ok(batches
+            .iter()
+            .flat_map(|batch| {
+                batch
+                    .column(2)
+                    .as_primitive::<Float64Type>()
+                    .values()
+                    .to_vec()
+            })
+            .collect())
+    }
+
+    #[tokio::test]
+    async fn test_rolling_functions() -> anyhow::Result<()> {
+        assert_eq!(
+            rolling(WindowFunction::RollingMean).await?,
+            [1.0, 1.5, 2.0, 3.0, 4.0]
+        );
+        assert_eq!(
+            rolling(WindowFunction::RollingSum).await?,
+            [1.0, 3.0, 6.0, 9.0, 12.0]
+        );
+        assert_eq!(
+            rolling(WindowFunction::RollingMin).await?,
+            [1.0, 1.0, 1.0, 2.0, 3.0]
+        );
+        assert_eq!(
+            rolling(WindowFunction::RollingMax).await?,
+            [1.0, 2.0, 3.0, 4.0, 5.0]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_window_size_zero() {
+        let mut backend = crate::backend::tests::test_backend();
+        let opts = WindowStatsOpts::new(
+            "t".to_string(),
+            "id".to_string(),
+            "id".to_string(),
+            0,
+            WindowFunction::RollingSum,
+        );
+        let err = opts.execute(&mut backend).await.unwrap_err();
+        assert_eq!(err.to_string(), "The window size must be at least 1");
+    }
+}
@@ -0,0 +1,159 @@
+use std::{env, fs};
+
+use anyhow::{Result, bail};
+use clap::ValueEnum;
+use serde::Deserialize;
+
+use crate::BackendKind;
+
+const DEFAULT_HEAD_SIZE: usize = 10;
+const DEFAULT_NULL_DISPLAY: &str = "NULL";
+const DEFAULT_DESCRIBE_CACHE_TTL: u64 = 60;
+const HEAD_SIZE_ENV: &str = "TAOTIE_HEAD_SIZE";
+const DEFAULT_CACHE_SIZE: usize = 64;
+const DEFAULT_CHANNEL_CAPACITY: usize = 32;
+const CONFIG_FILE: &str = ".taotie.json";
+
+/// How command results are printed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum DisplayFormat {
+    #[default]
+    Table,
+    Json,
+    /// An Arrow IPC stream, for piping into Arrow-aware tools.
+    ArrowIpc,
+}
+
+/// Startup configuration of the REPL, loaded from `~/.taotie.json`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct TaotieConfig {
+    #[serde(skip)]
+    pub backend: BackendKind,
+    /// The memory limit (in bytes) of the query engine, unlimited if unset.
+    pub memory_limit: Option<usize>,
+    /// How many describe results are cached.
+    pub cache_size: usize,
+    /// How many commands could be queued for the backend.
+    pub channel_capacity: usize,
+    /// The initial `global_row_limit` setting.
+    pub global_row_limit: Option<usize>,
+}
+
+impl TaotieConfig {
+    /// Load the config file from the home directory, falling back to the
+    /// defaults if there is none.
+    pub fn load() -> Result<Self> {
+        let path = match dirs::home_dir() {
+            Some(home) => home.join(CONFIG_FILE),
+            None => return Ok(Self::default()),
+        };
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+}
+
+impl Default for TaotieConfig {
+    fn default() -> Self {
+        Self {
+            backend: BackendKind::default(),
+            memory_limit: None,
+            cache_size: DEFAULT_CACHE_SIZE,
+            channel_capacity: DEFAULT_CHANNEL_CAPACITY,
+            global_row_limit: None,
+        }
+    }
+}
+
+/// Session variables that could be changed at runtime with the `set` command.
+#[derive(Debug, Clone)]
+pub struct Settings {
+    pub default_head_size: usize,
+    pub null_display: String,
+    /// How long (in seconds) describe results are cached.
+    pub describe_cache_ttl: u64,
+    /// Whether the output could be colored with ANSI escape codes.
+    pub color: bool,
+    /// The maximum number of rows returned by a command, unlimited if unset.
+    pub global_row_limit: Option<usize>,
+}
+
+impl Settings {
+    pub fn new() -> Self {
+        let default_head_size = env::var(HEAD_SIZE_ENV)
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_HEAD_SIZE);
+        Self {
+            default_head_size,
+            null_display: DEFAULT_NULL_DISPLAY.to_string(),
+            describe_cache_ttl: DEFAULT_DESCRIBE_CACHE_TTL,
+            color: false,
+            global_row_limit: None,
+        }
+    }
+
+    pub fn set(&mut self, key: &str, value: &str) -> Result<()> {
+        match key {
+            "default_head_size" => self.default_head_size = value.parse()?,
+            "null_display" => self.null_display = value.to_string(),
+            "describe_cache_ttl" => self.describe_cache_ttl = value.parse()?,
+            "color" => self.color = value.parse()?,
+            "global_row_limit" => {
+                self.global_row_limit = match value {
+                    "none" => None,
+                    v => Some(v.parse()?),
+                }
+            }
+            _ => bail!("Unknown setting: {}", key),
+        }
+        Ok(())
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_default_head_size() -> Result<()> {
+        let mut settings = Settings::new();
+        settings.set("default_head_size", "25")?;
+        assert_eq!(settings.default_head_size, 25);
+        Ok(())
+    }
+
+    #[test]
+    fn test_set_null_display() -> Result<()> {
+        let mut settings = Settings::new();
+        assert_eq!(settings.null_display, "NULL");
+        settings.set("null_display", "<null>")?;
+        assert_eq!(settings.null_display, "<null>");
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_partial_config() -> Result<()> {
+        let config: TaotieConfig = serde_json::from_str(r#"{"memory_limit": 1024}"#)?;
+        assert_eq!(config.memory_limit, Some(1024));
+        assert_eq!(config.cache_size, DEFAULT_CACHE_SIZE);
+        assert_eq!(config.channel_capacity, DEFAULT_CHANNEL_CAPACITY);
+        Ok(())
+    }
+
+    #[test]
+    fn test_set_invalid_value() {
+        let mut settings = Settings::new();
+        assert!(settings.set("default_head_size", "abc").is_err());
+        assert!(settings.set("unknown", "1").is_err());
+    }
+}
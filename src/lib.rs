@@ -1,24 +1,139 @@
 mod backend;
 mod cli;
+mod config;
 
-use std::{ops::Deref, thread};
+use std::{
+    collections::HashMap,
+    ops::Deref,
+    sync::{Arc, Mutex},
+    thread,
+};
 
-use backend::DatafusionBackend;
+pub use backend::BackendKind;
+#[cfg(feature = "duckdb")]
+use backend::DuckdbBackend;
+use backend::{DatafusionBackend, ipc_stream_bytes, json_bytes};
 pub use cli::*;
-use crossbeam::channel::Sender;
+pub use config::{DisplayFormat, Settings, TaotieConfig};
+use crossbeam::channel::{Receiver, Sender};
 use enum_dispatch::enum_dispatch;
 use reedline_repl_rs::CallBackMap;
 
 use anyhow::Result;
+use arrow::{
+    array::RecordBatch,
+    datatypes::{DataType, Schema, SchemaRef},
+};
 use tokio::runtime::Runtime;
 
 trait Backend {
+    async fn cast_schema(
+        &mut self,
+        name: &str,
+        casts: &[(String, String)],
+        strict: bool,
+    ) -> Result<()>;
+    async fn column_correlation_with_target(
+        &self,
+        name: &str,
+        target: &str,
+        method: CorrelationMethod,
+    ) -> Result<RecordBatch>;
+    async fn column_names(&self, name: &str) -> Result<Vec<String>>;
+    async fn column_histogram(&self, name: &str, column: &str, bins: usize) -> Result<RecordBatch>;
+    async fn column_type(&self, name: &str, column: &str) -> Result<DataType>;
+    async fn column_types_summary(&self, name: &str) -> Result<RecordBatch>;
+    async fn compare_distributions(
+        &self,
+        name1: &str,
+        col1: &str,
+        name2: &str,
+        col2: &str,
+    ) -> Result<RecordBatch>;
+    async fn compute_hash(&self, name: &str, algorithm: HashAlgorithm) -> Result<String>;
     async fn connect(&mut self, opts: &ConnectOpts) -> Result<()>;
-    async fn describe(&self, name: &str) -> Result<impl ReplDisplay>;
-    async fn head(&self, name: &str, size: usize) -> Result<impl ReplDisplay>;
-    async fn list(&self) -> Result<impl ReplDisplay>;
+    async fn connection_info(&self, name: &str) -> Result<ConnectOpts>;
+    async fn create_table_from_sql(&mut self, name: &str, query: &str) -> Result<()>;
+    async fn create_table_from_ordered_sql(&mut self, name: &str, query: &str) -> Result<()>;
+    async fn correlate(&self, name: &str, method: CorrelationMethod) -> Result<RecordBatch>;
+    async fn cross_tabulate(&self, name: &str, row_col: &str, col_col: &str)
+    -> Result<RecordBatch>;
+    async fn count_nulls(&self, name: &str) -> Result<RecordBatch>;
+    async fn deduplicate(
+        &mut self,
+        name: &str,
+        key: &[String],
+        keep_last: bool,
+        output: Option<&str>,
+    ) -> Result<usize>;
+    async fn describe(&self, opts: &DescribeOpts) -> Result<impl ReplDisplay>;
+    async fn describe_sample(&self, name: &str, sample_size: usize) -> Result<impl ReplDisplay>;
+    async fn detect_schema_changes(&self, name: &str) -> Result<RecordBatch>;
+    async fn drop_all(&mut self) -> Result<usize>;
+    async fn drop_table(&mut self, name: &str) -> Result<()>;
+    async fn explain_analyze(&self, sql: &str) -> Result<String>;
+    async fn explain_dot(&self, sql: &str) -> Result<String>;
+    async fn frequency_table(
+        &self,
+        name: &str,
+        column: &str,
+        normalize: bool,
+    ) -> Result<impl ReplDisplay>;
+    async fn fuzzy_join(
+        &mut self,
+        left: &str,
+        right: &str,
+        left_col: &str,
+        right_col: &str,
+        threshold: f64,
+        output: &str,
+    ) -> Result<usize>;
+    async fn generate_series(&mut self, name: &str, batch: RecordBatch) -> Result<()>;
+    async fn head(&self, name: &str, size: usize, offset: usize) -> Result<impl ReplDisplay>;
+    async fn infer_schema(&self, opts: &InferSchemaOpts) -> Result<RecordBatch>;
+    async fn ingest_streaming(&mut self, name: &str, file: &str) -> Result<usize>;
+    async fn list(&self, with_schema: bool, catalog: Option<&str>) -> Result<impl ReplDisplay>;
+    async fn numeric_columns(&self, name: &str) -> Result<Vec<String>>;
+    async fn outliers(
+        &self,
+        name: &str,
+        column: &str,
+        method: OutlierMethod,
+    ) -> Result<impl ReplDisplay>;
+    async fn partition_info(&self, name: &str) -> Result<RecordBatch>;
+    async fn pivot_longer(
+        &self,
+        name: &str,
+        id_cols: &[String],
+        value_cols: &[String],
+        variable_col: &str,
+        value_col: &str,
+    ) -> Result<impl ReplDisplay>;
+    async fn preview_values(&self, name: &str, column: &str, limit: usize) -> Result<Vec<String>>;
+    async fn quantiles(&self, name: &str, column: &str, quantiles: &[f64]) -> Result<RecordBatch>;
+    async fn register_schema(&mut self, name: &str, schema: SchemaRef) -> Result<()>;
+    async fn row_at(&self, name: &str, index: usize) -> Result<Option<RecordBatch>>;
     async fn schema(&self, name: &str) -> Result<impl ReplDisplay>;
+    async fn source_query(&self, name: &str) -> Result<Option<String>>;
     async fn sql(&self, sql: &str) -> Result<impl ReplDisplay>;
+    async fn string_columns(&self, name: &str) -> Result<Vec<String>>;
+    async fn table_exists(&self, name: &str) -> Result<bool>;
+    async fn table_names(&self) -> Result<Vec<String>>;
+    async fn text_search(
+        &self,
+        name: &str,
+        columns: &[String],
+        query: &str,
+        case_sensitive: bool,
+        limit: usize,
+    ) -> Result<impl ReplDisplay>;
+    async fn transpose(&self, name: &str, row: usize) -> Result<Option<RecordBatch>>;
+    async fn use_catalog(&mut self, catalog: &str) -> Result<()>;
+    async fn vacuum(&mut self) -> Result<usize>;
+    async fn validate_sql(&self, sql: &str) -> Result<String>;
+    async fn value_counts(&self, name: &str, column: &str, top: usize) -> Result<RecordBatch>;
+    fn settings(&self) -> &Settings;
+    fn settings_mut(&mut self) -> &mut Settings;
 }
 
 #[enum_dispatch(ReplCommands)]
@@ -27,59 +142,286 @@ trait CmdExecutor {
 }
 
 trait ReplDisplay {
-    async fn display(self) -> anyhow::Result<String>;
+    async fn display(self, settings: &Settings) -> anyhow::Result<String>;
+    async fn into_batches(self) -> anyhow::Result<Vec<RecordBatch>>;
+
+    /// The batches along with their schema, which is known even when there
+    /// are no batches.
+    async fn into_schema_batches(self) -> anyhow::Result<(SchemaRef, Vec<RecordBatch>)>
+    where
+        Self: Sized,
+    {
+        let batches = self.into_batches().await?;
+        let schema = batches
+            .first()
+            .map(|batch| batch.schema())
+            .unwrap_or_else(|| Arc::new(Schema::empty()));
+        Ok((schema, batches))
+    }
+
+    /// The results in the given format, as raw bytes so binary formats
+    /// could be written out as they are.
+    async fn display_bytes(self, format: DisplayFormat, settings: &Settings) -> Result<Vec<u8>>
+    where
+        Self: Sized,
+    {
+        match format {
+            DisplayFormat::Table => Ok(self.display(settings).await?.into_bytes()),
+            DisplayFormat::Json => json_bytes(&self.into_batches().await?),
+            DisplayFormat::ArrowIpc => {
+                let (schema, batches) = self.into_schema_batches().await?;
+                ipc_stream_bytes(&schema, &batches)
+            }
+        }
+    }
 }
 
 pub struct ReplContext {
     sender: Sender<ReplMsg>,
+    control: Sender<ReplMsg>,
+    /// The files followed in the background, by the dataset they append to.
+    streams: Mutex<HashMap<String, FileTail>>,
+}
+
+/// Control messages are processed before any queued normal message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessagePriority {
+    Normal,
+    Control,
 }
 
 pub struct ReplMsg {
     pub command: ReplCommands,
-    pub tx: oneshot::Sender<String>,
+    pub priority: MessagePriority,
+    pub tx: oneshot::Sender<CmdResult>,
 }
 
+/// The output of a command, or the error it failed with.
+pub type CmdResult = std::result::Result<String, String>;
+
 pub type ReplCallBacks = CallBackMap<ReplContext, reedline_repl_rs::Error>;
 
+/// A handle for driving taotie from other Rust code, without the REPL.
+pub type Client = ReplContext;
+
 pub fn callbacks_map() -> ReplCallBacks {
     let mut callbacks = ReplCallBacks::new();
+    callbacks.insert("anti-join".to_string(), anti_join);
+    callbacks.insert("cast-schema".to_string(), cast_schema);
+    callbacks.insert("compare-distrib".to_string(), compare_distrib);
+    callbacks.insert("conn-info".to_string(), conn_info);
     callbacks.insert("connect".to_string(), connect);
+    callbacks.insert("correlate".to_string(), correlate);
+    callbacks.insert("correlate-target".to_string(), correlate_target);
+    callbacks.insert("create".to_string(), create);
+    callbacks.insert("crosstab".to_string(), crosstab);
+    callbacks.insert("date-trunc".to_string(), date_trunc);
+    callbacks.insert("deduplicate".to_string(), deduplicate);
     callbacks.insert("describe".to_string(), describe);
+    callbacks.insert("describe-sample".to_string(), describe_sample);
+    callbacks.insert("detect-drift".to_string(), detect_drift);
+    callbacks.insert("detect-pk".to_string(), detect_pk);
+    callbacks.insert("drop".to_string(), drop_dataset);
+    callbacks.insert("encode-cat".to_string(), encode_cat);
+    callbacks.insert("explain".to_string(), explain);
+    callbacks.insert("fill-nulls".to_string(), fill_nulls);
+    callbacks.insert("freq".to_string(), freq);
+    callbacks.insert("fuzzy-join".to_string(), fuzzy_join);
+    callbacks.insert("generate-series".to_string(), generate_series);
+    callbacks.insert("hash".to_string(), hash);
     callbacks.insert("head".to_string(), head);
+    callbacks.insert("histogram".to_string(), histogram);
+    callbacks.insert("import".to_string(), import);
+    callbacks.insert("infer-schema".to_string(), infer_schema);
     callbacks.insert("list".to_string(), list);
+    callbacks.insert("normalize".to_string(), normalize);
+    callbacks.insert("null-audit".to_string(), null_audit);
+    callbacks.insert("outliers".to_string(), outliers);
+    callbacks.insert("parquet-meta".to_string(), parquet_meta);
+    callbacks.insert("partition-info".to_string(), partition_info);
+    callbacks.insert("pivot-longer".to_string(), pivot_longer);
+    callbacks.insert("preview".to_string(), preview);
+    callbacks.insert("profile".to_string(), profile);
+    callbacks.insert("quantiles".to_string(), quantiles);
+    callbacks.insert("register-schema".to_string(), register_schema);
+    callbacks.insert("reset".to_string(), reset);
+    callbacks.insert("row".to_string(), row);
+    callbacks.insert("run-file".to_string(), run_file);
+    callbacks.insert("running-total".to_string(), running_total);
     callbacks.insert("sql".to_string(), sql);
     callbacks.insert("schema".to_string(), schema);
+    callbacks.insert("set".to_string(), set);
+    callbacks.insert("stream".to_string(), stream);
+    callbacks.insert("text-search".to_string(), text_search);
+    callbacks.insert("time-between".to_string(), time_between);
+    callbacks.insert("transpose".to_string(), transpose);
+    callbacks.insert("type-summary".to_string(), type_summary);
+    callbacks.insert("use".to_string(), use_catalog);
+    callbacks.insert("vacuum".to_string(), vacuum);
+    callbacks.insert("validate-sql".to_string(), validate_sql);
+    callbacks.insert("value-counts".to_string(), value_counts);
+    callbacks.insert("window-stats".to_string(), window_stats);
     callbacks
 }
 
 impl ReplContext {
     pub fn new() -> Self {
-        let (sender, receiver) = crossbeam::channel::unbounded::<ReplMsg>();
-        let mut backend = DatafusionBackend::new();
+        Self::new_with_config(TaotieConfig::default())
+    }
+
+    pub fn with_backend(kind: BackendKind) -> Self {
+        Self::new_with_config(TaotieConfig {
+            backend: kind,
+            ..Default::default()
+        })
+    }
+
+    pub fn new_with_config(config: TaotieConfig) -> Self {
+        let (sender, receiver) = crossbeam::channel::bounded::<ReplMsg>(config.channel_capacity);
+        let (control, control_receiver) = crossbeam::channel::unbounded::<ReplMsg>();
         let rt = Runtime::new().expect("Failed to create runtime");
         thread::Builder::new()
             .name("ReplBackend".to_string())
-            .spawn(move || {
-                while let Ok(msg) = receiver.recv() {
-                    if let Err(e) = rt.block_on(async {
-                        let result = msg.command.execute(&mut backend).await?;
-                        msg.tx.send(result)?;
-                        Ok::<_, anyhow::Error>(())
-                    }) {
-                        eprintln!("Failed to process command: {}", e);
-                    }
+            .spawn(move || match config.backend {
+                BackendKind::Datafusion => {
+                    let backend = DatafusionBackend::with_config(&config)
+                        .expect("Failed to create datafusion backend");
+                    run_backend(backend, control_receiver, receiver, rt)
+                }
+                #[cfg(feature = "duckdb")]
+                BackendKind::Duckdb => {
+                    let backend =
+                        DuckdbBackend::with_config(&config).expect("Failed to open duckdb");
+                    run_backend(backend, control_receiver, receiver, rt)
                 }
             })
             .unwrap();
-        Self { sender }
+        Self {
+            sender,
+            control,
+            streams: Mutex::new(HashMap::new()),
+        }
     }
 
-    pub fn send(&self, command: ReplMsg, rx: oneshot::Receiver<String>) -> Option<String> {
-        if let Err(e) = self.sender.send(command) {
+    pub fn send(&self, command: ReplMsg, rx: oneshot::Receiver<CmdResult>) -> Option<String> {
+        if let Err(e) = self.sender_for(command.priority).send(command) {
             eprintln!("Failed to send command: {} to backend", e);
             std::process::exit(1);
         }
-        rx.recv().ok()
+        match rx.recv() {
+            Ok(Ok(output)) => Some(output),
+            Ok(Err(e)) => {
+                eprintln!("Failed to process command: {}", e);
+                None
+            }
+            Err(_) => None,
+        }
+    }
+
+    /// Run a command and wait for its output.
+    ///
+    /// ```
+    /// use taotie::{Client, SqlOpts};
+    ///
+    /// let client = Client::new();
+    /// let output = client.execute(SqlOpts::new("SELECT 1 AS one".to_string())).unwrap();
+    /// assert!(output.contains("one"));
+    /// ```
+    pub fn execute(&self, command: impl Into<ReplCommands>) -> Result<String> {
+        let (msg, rx) = ReplMsg::new(command);
+        self.sender_for(msg.priority)
+            .send(msg)
+            .map_err(|e| anyhow::anyhow!("Failed to send command to backend: {}", e))?;
+        rx.recv()
+            .map_err(|_| anyhow::anyhow!("The backend failed to process the command"))?
+            .map_err(anyhow::Error::msg)
+    }
+
+    /// Register a dataset.
+    ///
+    /// ```
+    /// use taotie::{Client, ConnectOpts, DatasetConn};
+    ///
+    /// let client = Client::new();
+    /// let conn = DatasetConn::Parquet("assets/sample.parquet".to_string());
+    /// let output = client
+    ///     .connect(ConnectOpts::new(conn, None, "sample".to_string()))
+    ///     .unwrap();
+    /// assert_eq!(output, "Connected to dataset sample");
+    /// ```
+    pub fn connect(&self, opts: ConnectOpts) -> Result<String> {
+        self.execute(opts)
+    }
+
+    /// Run a SQL query and return the formatted result.
+    pub fn sql(&self, query: &str) -> Result<String> {
+        self.execute(SqlOpts::new(query.to_string()))
+    }
+
+    /// Show the first `n` rows of a dataset.
+    pub fn head(&self, name: &str, n: usize) -> Result<String> {
+        self.execute(HeadOpts::new(name.to_string(), Some(n), 0))
+    }
+
+    /// Show the statistics of every column of a dataset.
+    pub fn describe(&self, name: &str) -> Result<String> {
+        self.execute(DescribeOpts::new(name.to_string()))
+    }
+
+    /// Show the schema of a dataset.
+    pub fn schema(&self, name: &str) -> Result<String> {
+        self.execute(SchemaOpts::new(Some(name.to_string()), false))
+    }
+
+    /// List the registered datasets.
+    pub fn list(&self) -> Result<String> {
+        self.execute(ListOpts::new(false))
+    }
+
+    /// Append the rows of a csv file to a dataset, then keep appending the
+    /// rows written to it later on in the background. Streaming into the
+    /// same dataset again replaces the previous stream.
+    pub fn stream(&self, opts: IngestStreamingOpts) -> Result<String> {
+        let output = self.execute(opts.clone())?;
+        let name = opts.name().to_string();
+        let tail = FileTail::spawn(opts, self.sender.clone())?;
+        self.streams.lock().unwrap().insert(name, tail);
+        Ok(output)
+    }
+
+    fn sender_for(&self, priority: MessagePriority) -> &Sender<ReplMsg> {
+        match priority {
+            MessagePriority::Normal => &self.sender,
+            MessagePriority::Control => &self.control,
+        }
+    }
+}
+
+fn run_backend<T: Backend>(
+    mut backend: T,
+    control: Receiver<ReplMsg>,
+    receiver: Receiver<ReplMsg>,
+    rt: Runtime,
+) {
+    while let Some(msg) = next_msg(&control, &receiver) {
+        let result = rt
+            .block_on(msg.command.execute(&mut backend))
+            .map_err(|e| e.to_string());
+        if let Err(e) = msg.tx.send(result) {
+            eprintln!("Failed to return the command result: {}", e);
+        }
+    }
+}
+
+/// Take the next message, preferring any pending control message.
+fn next_msg(control: &Receiver<ReplMsg>, receiver: &Receiver<ReplMsg>) -> Option<ReplMsg> {
+    if let Ok(msg) = control.try_recv() {
+        return Some(msg);
+    }
+    crossbeam::channel::select! {
+        // a closed control channel still leaves the queued messages to run
+        recv(control) -> msg => msg.or_else(|_| receiver.recv()).ok(),
+        recv(receiver) -> msg => msg.ok(),
     }
 }
 
@@ -98,12 +440,108 @@ impl Deref for ReplContext {
 }
 
 impl ReplMsg {
-    pub fn new(command: impl Into<ReplCommands>) -> (Self, oneshot::Receiver<String>) {
+    pub fn new(command: impl Into<ReplCommands>) -> (Self, oneshot::Receiver<CmdResult>) {
         let (tx, rx) = oneshot::channel();
+        let command = command.into();
         let msg = Self {
-            command: command.into(),
+            priority: command.priority(),
+            command,
             tx,
         };
         (msg, rx)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_with_config() {
+        let config = TaotieConfig {
+            channel_capacity: 4,
+            memory_limit: Some(64 * 1024 * 1024),
+            ..Default::default()
+        };
+        let context = ReplContext::new_with_config(config);
+        assert_eq!(context.capacity(), Some(4));
+
+        let (msg, rx) = ReplMsg::new(SqlOpts::new("SELECT 1 AS answer".to_string()));
+        let output = context.send(msg, rx).unwrap();
+        assert!(output.contains("answer"));
+    }
+
+    #[test]
+    fn test_client_api() -> Result<()> {
+        let client = Client::new();
+        let conn = DatasetConn::Parquet("assets/sample.parquet".to_string());
+        let output = client.connect(ConnectOpts::new(conn, None, "sample".to_string()))?;
+        assert_eq!(output, "Connected to dataset sample");
+
+        assert!(client.list()?.contains("sample"));
+        assert!(client.schema("sample")?.contains("email"));
+        assert!(client.describe("sample")?.contains("null_total"));
+        assert_eq!(backend::tests::row_count(&client.head("sample", 3)?), 3);
+
+        let output = client.sql("SELECT 1 AS one")?;
+        assert_eq!(backend::tests::row_cells(&output, "1"), ["1"]);
+        let err = client.sql("SELECT * FROM missing").unwrap_err();
+        assert!(err.to_string().contains("missing"));
+        assert!(client.head("missing", 3).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_control_message_jumps_queue() {
+        let (sender, receiver) = crossbeam::channel::unbounded::<ReplMsg>();
+        let (control, control_receiver) = crossbeam::channel::unbounded::<ReplMsg>();
+
+        // queue both messages before the backend starts, so the head is
+        // still waiting when the set arrives
+        let (head, head_rx) = ReplMsg::new(HeadOpts::new("t".to_string(), None, 0));
+        let (set, set_rx) = ReplMsg::new(SetOpts::new(
+            "default_head_size".to_string(),
+            "3".to_string(),
+        ));
+        assert_eq!(head.priority, MessagePriority::Normal);
+        assert_eq!(set.priority, MessagePriority::Control);
+        sender.send(head).unwrap();
+        control.send(set).unwrap();
+        drop((sender, control));
+
+        let backend = backend::tests::test_backend();
+        let rt = Runtime::new().unwrap();
+        let handle = thread::spawn(move || run_backend(backend, control_receiver, receiver, rt));
+        assert_eq!(
+            set_rx.recv().unwrap().unwrap(),
+            "Set default_head_size to 3"
+        );
+        let output = head_rx.recv().unwrap().unwrap();
+        assert_eq!(backend::tests::row_count(&output), 3);
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn test_drop_waits_for_queued_queries() {
+        let (sender, receiver) = crossbeam::channel::unbounded::<ReplMsg>();
+        let (control, control_receiver) = crossbeam::channel::unbounded::<ReplMsg>();
+
+        let (head, head_rx) = ReplMsg::new(HeadOpts::new("t".to_string(), Some(3), 0));
+        let (drop_msg, drop_rx) = ReplMsg::new(DropOpts::new("t".to_string()));
+        sender.send(head).unwrap();
+        // routed the way ReplContext routes it
+        match drop_msg.priority {
+            MessagePriority::Normal => sender.send(drop_msg).unwrap(),
+            MessagePriority::Control => control.send(drop_msg).unwrap(),
+        }
+        drop((sender, control));
+
+        let backend = backend::tests::test_backend();
+        let rt = Runtime::new().unwrap();
+        let handle = thread::spawn(move || run_backend(backend, control_receiver, receiver, rt));
+        let output = head_rx.recv().unwrap().unwrap();
+        assert_eq!(backend::tests::row_count(&output), 3);
+        assert_eq!(drop_rx.recv().unwrap().unwrap(), "Dropped dataset t");
+        handle.join().unwrap();
+    }
+}
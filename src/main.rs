@@ -1,21 +1,62 @@
-use reedline_repl_rs::{Repl, Result};
-use taotie::{ReplCommand, ReplContext, callbacks_map};
+use clap::Parser;
+use reedline_repl_rs::{Error, Repl, Result};
+use taotie::{BackendKind, ReplCommand, ReplContext, TaotieConfig, callbacks_map};
 
 const HISTORY_SIZE: usize = 1024;
 
+#[derive(Debug, Parser)]
+#[command(name = "taotie", about = "explore dataset")]
+struct Args {
+    #[arg(long, value_enum, default_value_t, help = "the query engine to use")]
+    backend: BackendKind,
+    #[arg(long, help = "the maximum number of rows returned by any command")]
+    global_row_limit: Option<usize>,
+    #[arg(
+        short,
+        long,
+        help = "run the command and exit instead of starting the REPL, may be repeated"
+    )]
+    execute: Vec<String>,
+}
+
 fn main() -> Result<()> {
+    let args = Args::parse();
     let callbacks = callbacks_map();
 
-    let ctx = ReplContext::new();
+    let mut config = TaotieConfig::load().unwrap_or_else(|e| {
+        eprintln!("Failed to load config, using the defaults: {}", e);
+        TaotieConfig::default()
+    });
+    config.backend = args.backend;
+    if args.global_row_limit.is_some() {
+        config.global_row_limit = args.global_row_limit;
+    }
+    let ctx = ReplContext::new_with_config(config);
+
+    let repl = Repl::new(ctx).with_derived::<ReplCommand>(callbacks);
+    if !args.execute.is_empty() {
+        // the REPL never reads stdin here, so it is left for `connect -`
+        return execute(repl, &args.execute);
+    }
 
     let history_file = dirs::home_dir()
         .expect("expect home dir")
         .join(".taotie_history");
 
-    let mut repl = Repl::new(ctx)
+    let mut repl = repl
         .with_history(history_file, HISTORY_SIZE)
-        .with_banner("Welcome to Taotie, your dataset exploration REPL!")
-        .with_derived::<ReplCommand>(callbacks);
+        .with_banner("Welcome to Taotie, your dataset exploration REPL!");
 
     repl.run()
 }
+
+fn execute(mut repl: Repl<ReplContext, Error>, commands: &[String]) -> Result<()> {
+    for command in commands {
+        let Some(argv) = shlex::split(command) else {
+            eprintln!("Failed to parse command: {}", command);
+            std::process::exit(1);
+        };
+        repl.process_argv(argv)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,40 @@
+use std::{
+    io::Write,
+    process::{Command, Stdio},
+};
+
+#[test]
+fn test_execute_connects_piped_stdin() -> anyhow::Result<()> {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_taotie"))
+        .args(["--execute", "connect - -n piped", "-e", "head -n piped"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    child
+        .stdin
+        .take()
+        .expect("expect stdin")
+        .write_all(b"id,name\n1,a\n2,b\n")?;
+    let output = child.wait_with_output()?;
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout)?;
+    assert!(stdout.contains("Connected to dataset piped"));
+    let rows = stdout
+        .lines()
+        .filter(|line| line.starts_with("| 1") || line.starts_with("| 2"))
+        .count();
+    assert_eq!(rows, 2);
+    Ok(())
+}
+
+#[test]
+fn test_execute_rejects_unbalanced_quotes() -> anyhow::Result<()> {
+    let output = Command::new(env!("CARGO_BIN_EXE_taotie"))
+        .args(["--execute", "sql 'SELECT 1"])
+        .stdin(Stdio::null())
+        .output()?;
+    assert!(!output.status.success());
+    Ok(())
+}